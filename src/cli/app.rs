@@ -0,0 +1,1354 @@
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::commands::changelog::ChangelogOptions;
+use crate::commands::deinit::DeinitOptions;
+use crate::commands::doctor::DoctorOptions;
+use crate::commands::export::{DataFormat, ExportRange, RangeBound};
+use crate::commands::import::ImportMode;
+use crate::commands::import_git::GitGrouping;
+use crate::commands::init::{InitMode, InitOptions};
+use crate::commands::pin::PinOptions;
+use crate::commands::self_update::SelfUpdateOptions;
+use crate::commands::sync_directives::SyncOptions;
+use crate::commands::vendor::VendorOptions;
+use crate::commands::wrappers::WrapperMode;
+
+use crate::cli;
+use crate::cli::completions::{Shell, COMPLETE_ENTRIES, ENTRY_VALUE};
+
+const LONG_ABOUT: &str = "\
+Engram provides AI coding agents with persistent, tamper-evident memory across sessions.
+
+It implements a hash-linked changelog (similar to blockchain) that allows agents to track 
+their work history and resume context. Each entry cryptographically links to the previous 
+state, creating a verifiable chain of work history.
+
+The system lives in the repository (.engram/ directory) and requires minimal context 
+window to understand current state - agents read only the summary index and current 
+draft, not full history.";
+
+const AFTER_HELP: &str = "\
+EXAMPLES:
+    Initialize Engram in a new project:
+        $ engram init
+
+    Initialize with Warp AI directive:
+        $ engram init --warp
+
+    Initialize with Claude AI directive:
+        $ engram init --claude
+
+    Initialize with all AI agent directives:
+        $ engram init --all
+
+    Commit your work after updating .engram/draft.md:
+        $ engram commit
+
+    Verify the integrity of the hash chain:
+        $ engram verify
+
+    Check current Engram status:
+        $ engram status
+
+    Check the installation and repair what can be repaired:
+        $ engram doctor --fix
+
+    Get machine-readable output (errors carry a stable code, e.g. E_DRAFT_EMPTY):
+        $ engram status --json
+
+WORKFLOW:
+    1. Run 'engram init' to set up Engram in your project
+    2. Before starting work, check .engram/draft.md for unfinished work
+    3. After completing work, update .engram/draft.md with your report
+    4. Run 'engram commit' to finalize the entry
+    5. Use 'engram verify' to validate history integrity at any time
+
+SECURITY:
+    ⛔ NEVER log sensitive data (passwords, API keys, secrets, tokens, 
+    credentials, PII) in any Engram documentation. Reference secrets by 
+    name only, never include actual values.
+
+Learn more: https://github.com/lkubicek1/engram";
+
+#[derive(Parser)]
+#[command(name = "engram")]
+#[command(version)]
+#[command(about = "Persistent, tamper-evident memory for AI coding agents")]
+#[command(long_about = LONG_ABOUT)]
+#[command(after_help = AFTER_HELP)]
+#[command(arg_required_else_help = true)]
+struct Cli {
+    /// Print results and errors as JSON on stdout
+    #[arg(
+        long,
+        global = true,
+        help = "Print results and errors as JSON on stdout"
+    )]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Initialize Engram in the current directory
+    #[command(
+        long_about = "\
+Initialize Engram in the current directory.
+
+Creates the .engram/ directory structure with:
+  • .engram/AGENTS.md     - Protocol instructions for AI agents
+  • .engram/draft.md      - Agent workspace (mutable, reset after commit)
+  • .engram/worklog/      - Hash-linked entry storage
+  • .engram/worklog/SUMMARY.md - Quick-reference index
+
+Optionally creates root-level AI agent instruction files with the Engram 
+protocol directive. Use flags to specify which files to create/update.
+
+Without flags, init adds the directive to the instruction files of the agent 
+tools it detects (WARP.md, CLAUDE.md, .junie/, .cursor/, 
+.github/copilot-instructions.md, .windsurfrules, .clinerules, GEMINI.md, 
+.aider.conf.yml) and creates AGENTS.md when it finds none.
+
+init refuses an existing .engram/ (E_ALREADY_INITIALIZED) unless:
+  • --repair - creates whatever is missing (directories, templates, hygiene 
+               files, VERSION, wrappers, SUMMARY.md rebuilt from the entries) 
+               and leaves existing files alone
+  • --force  - also resets AGENTS.md, .gitignore, .gitattributes, the 
+               wrappers, SUMMARY.md and directive blocks to this release
+
+Neither touches worklog entries or a draft with content; every action is 
+reported.
+
+The ./engram and engram.cmd wrappers fetch the pinned release on first run. 
+--wrapper-mode picks where from:
+  • github - GitHub releases, checked against the release's checksums.txt 
+             (default)
+  • local  - only the binary `engram vendor` copied into .engram/bin; never 
+             downloads
+  • mirror - ENGRAM_MIRROR (a URL or file:// path laid out like the GitHub 
+             releases, defaulting to --mirror), checked against SHA-256s 
+             pinned in the wrappers from --checksums or the local mirror's 
+             v<version>/checksums.txt
+  • path   - the engram on PATH (or ENGRAM_BIN); never downloads
+
+--repair and --force keep the installed mode unless one is given.",
+        after_help = "\
+EXAMPLES:
+    Basic initialization:
+        $ engram init
+
+    Initialize with Warp AI support:
+        $ engram init --warp
+
+    Initialize with Claude AI support:
+        $ engram init --claude
+
+    Initialize with Junie AI support:
+        $ engram init --junie
+
+    Initialize with Cursor and GitHub Copilot support:
+        $ engram init --cursor --copilot
+
+    Initialize with all AI agent directives:
+        $ engram init --all
+
+    Finish an installation that was interrupted or partly deleted:
+        $ engram init --repair
+
+    Reset templates and wrappers, keeping the worklog and draft:
+        $ engram init --force
+
+    Air-gapped CI with an internal release mirror:
+        $ engram init --wrapper-mode mirror --mirror file:///srv/engram-releases"
+    )]
+    Init {
+        /// Create/append WARP.md with Engram directive for Warp AI
+        #[arg(
+            long,
+            help = "Create or append to WARP.md with Engram protocol directive"
+        )]
+        warp: bool,
+
+        /// Create/append CLAUDE.md with Engram directive for Claude
+        #[arg(
+            long,
+            help = "Create or append to CLAUDE.md with Engram protocol directive"
+        )]
+        claude: bool,
+
+        /// Create/append .junie/guidelines.md with Engram directive for Junie AI
+        #[arg(
+            long,
+            help = "Create or append to .junie/guidelines.md with Engram protocol directive"
+        )]
+        junie: bool,
+
+        /// Create/append AGENTS.md with Engram directive in project root
+        #[arg(
+            long,
+            help = "Create or append to AGENTS.md with Engram protocol directive"
+        )]
+        agents: bool,
+
+        /// Create/append .cursor/rules/engram.mdc with Engram directive for Cursor rule
+        #[arg(
+            long,
+            help = "Create or update .cursor/rules/engram.mdc with the Engram protocol rule"
+        )]
+        cursor: bool,
+
+        /// Create/append .github/copilot-instructions.md with Engram directive for GitHub Copilot
+        #[arg(
+            long,
+            help = "Create or append to .github/copilot-instructions.md with Engram protocol directive"
+        )]
+        copilot: bool,
+
+        /// Create/append .windsurfrules with Engram directive for Windsurf
+        #[arg(
+            long,
+            help = "Create or append to .windsurfrules with Engram protocol directive"
+        )]
+        windsurf: bool,
+
+        /// Create/append .clinerules with Engram directive for Cline
+        #[arg(
+            long,
+            help = "Create or append to .clinerules (or .clinerules/engram.md) with Engram protocol directive"
+        )]
+        cline: bool,
+
+        /// Create/append GEMINI.md with Engram directive for Gemini CLI
+        #[arg(
+            long,
+            help = "Create or append to GEMINI.md with Engram protocol directive"
+        )]
+        gemini: bool,
+
+        /// Create/append CONVENTIONS.md with Engram directive for Aider
+        #[arg(
+            long,
+            help = "Create or append to CONVENTIONS.md and add it to the .aider.conf.yml read list"
+        )]
+        aider: bool,
+
+        /// Apply all directive options
+        #[arg(long, help = "Create/append every supported AI agent directive file")]
+        all: bool,
+
+        /// Complete an existing, partially initialized .engram/
+        #[arg(
+            long,
+            conflicts_with = "force",
+            help = "Create missing files in an existing .engram/ without touching entries or a non-empty draft"
+        )]
+        repair: bool,
+
+        /// Reset generated files to the current templates, keeping history
+        #[arg(
+            long,
+            help = "Like --repair, but also reset AGENTS.md, wrappers, SUMMARY.md and directive blocks to the current templates"
+        )]
+        force: bool,
+
+        /// Where the wrapper scripts get the Engram binary from
+        #[arg(long, value_enum, value_name = "MODE")]
+        wrapper_mode: Option<WrapperModeArg>,
+
+        /// Default ENGRAM_MIRROR for --wrapper-mode mirror
+        #[arg(long, value_name = "URL", requires = "wrapper_mode")]
+        mirror: Option<String>,
+
+        /// Release checksums.txt to pin in mirror-mode wrappers
+        #[arg(long, value_name = "FILE", requires = "wrapper_mode")]
+        checksums: Option<PathBuf>,
+    },
+
+    /// Commit the current draft to the hash-linked history
+    #[command(
+        long_about = "\
+Commit the current draft to the hash-linked history.
+
+Reads .engram/draft.md, extracts the summary and body content, then creates 
+a new entry in .engram/worklog/ with:
+  • The summary from the <summary> tag
+  • A SHA256 hash link to the previous entry
+  • An RFC 3339 UTC timestamp (pinned by SOURCE_DATE_EPOCH when set)
+  • The full body content
+
+The entry filename includes a sequence number and content hash (e.g., 002_e5f6a7b8.md).
+After committing, the draft is reset to an empty template.
+
+Requirements:
+  • The <summary> tag must contain non-empty text
+  • The draft body must have content beyond template comments
+  • The clock must not be behind the latest entry's date",
+        after_help = "\
+EXAMPLES:
+    After updating .engram/draft.md with your work report:
+        $ engram commit
+
+OUTPUT:
+    Committed: 002_e5f6a7b8.md
+    Summary: Added JWT authentication to the login endpoint
+    Previous: a1b2c3d4..."
+    )]
+    Commit,
+
+    /// Edit, show, clear or validate the draft without rewriting the file
+    #[command(
+        subcommand_required = true,
+        arg_required_else_help = true,
+        long_about = "\
+Edit .engram/draft.md one piece at a time instead of rewriting the whole file.
+
+Edits keep the rest of the template intact: the <summary> tag, front matter, 
+Tags:/Type:/Refs: lines, other sections and their guidance comments. A deleted 
+draft.md is recreated from the template before the edit.
+
+Subcommands:
+  • set-summary  - Replace the text of the <summary> tag
+  • append       - Add text to the end of a ## section
+  • show         - Print the draft
+  • clear        - Reset the draft to the template
+  • validate     - Check the draft exactly as `engram commit` would
+
+validate reports problems with the same error codes and exit status as 
+commit (e.g. E_DRAFT_SUMMARY_EMPTY, exit 4) and writes nothing.",
+        after_help = "\
+EXAMPLES:
+    Write a report step by step, then commit:
+        $ engram draft set-summary \"Add JWT authentication to the login endpoint\"
+        $ engram draft append --section Intent \"Sessions expired on every deploy.\"
+        $ engram draft append --section Changes \"- src/auth.rs: issue_token()\"
+        $ engram draft append --section Verification \"cargo test auth\"
+        $ engram draft validate && engram commit"
+    )]
+    Draft {
+        #[command(subcommand)]
+        action: DraftAction,
+    },
+
+    /// Verify the integrity of the hash chain
+    #[command(
+        long_about = "\
+Verify the integrity of the hash chain.
+
+Checks that each entry in .engram/worklog/ correctly links to the previous 
+entry via SHA256 hash. Also verifies that each filename's hash matches the 
+file's actual content hash, that sequence numbers run from 1 without gaps 
+or duplicates, and that nothing else lives in worklog/ besides SUMMARY.md.
+
+This detects:
+  • Tampered or modified historical entries
+  • Corrupted files
+  • Missing entries in the chain (sequence gaps)
+  • Two entries with the same sequence number
+  • Renamed or stray files (e.g. 000003_deadbeef.md.bak)
+  • Incorrect hash values
+  • Dates that go backwards or lie in the future (beyond 5 minutes of skew)
+  • Dates that are not strict RFC 3339",
+        after_help = "\
+EXAMPLES:
+    Verify the chain integrity:
+        $ engram verify
+
+OUTPUT (success):
+    ✓ Chain verified: 47 entries
+      First: 001_a1b2c3d4.md (2025-01-15)
+      Latest: 047_f9e8d7c6.md (2025-06-12)
+
+OUTPUT (failure):
+    ✗ Chain broken at entry 023_bad12345.md
+    Expected Previous: 8a7b6c5d4e3f2a1b...
+    Found Previous:    0000000000000000...
+
+    Error code: E_CHAIN_BROKEN"
+    )]
+    Verify,
+
+    /// Display current Engram state and status
+    #[command(
+        long_about = "\
+Display current Engram state and status.
+
+Shows a summary of the current Engram state including:
+  • Number of entries in worklog
+  • Latest entry filename, date, and summary
+  • Draft status (empty or has uncommitted content)
+  • Chain verification status",
+        after_help = "\
+EXAMPLES:
+    Check current status:
+        $ engram status
+
+OUTPUT:
+    Engram Status
+    ─────────────
+    History: 47 entries
+    Latest:  047_f9e8d7c6.md (2025-06-12T14:32:07Z)
+             \"Fixed token expiration validation bug\"
+
+    Draft:   Has content (uncommitted work)
+             Summary: \"Added rate limiting to API endpoints\"
+
+    Chain:   ✓ Verified"
+    )]
+    Status,
+
+    /// Check the Engram installation and suggest or apply fixes
+    #[command(
+        long_about = "\
+Diagnose problems with the Engram installation.
+
+Checks the whole installation and reports each problem with a suggested fix:
+  • .engram/ layout (worklog/, AGENTS.md, SUMMARY.md)
+  • Missing .engram/.gitignore or .engram/.gitattributes
+  • Stray or misnamed files in .engram/worklog/
+  • Entries that differ from their hash only by CRLF, BOM or trailing whitespace
+  • Hash chain integrity
+  • SUMMARY.md rows that disagree with the entries on disk
+  • A draft that has lost its <summary> tag
+  • Wrapper scripts pinned to a different version than this binary
+  • Directive presence in WARP.md, CLAUDE.md, .junie/guidelines.md, AGENTS.md
+  • The git eol attribute actually in effect under .engram/
+
+Use --fix to apply every automatic fix. Fixes never modify entry content; 
+problems that need judgement are reported with manual instructions.",
+        after_help = "\
+EXAMPLES:
+    Report problems without changing anything:
+        $ engram doctor
+
+    Apply all automatic fixes:
+        $ engram doctor --fix
+
+    Only restore LF line endings in affected entries:
+        $ engram doctor --fix-eol"
+    )]
+    Doctor {
+        /// Apply every automatic fix
+        #[arg(long, help = "Apply every automatic fix")]
+        fix: bool,
+
+        /// Rewrite entries whose only difference is CRLF line endings
+        #[arg(
+            long,
+            help = "Convert entries back to LF when CRLF line endings are the only difference"
+        )]
+        fix_eol: bool,
+    },
+
+    /// Run a Model Context Protocol server on stdin/stdout
+    #[command(
+        long_about = "\
+Run a Model Context Protocol (MCP) server on stdin/stdout.
+
+Speaks newline-delimited JSON-RPC 2.0 so agents can follow the Engram 
+protocol without parsing CLI output. The server works on the repository 
+in the current directory.
+
+Tools:
+  • read_draft, write_draft - Read or replace .engram/draft.md
+  • commit                  - Commit the draft as a new entry
+  • verify, status          - Chain verification and repository state
+  • log, show, search       - Browse and search committed entries
+
+Resources:
+  • engram://summary              - .engram/worklog/SUMMARY.md
+  • engram://entries/<filename>   - One worklog entry
+
+Tool failures are returned with the same error codes as the CLI (e.g. E_DRAFT_EMPTY).",
+        after_help = "\
+EXAMPLES:
+    Register with an MCP client (command and working directory):
+        command: engram
+        args:    [\"mcp\"]
+        cwd:     /path/to/project"
+    )]
+    Mcp,
+
+    /// Browse the worklog in a web browser (read-only, localhost only)
+    #[command(
+        long_about = "\
+Serve a read-only web view of the worklog on localhost.
+
+Pages:
+  • /                 - Timeline of entries with a live verify badge
+  • /entries/<seq>    - One entry rendered from markdown, with hash and Previous link
+
+JSON API:
+  • /api/entries        - All entries
+  • /api/entries/<seq>  - One entry
+  • /api/verify         - Current verification result
+
+The server binds to 127.0.0.1 only and accepts GET and HEAD requests; it never 
+modifies the repository.",
+        after_help = "\
+EXAMPLES:
+    Serve on the default port:
+        $ engram serve
+
+    Serve on a specific port:
+        $ engram serve --port 9000"
+    )]
+    Serve {
+        /// Port to listen on (0 picks a free port)
+        #[arg(
+            long,
+            default_value_t = 8765,
+            help = "Port to listen on (0 picks a free port)"
+        )]
+        port: u16,
+    },
+
+    /// Export the worklog as data (JSON Lines, JSON, CSV) or a static site
+    #[command(
+        args_conflicts_with_subcommands = true,
+        long_about = "\
+Export worklog entries as data, in chain order, to stdout.
+
+Each record has: sequence, filename, hash (full SHA-256), previous, date, 
+summary, body, sections (the body split at its ## headings), headers (extra 
+entry headers such as Tags) and content (the exact entry text).
+
+The content and hash fields are enough to re-verify the chain independently: 
+SHA-256 of content equals hash, and previous equals the preceding entry's hash 
+(\"none\" for the first entry).
+
+Formats:
+  • jsonl  - One JSON object per line (default)
+  • json   - A single document with the entries and verification rules
+  • csv    - RFC 4180, header row; sections and headers hold JSON
+
+Use `engram export html` to render a static site instead.",
+        after_help = "\
+EXAMPLES:
+    Everything as JSON Lines:
+        $ engram export > worklog.jsonl
+
+    Entries 10 to 20 as CSV:
+        $ engram export --format csv --from 10 --to 20 > worklog.csv
+
+    One month as a JSON document:
+        $ engram export --format json --from 2025-06-01 --to 2025-06-30"
+    )]
+    Export {
+        #[command(subcommand)]
+        site: Option<ExportSite>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl, help = "Output format")]
+        format: ExportFormat,
+
+        /// First entry to include: sequence number, YYYY-MM-DD or RFC 3339 timestamp
+        #[arg(
+            long,
+            help = "First entry to include (sequence number, YYYY-MM-DD or RFC 3339 timestamp)"
+        )]
+        from: Option<RangeBound>,
+
+        /// Last entry to include: sequence number, YYYY-MM-DD or RFC 3339 timestamp
+        #[arg(
+            long,
+            help = "Last entry to include (sequence number, YYYY-MM-DD or RFC 3339 timestamp)"
+        )]
+        to: Option<RangeBound>,
+    },
+
+    /// Replay entries from an `engram export` file into this worklog
+    #[command(
+        long_about = "\
+Replay entries from an `engram export` file (JSON Lines, or the --format json 
+document) into this worklog.
+
+By default entries are imported unchanged: their exact bytes, hashes, dates 
+and sequence numbers are kept, so they must continue the existing chain (or 
+start it, in an empty worklog). Records already present are skipped, so the 
+same file can be imported twice.
+
+With --rechain each record becomes a new entry on top of the chain, dated 
+now, with provenance headers pointing at the original:
+  • Source-Entry  - Original filename
+  • Source-Hash   - Original entry hash
+  • Source-Date   - Original date
+
+Every record is checked before anything is written; a rejected import leaves 
+the worklog untouched. SUMMARY.md is rebuilt afterwards.
+
+Error code: E_IMPORT_REJECTED (exit 10)",
+        after_help = "\
+EXAMPLES:
+    Move a worklog to a new repository:
+        $ engram export > /tmp/worklog.jsonl
+        $ cd ../new-repo && engram init && engram import /tmp/worklog.jsonl
+
+    Merge another worklog's entries as new entries:
+        $ engram import --rechain other.jsonl"
+    )]
+    Import {
+        /// File written by `engram export`
+        file: PathBuf,
+
+        /// Commit records as new entries with Source-* headers instead of preserving hashes
+        #[arg(
+            long,
+            help = "Commit records as new entries with Source-* headers instead of preserving hashes"
+        )]
+        rechain: bool,
+    },
+
+    /// Generate a Keep a Changelog section from worklog entries
+    #[command(
+        long_about = "\
+Generate a Keep a Changelog (https://keepachangelog.com) section from worklog 
+entries.
+
+--from and --to accept an entry reference (sequence, filename or hash 
+prefix) or a date (YYYY-MM-DD or RFC 3339); both bounds are inclusive.
+
+--from-rev and --to-rev accept a git revision such as a release tag, meaning 
+the worklog as committed in it: --from-rev v1.0 starts after the last entry 
+v1.0 contains (exclusive); --to-rev v1.1 ends at the last entry v1.1 contains 
+(inclusive). Each side takes either an entry bound or a revision, not both.
+
+Entries are grouped into Added, Changed, Deprecated, Removed, Fixed and 
+Security by, in order of precedence:
+  • A Type: header (e.g. Type: fix)
+  • The first Tags: value naming a category (e.g. security)
+  • A conventional-commit prefix in the summary (feat:, fix(scope):, ...), 
+    which is removed from the changelog text
+Anything else is listed under Changed.
+
+Without --append the document is printed to stdout. --append inserts the 
+section into an existing CHANGELOG.md above the newest release, leaving older 
+sections untouched, and refuses to add a version that is already there.",
+        after_help = "\
+EXAMPLES:
+    Preview unreleased changes since the last tag:
+        $ engram changelog --from-rev v1.0.0
+
+    Add a release section to CHANGELOG.md:
+        $ engram changelog --from-rev v1.0.0 --to-rev v1.1.0 --version 1.1.0 --append CHANGELOG.md"
+    )]
+    Changelog {
+        /// Start at this entry or date (inclusive)
+        #[arg(
+            long,
+            value_name = ENTRY_VALUE,
+            help = "Start at this entry or date (inclusive)"
+        )]
+        from: Option<String>,
+
+        /// End at this entry or date (inclusive)
+        #[arg(
+            long,
+            value_name = ENTRY_VALUE,
+            help = "End at this entry or date (inclusive)"
+        )]
+        to: Option<String>,
+
+        /// Start after the last entry in this git revision (exclusive)
+        #[arg(
+            long,
+            value_name = "REV",
+            conflicts_with = "from",
+            help = "Start after the last entry in this git revision (exclusive)"
+        )]
+        from_rev: Option<String>,
+
+        /// End at the last entry in this git revision (inclusive)
+        #[arg(
+            long,
+            value_name = "REV",
+            conflicts_with = "to",
+            help = "End at the last entry in this git revision (inclusive)"
+        )]
+        to_rev: Option<String>,
+
+        /// Section title (default: Unreleased); a version is dated by its newest entry
+        #[arg(
+            long,
+            help = "Section title (default: Unreleased); a version is dated by its newest entry"
+        )]
+        version: Option<String>,
+
+        /// Insert the section into this changelog file instead of printing
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Insert the section into this changelog file instead of printing"
+        )]
+        append: Option<PathBuf>,
+    },
+
+    /// Create worklog entries from existing git history
+    #[command(
+        name = "import-git",
+        long_about = "\
+Create worklog entries from existing git history, oldest first.
+
+Each entry uses the commit subject as its summary, the commit body as a 
+## Changes section and the changed files as a ## Files section, and records 
+its commit(s) in a Source-Commit: header. Entries are appended to the chain 
+like normal commits, so `engram verify` accepts the result.
+
+Grouping (--by):
+  • commit  - One entry per non-merge commit (default)
+  • pr      - One entry per first-parent commit; a merge commit stands for its 
+              pull request and is summarized by the PR title
+  • day     - One entry per UTC day of non-merge commits
+
+Entries are dated by commit date, raised where needed so dates never go 
+backwards along the chain and capped at the current time. Commits already 
+recorded in a Source-Commit: header are skipped, so the command can be re-run 
+as history grows.
+
+Error code: E_IMPORT_REJECTED (exit 10) when git log fails",
+        after_help = "\
+EXAMPLES:
+    Import everything since a release:
+        $ engram import-git --since v1.0.0
+
+    One entry per merged pull request:
+        $ engram import-git --since v1.0.0 --by pr"
+    )]
+    ImportGit {
+        /// Start after this revision (default: the whole history)
+        #[arg(long, help = "Start after this revision (default: the whole history)")]
+        since: Option<String>,
+
+        /// How commits are grouped into entries
+        #[arg(
+            long = "by",
+            value_enum,
+            default_value_t = GitGroupBy::Commit,
+            help = "How commits are grouped into entries"
+        )]
+        by: GitGroupBy,
+    },
+
+    /// List tags with their entry counts, or the entries carrying a tag
+    #[command(
+        long_about = "\
+List tags with their entry counts, or the entries carrying a tag.
+
+Tag an entry in the draft, either on a line of its own:
+
+    <summary>Fix token expiry</summary>
+    Tags: auth, bugfix
+
+or in front matter at the top of the draft:
+
+    ---
+    tags: [auth, bugfix]
+    ---
+
+Tags are lowercased and recorded in the entry's Tags: header; SUMMARY.md 
+shows them after the summary. Tags use letters, digits, '-', '_', '.' and '/'.
+
+Error code: E_DRAFT_INVALID_TAG (from commit)",
+        after_help = "\
+EXAMPLES:
+    Tag counts over the whole chain:
+        $ engram tags
+
+    Entries tagged auth:
+        $ engram tags auth"
+    )]
+    Tags {
+        /// Show the entries carrying this tag
+        tag: Option<String>,
+    },
+
+    /// Show an entry's references and the entries that reference it
+    #[command(
+        long_about = "\
+Show an entry's references and the entries that reference it (backlinks).
+
+Reference other work from the draft with a Refs: line (or `refs:` in front 
+matter):
+
+    <summary>Revert the login form</summary>
+    Refs: 3f2a…(full 64-character entry hash), #123, JIRA-45
+
+Entry references must be full hashes of committed entries; commit rejects 
+anything else (E_DRAFT_UNKNOWN_REF). External ids like #123, JIRA-45 or 
+org/repo#7 are recorded as-is. `engram verify` checks that every entry 
+reference points to an earlier entry (E_REF_UNRESOLVED).
+
+The entry can be given by sequence number, filename or hash prefix.",
+        after_help = "\
+EXAMPLES:
+    Who built on entry 42:
+        $ engram refs 42"
+    )]
+    Refs {
+        /// Entry: sequence number, filename or hash prefix
+        #[arg(value_name = ENTRY_VALUE)]
+        entry: String,
+    },
+
+    /// Update directive blocks and .engram/AGENTS.md to the current templates
+    #[command(
+        long_about = "\
+Update the Engram directive in agent instruction files, and .engram/AGENTS.md, 
+to the templates of this release.
+
+init wraps the directive it writes in versioned markers:
+
+    <!-- engram:begin v0.1.1 -->
+    ## 🔒 Engram Protocol (MANDATORY)
+    ...
+    <!-- engram:end -->
+
+sync-directives replaces each block in place and leaves the rest of the file 
+alone. Directives written before the markers existed are replaced by a 
+marked block. .engram/AGENTS.md is replaced whole.",
+        after_help = "\
+EXAMPLES:
+    Preview the changes as a diff:
+        $ engram sync-directives --dry-run
+
+    Apply them:
+        $ engram sync-directives"
+    )]
+    SyncDirectives {
+        /// Show a diff instead of writing
+        #[arg(
+            long,
+            help = "Show what would change as a unified diff without writing"
+        )]
+        dry_run: bool,
+    },
+
+    /// Migrate an installation made by an older Engram to this release
+    #[command(
+        long_about = "\
+Migrate an installation made by an older Engram to this release.
+
+The installed version is read from .engram/VERSION (written by init since 
+this release), or else from the version pinned in ./engram. Migrations then 
+run in order, each one skipping what is already current:
+
+  • Legacy entry filenames - renames NNN_HHHHHHHH.md entries to 6-digit 
+    sequences and rebuilds SUMMARY.md (names are not hashed, so the chain 
+    is unchanged)
+  • Git hygiene files      - restores .engram/.gitignore and .gitattributes
+  • Wrapper scripts        - re-pins unmodified ./engram and engram.cmd to 
+    this release; customized wrappers are reported and left alone
+  • Templates              - refreshes .engram/AGENTS.md and the directive 
+    blocks in agent files (as `engram sync-directives` does)
+
+When anything changed, upgrade appends a worklog entry listing every change 
+with an Engram-Upgrade: <from> -> <to> header, anchoring the migrated 
+history. The draft is never touched. Finally .engram/VERSION is updated.
+
+An installation recorded as newer than this binary is refused (E_CONFIG).",
+        after_help = "\
+EXAMPLES:
+    After installing a new engram binary:
+        $ engram upgrade
+        $ engram verify"
+    )]
+    Upgrade,
+
+    /// Pin the wrapper scripts to another Engram release
+    #[command(
+        long_about = "\
+Regenerate ./engram and engram.cmd pinned to another Engram release, keeping 
+their wrapper mode. Missing wrappers are created.
+
+Local additions survive when they sit between marker comment lines:
+
+    # engram:custom:begin
+    export HTTPS_PROXY=http://proxy.internal:3128
+    # engram:custom:end
+
+(`rem engram:custom:begin` / `rem engram:custom:end` in engram.cmd). Each 
+block goes back after the line it followed, or after the wrapper's `cd` 
+into the project when that line changed. A wrapper edited anywhere else is 
+left alone and pin exits with E_CONFIG.
+
+Mirror-mode wrappers pin the new release's SHA-256s, read from --checksums 
+or the local mirror's v<version>/checksums.txt.",
+        after_help = "\
+EXAMPLES:
+    Move the repository to Engram 0.3.0:
+        $ engram pin 0.3.0
+
+    Mirror-mode wrappers with a remote mirror:
+        $ engram pin 0.3.0 --checksums checksums.txt"
+    )]
+    Pin {
+        /// Release to pin (MAJOR.MINOR.PATCH)
+        version: String,
+
+        /// Release checksums.txt for mirror-mode wrappers
+        #[arg(long, value_name = "FILE")]
+        checksums: Option<PathBuf>,
+    },
+
+    /// Replace this engram binary with a verified release
+    #[command(
+        name = "self-update",
+        long_about = "\
+Replace the running engram binary with a release, verified against the 
+checksums.txt the release workflow publishes.
+
+Releases are read from --from, else ENGRAM_MIRROR, else GitHub releases. 
+The directory (a URL, file:// URL or path) is laid out like the GitHub 
+releases: <dir>/v<version>/checksums.txt and <dir>/v<version>/<asset>. 
+Remote files are downloaded with curl or wget.
+
+Without --version, the newest release is installed if it is newer than this 
+binary: the highest v<version> in a local directory, or GitHub's latest 
+release when no directory is configured. Other remote directories cannot be 
+listed and need --version. The binary is only replaced when its SHA-256 
+matches its checksums.txt line.",
+        after_help = "\
+EXAMPLES:
+    Install the newest release from an internal mirror:
+        $ engram self-update --from /srv/engram-releases
+
+    Install the latest release from GitHub:
+        $ engram self-update
+
+    Install a specific release from GitHub:
+        $ engram self-update --version 0.3.0
+
+    Then move a repository's wrappers to it:
+        $ engram pin 0.3.0"
+    )]
+    SelfUpdate {
+        /// Release directory: URL, file:// URL or path
+        #[arg(long, value_name = "DIR")]
+        from: Option<String>,
+
+        /// Release to install (required for remote mirrors)
+        #[arg(long, value_name = "VERSION")]
+        version: Option<String>,
+    },
+
+    /// Copy this engram binary into .engram/bin for offline use
+    #[command(
+        long_about = "\
+Copy this engram binary into .engram/bin/engram-v<version>, where every 
+wrapper mode finds it without downloading (and where --wrapper-mode local 
+requires it).
+
+The copy is checked against the binary's SHA-256 before it is moved into 
+place, and the hash is recorded in engram-v<version>.sha256, which 
+local-mode wrappers check before every run. With 
+--checksums, the binary must also match this platform's asset in a release 
+checksums.txt.
+
+.engram/bin/ is git-ignored; vendor in CI, or commit the binary with 
+`git add -f` if every checkout should carry it.",
+        after_help = "\
+EXAMPLES:
+    Vendor the running binary:
+        $ engram vendor
+
+    Vendor only an unmodified release build:
+        $ engram vendor --checksums checksums.txt"
+    )]
+    Vendor {
+        /// Release checksums.txt the binary must match
+        #[arg(long, value_name = "FILE")]
+        checksums: Option<PathBuf>,
+    },
+
+    /// Remove Engram from this project
+    #[command(
+        long_about = "\
+Remove Engram from this project, undoing `engram init`.
+
+  • Agent files      - the Engram directive is cut out and the rest of the 
+                       file is left as it was; files that held nothing but 
+                       the directive are deleted
+  • Wrapper scripts  - ./engram and engram.cmd are deleted unless they have 
+                       local changes
+  • .engram/         - deleted, worklog included; pass --archive to keep a 
+                       copy as a tar file first, or --yes to delete it 
+                       without one
+
+The archive is written before anything is removed, so a failed archive 
+leaves the installation as it was. Use --dry-run to list everything deinit 
+would touch.",
+        after_help = "\
+EXAMPLES:
+    See what would be removed:
+        $ engram deinit --dry-run
+
+    Keep the worklog in an archive, then remove everything:
+        $ engram deinit --archive engram-worklog.tar
+
+    Remove everything, worklog included:
+        $ engram deinit --yes"
+    )]
+    Deinit {
+        /// List the changes without making them
+        #[arg(long, help = "List what would be removed without changing anything")]
+        dry_run: bool,
+
+        /// Write .engram/ to this tar file before deleting it
+        #[arg(long, value_name = "FILE")]
+        archive: Option<PathBuf>,
+
+        /// Delete .engram/ and the worklog without writing an archive
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Browse entries and edit the draft in an interactive terminal UI
+    #[command(
+        long_about = "\
+Browse entries and edit the draft in an interactive terminal UI.
+
+The left pane lists entries, newest first, with their date, summary and 
+verify status (✓ verified, ✗ where verification failed, · not checked 
+after a failure); the right pane shows the selected entry.
+
+Keys:
+  • j/k, arrows      - Move through the list (g/G: first/last)
+  • space/b, J/K     - Scroll the entry
+  • /                - Search summaries and bodies as you type
+  • t                - Filter by tag; type a prefix, Tab picks, Enter applies
+  • Esc              - Clear the search and tag filter
+  • d                - Edit .engram/draft.md; the status line shows the 
+                       Draft::parse result as you type
+  • Ctrl-S / Ctrl-G  - In the editor: save the draft / save and commit it
+  • r, q             - Reload the worklog, quit (a modified draft is saved)
+
+Runs in any Unix terminal, including over SSH; it needs stty and a terminal 
+that understands ANSI escape sequences.",
+        after_help = "\
+EXAMPLES:
+    Review an agent's work on a remote machine:
+        $ ssh build-host -t 'cd project && engram tui'"
+    )]
+    Tui,
+
+    /// Print a shell completion script
+    #[command(
+        long_about = "\
+Print a completion script for bash, zsh, fish, PowerShell or elvish, 
+generated from this binary's command definitions.
+
+In bash, zsh and fish, entry arguments (`engram refs <ENTRY>`, `engram 
+changelog --from/--to`) complete from the worklog of the current directory: 
+sequence numbers and hash prefixes, with the entry summaries where the shell 
+shows descriptions.",
+        after_help = "\
+EXAMPLES:
+    bash (add to ~/.bashrc):
+        $ source <(engram completions bash)
+
+    zsh (any directory on $fpath):
+        $ engram completions zsh > ~/.zfunc/_engram
+
+    fish:
+        $ engram completions fish > ~/.config/fish/completions/engram.fish
+
+    PowerShell (add to $PROFILE):
+        PS> engram completions powershell | Out-String | Invoke-Expression
+
+    elvish (add to rc.elv):
+        eval (engram completions elvish | slurp)"
+    )]
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Write man pages for engram and every subcommand
+    #[command(
+        long_about = "\
+Write roff man pages for engram and each of its subcommands (engram.1, 
+engram-init.1, engram-export-html.1, ...), generated from the same text as 
+--help.",
+        after_help = "\
+EXAMPLES:
+    Install the pages for the current user:
+        $ engram man --out ~/.local/share/man/man1
+        $ man engram-commit"
+    )]
+    Man {
+        /// Directory the pages are written to (created if missing)
+        #[arg(long, value_name = "DIR", default_value = "man")]
+        out: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum WrapperModeArg {
+    Github,
+    Local,
+    Mirror,
+    Path,
+}
+
+impl From<WrapperModeArg> for WrapperMode {
+    fn from(mode: WrapperModeArg) -> Self {
+        match mode {
+            WrapperModeArg::Github => WrapperMode::Github,
+            WrapperModeArg::Local => WrapperMode::Local,
+            WrapperModeArg::Mirror => WrapperMode::Mirror,
+            WrapperModeArg::Path => WrapperMode::Path,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GitGroupBy {
+    Commit,
+    Pr,
+    Day,
+}
+
+impl From<GitGroupBy> for GitGrouping {
+    fn from(by: GitGroupBy) -> Self {
+        match by {
+            GitGroupBy::Commit => GitGrouping::Commit,
+            GitGroupBy::Pr => GitGrouping::Pr,
+            GitGroupBy::Day => GitGrouping::Day,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Jsonl,
+    Json,
+    Csv,
+}
+
+impl From<ExportFormat> for DataFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Jsonl => DataFormat::Jsonl,
+            ExportFormat::Json => DataFormat::Json,
+            ExportFormat::Csv => DataFormat::Csv,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum DraftAction {
+    /// Replace the text of the <summary> tag
+    #[command(long_about = "\
+Replace the text of the <summary> tag, collapsed to a single line. If the tag 
+is missing it is added at the top of the draft.")]
+    SetSummary {
+        /// One sentence describing the change
+        #[arg(allow_hyphen_values = true)]
+        summary: String,
+    },
+
+    /// Append text to a section of the draft
+    #[command(long_about = "\
+Append text to the end of a ## section, below its guidance comment. The 
+section name is matched ignoring case; a section that does not exist is added 
+at the end of the draft.")]
+    Append {
+        /// Section heading without the ##, e.g. Intent, Changes or Verification
+        #[arg(long, value_name = "NAME")]
+        section: String,
+
+        /// Markdown to append; may span several lines
+        #[arg(allow_hyphen_values = true)]
+        text: String,
+    },
+
+    /// Print the draft
+    Show,
+
+    /// Reset the draft to the empty template
+    Clear,
+
+    /// Check the draft exactly as commit would, without committing
+    Validate,
+}
+
+#[derive(Subcommand)]
+enum ExportSite {
+    /// Render the worklog as a static HTML site
+    #[command(
+        long_about = "\
+Render the worklog as a static HTML site.
+
+Writes into the output directory:
+  • index.html            - Timeline of entries with search
+  • entries/<entry>.html  - One page per entry, rendered from markdown
+  • chain.html            - Chain integrity: every entry's hash and Previous: link
+  • search-index.js       - Summary and body of every entry, used by the search box
+  • search-index.json     - The same index as JSON, for other tools
+
+The verification result at export time is embedded in the pages. A broken 
+chain does not stop the export; it is shown as a failing badge.
+
+Output contains no export timestamp, so exporting the same worklog twice 
+produces identical files that can be diffed between runs.
+
+The output directory must be new, empty or a previous export, which is 
+replaced; a directory holding anything else is refused.",
+        after_help = "\
+EXAMPLES:
+    Export for a release review:
+        $ engram export html --out review-site/"
+    )]
+    Html {
+        /// Output directory (created if missing)
+        #[arg(long, help = "Output directory (created if missing)")]
+        out: PathBuf,
+    },
+}
+
+/// Parse the process arguments, run the command and return its exit code
+pub(crate) fn run() -> i32 {
+    // Called by the completion scripts; not a clap subcommand, so the
+    // generated scripts never offer it
+    if std::env::args().nth(1).as_deref() == Some(COMPLETE_ENTRIES) {
+        return cli::completions::run_entries();
+    }
+
+    let args = Cli::parse();
+    let json = args.json;
+
+    let result = match args.command {
+        Commands::Init {
+            warp,
+            claude,
+            junie,
+            agents,
+            cursor,
+            copilot,
+            windsurf,
+            cline,
+            gemini,
+            aider,
+            all,
+            repair,
+            force,
+            wrapper_mode,
+            mirror,
+            checksums,
+        } => {
+            let mode = if force {
+                InitMode::Force
+            } else if repair {
+                InitMode::Repair
+            } else {
+                InitMode::Fresh
+            };
+            let options = InitOptions {
+                mode,
+                warp: warp || all,
+                claude: claude || all,
+                junie: junie || all,
+                agents: agents || all,
+                cursor: cursor || all,
+                copilot: copilot || all,
+                windsurf: windsurf || all,
+                cline: cline || all,
+                gemini: gemini || all,
+                aider: aider || all,
+                all,
+                wrapper_mode: wrapper_mode.map(Into::into),
+                mirror,
+                checksums,
+            };
+            cli::init::run(options, json)
+        }
+        Commands::Commit => cli::commit::run(json),
+        Commands::Draft { action } => match action {
+            DraftAction::SetSummary { summary } => cli::draft::run_set_summary(&summary, json),
+            DraftAction::Append { section, text } => cli::draft::run_append(&section, &text, json),
+            DraftAction::Show => cli::draft::run_show(json),
+            DraftAction::Clear => cli::draft::run_clear(json),
+            DraftAction::Validate => cli::draft::run_validate(json),
+        },
+        Commands::Verify => cli::verify::run(json),
+        Commands::Status => cli::status::run(json),
+        Commands::Doctor { fix, fix_eol } => cli::doctor::run(DoctorOptions { fix, fix_eol }, json),
+        Commands::Mcp => cli::mcp::run(),
+        Commands::Serve { port } => cli::serve::run(port),
+        Commands::Export {
+            site: Some(ExportSite::Html { out }),
+            ..
+        } => cli::export::run_html(&out, json),
+        Commands::Export {
+            site: None,
+            format,
+            from,
+            to,
+        } => cli::export::run_data(format.into(), ExportRange { from, to }),
+        Commands::Tags { tag } => cli::tags::run(tag.as_deref(), json),
+        Commands::Refs { entry } => cli::refs::run(&entry, json),
+        Commands::Upgrade => cli::upgrade::run(json),
+        Commands::Pin { version, checksums } => {
+            cli::pin::run(&version, PinOptions { checksums }, json)
+        }
+        Commands::SelfUpdate { from, version } => {
+            cli::self_update::run(SelfUpdateOptions { from, version }, json)
+        }
+        Commands::Vendor { checksums } => cli::vendor::run(VendorOptions { checksums }, json),
+        Commands::Deinit {
+            dry_run,
+            archive,
+            yes,
+        } => cli::deinit::run(
+            DeinitOptions {
+                dry_run,
+                archive,
+                yes,
+            },
+            json,
+        ),
+        Commands::Tui => cli::tui::run(),
+        Commands::Completions { shell } => cli::completions::run(shell, Cli::command()),
+        Commands::Man { out } => cli::man::run(Cli::command(), &out, json),
+        Commands::SyncDirectives { dry_run } => {
+            cli::sync_directives::run(SyncOptions { dry_run }, json)
+        }
+        Commands::Changelog {
+            from,
+            to,
+            from_rev,
+            to_rev,
+            version,
+            append,
+        } => cli::changelog::run(
+            ChangelogOptions {
+                from,
+                to,
+                from_rev,
+                to_rev,
+                version,
+            },
+            append,
+            json,
+        ),
+        Commands::ImportGit { since, by } => {
+            cli::import_git::run(since.as_deref(), by.into(), json)
+        }
+        Commands::Import { file, rechain } => {
+            let mode = if rechain {
+                ImportMode::Rechain
+            } else {
+                ImportMode::Preserve
+            };
+            cli::import::run(&file, mode, json)
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => cli::report_error(&e, json),
+    }
+}
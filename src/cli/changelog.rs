@@ -0,0 +1,23 @@
+use std::path::{Path, PathBuf};
+
+use crate::commands::changelog::{append_to_file, build_changelog, ChangelogOptions};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: ChangelogOptions, append: Option<PathBuf>, json: bool) -> CliResult {
+    let changelog = build_changelog(Path::new("."), &options)?;
+
+    if let Some(path) = &append {
+        append_to_file(path, &changelog)?;
+    }
+
+    if json {
+        print_json(&changelog.to_json());
+    } else if let Some(path) = &append {
+        println!("Added {} to {}", changelog.heading(), path.display());
+    } else {
+        print!("{}", changelog.render_document());
+    }
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,26 @@
+use crate::error::EXIT_SUCCESS;
+use crate::Repository;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(json: bool) -> CliResult {
+    let result = Repository::open(".")?.commit()?;
+
+    if json {
+        print_json(&result.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    // Output
+    let prev_display = if result.previous == "none" {
+        "none".to_string()
+    } else {
+        format!("{}...", &result.previous[..8])
+    };
+
+    println!("Committed: {}", result.filename);
+    println!("Summary: {}", result.summary);
+    println!("Previous: {}", prev_display);
+
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,297 @@
+//! `engram completions`: shell completion scripts generated from the clap
+//! definition by clap_complete. In bash, zsh and fish, arguments whose value
+//! name is ENTRY also complete from the worklog by calling the hidden
+//! `engram __complete-entries`.
+
+use clap::{Arg, Command};
+pub use clap_complete::Shell;
+
+use crate::error::EXIT_SUCCESS;
+use crate::{Entry, Repository};
+
+use crate::cli::CliResult;
+
+/// Value name marking an argument as an entry reference
+pub const ENTRY_VALUE: &str = "ENTRY";
+/// First argument that makes engram print entry references for the completion scripts
+pub const COMPLETE_ENTRIES: &str = "__complete-entries";
+
+/// Length of the hash prefixes offered as entry references
+const HASH_PREFIX: usize = 8;
+
+pub fn run(shell: Shell, mut command: Command) -> CliResult {
+    print!("{}", generate(shell, &mut command));
+    Ok(EXIT_SUCCESS)
+}
+
+/// Print `<reference>\t<summary>` lines for the entries, newest first, and
+/// return the exit code. Prints nothing outside an Engram repository so
+/// completion falls back quietly.
+pub fn run_entries() -> i32 {
+    if let Ok(entries) = Repository::open(".").and_then(|repo| repo.entries()) {
+        for (reference, description) in entry_candidates(&entries) {
+            println!("{}\t{}", reference, description);
+        }
+    }
+    EXIT_SUCCESS
+}
+
+/// Sequence numbers, then hash prefixes, for every entry
+fn entry_candidates(entries: &[Entry]) -> Vec<(String, String)> {
+    let summary = |entry: &Entry| entry.content.summary.replace('\t', " ");
+    let sequences = entries
+        .iter()
+        .rev()
+        .map(|entry| (entry.sequence.to_string(), summary(entry)));
+    let hashes = entries.iter().rev().map(|entry| {
+        (
+            entry.hash[..HASH_PREFIX].to_string(),
+            format!("#{} {}", entry.sequence, summary(entry)),
+        )
+    });
+    sequences.chain(hashes).collect()
+}
+
+/// Completion script for `shell`: clap_complete's script, followed by entry
+/// completion for the shells that can call back into engram
+pub fn generate(shell: Shell, command: &mut Command) -> String {
+    command.build();
+    let bin = command.get_name().to_string();
+    let mut script = Vec::new();
+    clap_complete::generate(shell, command, bin.clone(), &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+
+    let mut entry_args = Vec::new();
+    collect_entry_args(command, &[], &mut entry_args);
+    match shell {
+        Shell::Bash => script.push_str(&bash_entries(&bin, &entry_args)),
+        Shell::Zsh => script = zsh_entries(&bin, &script, &entry_args),
+        Shell::Fish => script.push_str(&fish_entries(&bin, &entry_args)),
+        _ => {}
+    }
+    script
+}
+
+/// An argument taking an entry reference, identified by its subcommand path
+struct EntryArg {
+    path: Vec<String>,
+    /// `--long` name of an option, or None for a positional
+    long: Option<String>,
+}
+
+fn collect_entry_args(command: &Command, path: &[String], args: &mut Vec<EntryArg>) {
+    for arg in command.get_arguments().filter(|arg| is_entry(arg)) {
+        args.push(EntryArg {
+            path: path.to_vec(),
+            long: arg.get_long().map(str::to_string),
+        });
+    }
+    for sub in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        let mut path = path.to_vec();
+        path.push(sub.get_name().to_string());
+        collect_entry_args(sub, &path, args);
+    }
+}
+
+fn is_entry(arg: &Arg) -> bool {
+    !arg.is_hide_set()
+        && arg
+            .get_value_names()
+            .is_some_and(|names| names.first().is_some_and(|name| name == ENTRY_VALUE))
+}
+
+/// `case` arms matching `<subcommand path>|<previous word>` for the entry
+/// arguments; `reply` completes the entries and returns
+fn entry_cases(args: &[EntryArg], current: &str, reply: &str) -> String {
+    args.iter()
+        .map(|arg| match &arg.long {
+            Some(long) => format!(
+                "        \"{}\"*\"|--{}\") {} ;;\n",
+                arg.path.join(" "),
+                long,
+                reply
+            ),
+            None => format!(
+                "        \"{}|\"*) [[ {} == -* ]] || {{ {}; }} ;;\n",
+                arg.path.join(" "),
+                current,
+                reply
+            ),
+        })
+        .collect()
+}
+
+/// Wrap the generated `_engram` so entry arguments complete from the worklog.
+/// `cmdpath` is the words typed so far that are not options, so a positional
+/// matches only directly after its subcommand.
+fn bash_entries(bin: &str, args: &[EntryArg]) -> String {
+    let func = format!("_{}", bin.replace('-', "_"));
+    let cases = entry_cases(args, "\"${cur}\"", &format!("{}_entries; return 0", func));
+    format!(
+        "\n{func}_entries() {{\n    \
+         COMPREPLY=($(compgen -W \"$(\"${{COMP_WORDS[0]}}\" {COMPLETE_ENTRIES} 2>/dev/null | cut -f1)\" -- \"${{COMP_WORDS[COMP_CWORD]}}\"))\n\
+         }}\n\n\
+         {func}_with_entries() {{\n    \
+         local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\" cmdpath=\"\" i\n    \
+         for ((i = 1; i < COMP_CWORD; i++)); do\n        \
+         [[ \"${{COMP_WORDS[i]}}\" == -* ]] || cmdpath=\"${{cmdpath:+${{cmdpath}} }}${{COMP_WORDS[i]}}\"\n    \
+         done\n    \
+         case \"${{cmdpath}}|${{prev}}\" in\n\
+         {cases}    \
+         esac\n    \
+         {func} \"$@\"\n\
+         }}\n\n\
+         complete -F {func}_with_entries -o bashdefault -o default {bin}\n"
+    )
+}
+
+/// The zsh counterpart of `bash_entries`. The generated script ends by
+/// calling or registering `_engram`; that block is replaced so both the
+/// autoloaded and the sourced script go through the wrapper.
+fn zsh_entries(bin: &str, script: &str, args: &[EntryArg]) -> String {
+    let func = format!("_{}", bin.replace('-', "_"));
+    let dispatch = format!("if [ \"$funcstack[1]\" = \"{}\" ]; then", func);
+    let script = match script.rfind(&dispatch) {
+        Some(offset) => &script[..offset],
+        None => script,
+    };
+    let cases = entry_cases(
+        args,
+        "$words[CURRENT]",
+        &format!("{}_entries; return", func),
+    );
+    format!(
+        "{script}\
+         {func}_entries() {{\n    \
+         local line\n    \
+         local -a entries\n    \
+         for line in ${{(f)\"$($words[1] {COMPLETE_ENTRIES} 2>/dev/null)\"}}; do\n        \
+         entries+=(\"${{line%%$'\\t'*}}:${{${{line#*$'\\t'}}//:/\\\\:}}\")\n    \
+         done\n    \
+         _describe -t entries 'entry' entries\n\
+         }}\n\n\
+         {func}_with_entries() {{\n    \
+         local prev=$words[CURRENT-1] cmdpath=\"\" i\n    \
+         for ((i = 2; i < CURRENT; i++)); do\n        \
+         [[ $words[i] == -* ]] || cmdpath=\"${{cmdpath:+$cmdpath }}$words[i]\"\n    \
+         done\n    \
+         case \"$cmdpath|$prev\" in\n\
+         {cases}    \
+         esac\n    \
+         {func} \"$@\"\n\
+         }}\n\n\
+         if [ \"$funcstack[1]\" = \"{func}\" ]; then\n    \
+         {func}_with_entries \"$@\"\n\
+         else\n    \
+         compdef {func}_with_entries {bin}\n\
+         fi\n"
+    )
+}
+
+/// fish adds these to the generated completions for the same options
+fn fish_entries(bin: &str, args: &[EntryArg]) -> String {
+    let func = format!("__fish_{}_entries", bin.replace('-', "_"));
+    let mut script = format!(
+        "\nfunction {func}\n    \
+         command (commandline -opc)[1] {COMPLETE_ENTRIES} 2>/dev/null\n\
+         end\n"
+    );
+    for arg in args {
+        let condition: Vec<String> = arg
+            .path
+            .iter()
+            .map(|name| format!("__fish_seen_subcommand_from {}", name))
+            .collect();
+        let target = match &arg.long {
+            Some(long) => format!("-l {} -x", long),
+            None => "-f".to_string(),
+        };
+        script.push_str(&format!(
+            "complete -c {} -n '{}' {} -a '({})'\n",
+            bin,
+            condition.join("; and "),
+            target,
+            func
+        ));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command() -> Command {
+        Command::new("engram")
+            .arg(clap::arg!(--json "Print JSON").global(true))
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("refs")
+                    .about("Show references")
+                    .arg(Arg::new("entry").value_name(ENTRY_VALUE).required(true)),
+            )
+            .subcommand(
+                Command::new("sync-directives")
+                    .about("Update directive blocks")
+                    .arg(clap::arg!(--"dry-run" "Show a diff: nothing is written")),
+            )
+            .subcommand(
+                Command::new("export")
+                    .about("Export the worklog")
+                    .arg(
+                        clap::arg!(--format <FORMAT> "Output format")
+                            .value_parser(["jsonl", "csv"]),
+                    )
+                    .arg(clap::arg!(--from <ENTRY> "First entry"))
+                    .subcommand(Command::new("html").about("Render a site")),
+            )
+    }
+
+    #[test]
+    fn test_bash_wraps_generated_function() {
+        let script = generate(Shell::Bash, &mut command());
+
+        assert!(script.contains("_engram() {"));
+        assert!(script.contains("\"${COMP_WORDS[0]}\" __complete-entries"));
+        assert!(script.contains("        \"refs|\"*) [[ \"${cur}\" == -* ]]"));
+        assert!(
+            script.ends_with("complete -F _engram_with_entries -o bashdefault -o default engram\n")
+        );
+    }
+
+    #[test]
+    fn test_zsh_dispatches_through_wrapper() {
+        let script = generate(Shell::Zsh, &mut command());
+
+        assert!(script.starts_with("#compdef engram\n"));
+        assert!(script.contains("        \"refs|\"*) [[ $words[CURRENT] == -* ]]"));
+        assert!(script.ends_with(
+            "    _engram_with_entries \"$@\"\nelse\n    compdef _engram_with_entries engram\nfi\n"
+        ));
+        assert_eq!(
+            script
+                .matches("if [ \"$funcstack[1]\" = \"_engram\" ]")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_fish_adds_entry_arguments() {
+        let script = generate(Shell::Fish, &mut command());
+
+        assert!(script.contains(
+            "complete -c engram -n '__fish_seen_subcommand_from refs' -f -a '(__fish_engram_entries)'"
+        ));
+        assert!(script.contains(
+            "complete -c engram -n '__fish_seen_subcommand_from export' -l from -x -a '(__fish_engram_entries)'"
+        ));
+    }
+
+    #[test]
+    fn test_other_shells_use_generated_script() {
+        let script = generate(Shell::PowerShell, &mut command());
+        assert!(script.contains("Register-ArgumentCompleter"));
+        assert!(!script.contains("__complete-entries"));
+    }
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use crate::commands::deinit::{run_deinit_in_dir, DeinitOptions};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: DeinitOptions, json: bool) -> CliResult {
+    let report = run_deinit_in_dir(Path::new("."), &options)?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    for action in &report.actions {
+        println!("{}", action);
+    }
+    if report.dry_run {
+        println!(
+            "Dry run: nothing was changed. Run `engram deinit --yes`, or with --archive, to apply."
+        );
+    } else {
+        println!("Engram removed from this project.");
+    }
+    Ok(EXIT_SUCCESS)
+}
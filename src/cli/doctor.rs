@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use crate::commands::doctor::{run_doctor_in_dir, DoctorOptions, Resolution};
+use crate::error::{EXIT_PROBLEMS_FOUND, EXIT_SUCCESS};
+use serde_json::json;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: DoctorOptions, json: bool) -> CliResult {
+    let report = run_doctor_in_dir(Path::new("."), &options)?;
+    let code = if report.has_unresolved() {
+        EXIT_PROBLEMS_FOUND
+    } else {
+        EXIT_SUCCESS
+    };
+
+    if json {
+        let checks: Vec<_> = report
+            .checks
+            .iter()
+            .map(|check| {
+                let findings: Vec<_> = check
+                    .findings
+                    .iter()
+                    .map(|finding| {
+                        let (resolution, hint) = match &finding.resolution {
+                            Resolution::Fixed => ("fixed", None),
+                            Resolution::Fixable(flag) => {
+                                ("fixable", Some(format!("engram doctor {}", flag)))
+                            }
+                            Resolution::Manual(hint) => ("manual", Some(hint.clone())),
+                        };
+                        json!({
+                            "subject": finding.subject,
+                            "problem": finding.problem,
+                            "resolution": resolution,
+                            "hint": hint,
+                        })
+                    })
+                    .collect();
+                json!({"name": check.name, "findings": findings})
+            })
+            .collect();
+        print_json(&json!({
+            "checks": checks,
+            "unresolved": report.has_unresolved(),
+        }));
+        return Ok(code);
+    }
+
+    println!("Engram Doctor");
+    println!("─────────────");
+
+    for check in &report.checks {
+        if check.findings.is_empty() {
+            println!("✓ {}", check.name);
+            continue;
+        }
+
+        let all_fixed = check
+            .findings
+            .iter()
+            .all(|f| f.resolution == Resolution::Fixed);
+        println!("{} {}", if all_fixed { "✓" } else { "✗" }, check.name);
+
+        for finding in &check.findings {
+            match &finding.resolution {
+                Resolution::Fixed => {
+                    println!("    Fixed {}: {}", finding.subject, finding.problem)
+                }
+                Resolution::Fixable(flag) => {
+                    println!("    {}: {}", finding.subject, finding.problem);
+                    println!("      Fix: engram doctor {}", flag);
+                }
+                Resolution::Manual(hint) => {
+                    println!("    {}: {}", finding.subject, finding.problem);
+                    println!("      Manual: {}", hint);
+                }
+            }
+        }
+    }
+
+    Ok(code)
+}
@@ -0,0 +1,78 @@
+use serde_json::json;
+use std::path::Path;
+
+use crate::commands::draft::{
+    run_append_in_dir, run_clear_in_dir, run_set_summary_in_dir, validate_draft_in_dir,
+};
+use crate::error::EXIT_SUCCESS;
+use crate::Repository;
+
+use crate::cli::{print_json, CliResult};
+
+const DRAFT_FILE: &str = ".engram/draft.md";
+
+pub fn run_set_summary(summary: &str, json: bool) -> CliResult {
+    let draft = run_set_summary_in_dir(Path::new("."), summary)?;
+    print_updated(&draft, json);
+    Ok(EXIT_SUCCESS)
+}
+
+pub fn run_append(section: &str, text: &str, json: bool) -> CliResult {
+    let draft = run_append_in_dir(Path::new("."), section, text)?;
+    print_updated(&draft, json);
+    Ok(EXIT_SUCCESS)
+}
+
+pub fn run_show(json: bool) -> CliResult {
+    let draft = Repository::open(".")?.read_draft()?;
+    if json {
+        print_json(&json!({ "path": DRAFT_FILE, "content": draft }));
+    } else {
+        print!("{}", draft);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+pub fn run_clear(json: bool) -> CliResult {
+    run_clear_in_dir(Path::new("."))?;
+    if json {
+        print_json(&json!({ "path": DRAFT_FILE, "cleared": true }));
+    } else {
+        println!("✓ Reset {} to the template", DRAFT_FILE);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+pub fn run_validate(json: bool) -> CliResult {
+    let draft = validate_draft_in_dir(Path::new("."))?;
+    if json {
+        print_json(&draft.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!("✓ Draft is ready to commit");
+    println!("  Summary: {}", draft.summary);
+    if !draft.tags.is_empty() {
+        println!("  Tags: {}", draft.tags.join(", "));
+    }
+    if let Some(change_type) = &draft.change_type {
+        println!("  Type: {}", change_type);
+    }
+    if !draft.refs.is_empty() {
+        let refs: Vec<&str> = draft
+            .refs
+            .iter()
+            .map(|reference| reference.as_str())
+            .collect();
+        println!("  Refs: {}", refs.join(", "));
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn print_updated(draft: &str, json: bool) {
+    if json {
+        print_json(&json!({ "path": DRAFT_FILE, "content": draft }));
+    } else {
+        println!("✓ Updated {}", DRAFT_FILE);
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use crate::commands::export::{export_data, export_html, DataFormat, ExportRange};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run_html(out: &Path, json: bool) -> CliResult {
+    let export = export_html(Path::new("."), out)?;
+
+    if json {
+        print_json(&export.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!(
+        "Exported {} files to {}",
+        export.files.len(),
+        export.out.display()
+    );
+    match &export.verify {
+        Ok(result) => println!("✓ Chain verified: {} entries", result.entry_count),
+        Err(e) => eprintln!(
+            "Warning [{}]: {} (shown in the exported pages)",
+            e.code(),
+            e
+        ),
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+/// Write entries to stdout. `--json` does not apply: the format is the output.
+pub fn run_data(format: DataFormat, range: ExportRange) -> CliResult {
+    print!("{}", export_data(Path::new("."), format, range)?);
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use crate::commands::import::{run_import_in_dir, ImportMode};
+use crate::error::EXIT_SUCCESS;
+use crate::utils::clock;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(file: &Path, mode: ImportMode, json: bool) -> CliResult {
+    let clock = clock::from_env()?;
+    let report = run_import_in_dir(Path::new("."), file, mode, clock.as_ref())?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    for filename in &report.imported {
+        println!("Imported: {}", filename);
+    }
+    if report.skipped > 0 {
+        println!("Skipped {} entries already in the worklog", report.skipped);
+    }
+    println!(
+        "✓ Imported {} entries; SUMMARY.md rebuilt and chain verified",
+        report.imported.len()
+    );
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use crate::commands::import_git::{run_import_git_in_dir, GitGrouping};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(since: Option<&str>, grouping: GitGrouping, json: bool) -> CliResult {
+    let report = run_import_git_in_dir(Path::new("."), since, grouping)?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    for filename in &report.imported {
+        println!("Created: {}", filename);
+    }
+    if report.skipped > 0 {
+        println!("Skipped {} commits already in the worklog", report.skipped);
+    }
+    println!(
+        "✓ Imported git history as {} entries; chain verified",
+        report.imported.len()
+    );
+    Ok(EXIT_SUCCESS)
+}
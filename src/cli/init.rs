@@ -0,0 +1,39 @@
+use crate::commands::init::{run_init_in_dir, InitAction, InitOptions};
+use crate::error::EXIT_SUCCESS;
+use serde_json::json;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: InitOptions, json: bool) -> CliResult {
+    let cwd = std::env::current_dir()?;
+    let report = run_init_in_dir(&cwd, options)?;
+
+    if json {
+        let actions: Vec<_> = report
+            .actions
+            .iter()
+            .map(|action| match action {
+                InitAction::Created(path) => json!({"action": "created", "path": path}),
+                InitAction::Appended(path) => json!({"action": "appended", "path": path}),
+                InitAction::Updated { path, change } => {
+                    json!({"action": "updated", "path": path, "change": change})
+                }
+                InitAction::Skipped { path, reason } => {
+                    json!({"action": "skipped", "path": path, "reason": reason})
+                }
+            })
+            .collect();
+        print_json(&json!({
+            "root": cwd.display().to_string(),
+            "actions": actions,
+        }));
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!("Initialized Engram in {}", cwd.display());
+    for action in &report.actions {
+        println!("{}", action);
+    }
+
+    Ok(EXIT_SUCCESS)
+}
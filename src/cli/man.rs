@@ -0,0 +1,119 @@
+//! `engram man`: roff manual pages generated from the clap definition by
+//! clap_mangen, one page per command (`engram.1`, `engram-init.1`,
+//! `engram-export-html.1`, ...)
+
+use clap::Command;
+use clap_mangen::Man;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(command: Command, out: &Path, json: bool) -> CliResult {
+    fs::create_dir_all(out)?;
+    let mut written = Vec::new();
+    for (name, page) in pages(command)? {
+        let path = out.join(name);
+        fs::write(&path, page)?;
+        written.push(path.display().to_string());
+    }
+
+    if json {
+        print_json(&json!({ "pages": written }));
+    } else {
+        for path in &written {
+            println!("Wrote {}", path);
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+/// File name and roff source of every visible command's page, parents first
+pub fn pages(command: Command) -> std::io::Result<Vec<(String, String)>> {
+    let mut command = command.disable_help_subcommand(true);
+    // Building names subcommands `engram-init` and copies global arguments down
+    command.build();
+    // Subcommands have no version of their own; every page names the release
+    let source = format!(
+        "{} {}",
+        command.get_name(),
+        command.get_version().unwrap_or_default()
+    );
+    let mut pages = Vec::new();
+    collect(&command, &source, &mut pages)?;
+    Ok(pages)
+}
+
+fn collect(
+    command: &Command,
+    source: &str,
+    pages: &mut Vec<(String, String)>,
+) -> std::io::Result<()> {
+    let man = Man::new(command.clone()).source(source);
+    let mut page = Vec::new();
+    man.render(&mut page)?;
+    pages.push((
+        man.get_filename(),
+        String::from_utf8_lossy(&page).into_owned(),
+    ));
+    for sub in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        collect(sub, source, pages)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command() -> Command {
+        Command::new("engram")
+            .version("1.2.3")
+            .about("Worklog")
+            .arg(clap::arg!(--json "Print JSON").global(true))
+            .subcommand(
+                Command::new("export")
+                    .about("Export the worklog")
+                    .long_about("Export entries.\n\nFormats:\n  • jsonl - One per line")
+                    .after_help("EXAMPLES:\n    Everything:\n        $ engram export")
+                    .arg(
+                        clap::arg!(--format <FORMAT> "Output format")
+                            .value_parser(["jsonl", "csv"])
+                            .default_value("jsonl"),
+                    )
+                    .subcommand(Command::new("html").about("Render a site")),
+            )
+            .subcommand(Command::new("__complete-entries").hide(true))
+    }
+
+    #[test]
+    fn test_pages_cover_visible_subcommands() {
+        let names: Vec<String> = pages(command())
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            ["engram.1", "engram-export.1", "engram-export-html.1"]
+        );
+    }
+
+    #[test]
+    fn test_page_sections() {
+        let pages = pages(command()).unwrap();
+        let export = &pages[1].1;
+
+        assert!(export.contains(".TH engram-export 1  \"engram 1.2.3\""));
+        assert!(export.contains(".SH NAME\nengram\\-export \\- Export the worklog\n"));
+        assert!(export.contains("Export entries."));
+        assert!(export.contains("\\-\\-format"));
+        assert!(export.contains("jsonl"));
+        assert!(export.contains("\\-\\-json"));
+        assert!(export.contains(".SH SUBCOMMANDS\n"));
+        assert!(export.contains("$ engram export"));
+    }
+}
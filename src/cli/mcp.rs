@@ -0,0 +1,12 @@
+use std::io;
+
+use crate::error::EXIT_SUCCESS;
+use crate::mcp::McpServer;
+
+use crate::cli::CliResult;
+
+pub fn run() -> CliResult {
+    let server = McpServer::new(std::env::current_dir()?);
+    server.serve(io::stdin().lock(), io::stdout().lock())?;
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,52 @@
+//! Command-line front end: argument handling, printing and exit codes.
+//! Commands print and return exit codes here; the behaviour behind them is
+//! the same library code `Repository` uses.
+
+use crate::EngramError;
+use serde_json::Value;
+
+pub mod app;
+pub mod changelog;
+pub mod commit;
+pub mod completions;
+pub mod deinit;
+pub mod doctor;
+pub mod draft;
+pub mod export;
+pub mod import;
+pub mod import_git;
+pub mod init;
+pub mod man;
+pub mod mcp;
+pub mod pin;
+pub mod refs;
+pub mod self_update;
+pub mod serve;
+pub mod status;
+pub mod sync_directives;
+pub mod tags;
+pub mod tui;
+pub mod upgrade;
+pub mod vendor;
+pub mod verify;
+
+/// Exit code of a subcommand, or the error that stopped it
+pub type CliResult = Result<i32, EngramError>;
+
+/// Print an error with its identifier and return the matching exit code.
+/// In JSON mode the error goes to stdout so scripts read a single stream.
+pub fn report_error(error: &EngramError, json: bool) -> i32 {
+    if json {
+        print_json(&error.to_json());
+    } else {
+        eprintln!("Error [{}]: {}", error.code(), error);
+    }
+    error.exit_code()
+}
+
+pub fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("JSON values always serialize")
+    );
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use crate::commands::pin::{run_pin_in_dir, PinOptions};
+use crate::error::{EXIT_CONFIG, EXIT_SUCCESS};
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(version: &str, options: PinOptions, json: bool) -> CliResult {
+    let report = run_pin_in_dir(Path::new("."), version, &options)?;
+    let code = if report.has_skipped() {
+        EXIT_CONFIG
+    } else {
+        EXIT_SUCCESS
+    };
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(code);
+    }
+
+    for wrapper in &report.wrappers {
+        println!("{}", wrapper);
+    }
+    if report.has_skipped() {
+        eprintln!(
+            "⚠ Move local edits between `engram:custom:begin` and `engram:custom:end` comment lines, then run `engram pin {}` again.",
+            report.version
+        );
+    } else {
+        println!("✓ Wrappers pin Engram {}", report.version);
+    }
+    Ok(code)
+}
@@ -0,0 +1,53 @@
+use serde_json::json;
+
+use crate::engram::refs::Reference;
+use crate::error::EXIT_SUCCESS;
+use crate::{Entry, Repository};
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(reference: &str, json: bool) -> CliResult {
+    let repo = Repository::open(".")?;
+    let entry = repo.find_entry(reference)?;
+    let entries = repo.entries()?;
+    let backlinks = repo.backlinks(&entry)?;
+
+    // Entry references are shown by filename when they resolve
+    let refs: Vec<String> = entry
+        .content
+        .refs()
+        .into_iter()
+        .map(|reference| match &reference {
+            Reference::Entry(hash) => entries
+                .iter()
+                .find(|e| e.hash == *hash)
+                .map_or_else(|| hash.clone(), |e| e.filename.clone()),
+            Reference::External(id) => id.clone(),
+        })
+        .collect();
+
+    if json {
+        print_json(&json!({
+            "entry": entry.filename,
+            "refs": refs,
+            "referenced_by": backlinks.iter().map(|e| &e.filename).collect::<Vec<_>>(),
+        }));
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!("{}  {}", entry.filename, entry.content.summary);
+    if !refs.is_empty() {
+        println!("Refs:          {}", refs.join(", "));
+    }
+    if backlinks.is_empty() {
+        println!("Referenced by: (none)");
+    } else {
+        let sequences: Vec<String> = backlinks.iter().map(sequence_label).collect();
+        println!("Referenced by: {}", sequences.join(", "));
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn sequence_label(entry: &Entry) -> String {
+    format!("{:06}", entry.sequence)
+}
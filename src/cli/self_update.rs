@@ -0,0 +1,34 @@
+use crate::commands::self_update::{run_self_update, SelfUpdateOptions};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: SelfUpdateOptions, json: bool) -> CliResult {
+    let exe = std::env::current_exe()?;
+    let report = run_self_update(&exe, &options)?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    match (&report.source, &report.sha256) {
+        (Some(source), Some(sha256)) => {
+            println!(
+                "Updated Engram {} → {}",
+                report.from_version, report.to_version
+            );
+            println!("Source:  {}", source);
+            println!("SHA-256: {} (matches checksums.txt)", sha256);
+            println!(
+                "Run `engram pin {}` in each repository to move its wrappers to this release.",
+                report.to_version
+            );
+        }
+        _ => println!(
+            "Engram {} is up to date (newest available: {})",
+            report.from_version, report.to_version
+        ),
+    }
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,20 @@
+use crate::error::EXIT_SUCCESS;
+use crate::server::WebServer;
+use crate::Repository;
+
+use crate::cli::CliResult;
+
+pub fn run(port: u16) -> CliResult {
+    let root = std::env::current_dir()?;
+    // Fail fast instead of serving error pages for an uninitialized directory
+    Repository::open(&root)?;
+
+    let listener = WebServer::bind(port)?;
+    println!(
+        "Serving the Engram worklog at http://{} (read-only, Ctrl-C to stop)",
+        listener.local_addr()?
+    );
+
+    WebServer::new(root).serve(listener)?;
+    Ok(EXIT_SUCCESS)
+}
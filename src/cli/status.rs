@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use crate::commands::status::{run_status_in_dir, DraftStatus};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(json: bool) -> CliResult {
+    let report = run_status_in_dir(Path::new("."))?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!("Engram Status");
+    println!("─────────────");
+    println!("Worklog: {} entries", report.worklog.entry_count);
+
+    // Display latest entry info if available
+    if let Some(latest) = report.worklog.latest {
+        println!("Latest:  {} ({})", latest.filename, latest.date);
+        println!("         \"{}\"", latest.summary);
+    }
+
+    println!();
+
+    match report.draft {
+        DraftStatus::HasContent(summary) => {
+            println!("Draft:   Has content (uncommitted work)");
+            println!("         Summary: \"{}\"", summary);
+        }
+        DraftStatus::Empty => {
+            println!("Draft:   Empty (ready for new work)");
+        }
+        DraftStatus::NotFound => {
+            println!("Draft:   Not found");
+        }
+    }
+
+    println!();
+
+    match report.chain {
+        Ok(_) => {
+            println!("Chain:   ✓ Verified");
+        }
+        Err(e) => {
+            println!("Chain:   ✗ [{}] {}", e.code(), e);
+        }
+    }
+
+    Ok(EXIT_SUCCESS)
+}
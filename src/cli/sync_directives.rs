@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use crate::commands::sync_directives::{run_sync_directives_in_dir, SyncOptions};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: SyncOptions, json: bool) -> CliResult {
+    let report = run_sync_directives_in_dir(Path::new("."), &options)?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    if !options.dry_run {
+        for file in &report.files {
+            println!("{}", file);
+        }
+        return Ok(EXIT_SUCCESS);
+    }
+
+    for file in report.changed() {
+        print!("{}", file.diff);
+    }
+    match report.changed().count() {
+        0 => println!("All directives are up to date."),
+        count => println!(
+            "{} file(s) would change. Run `engram sync-directives` to apply.",
+            count
+        ),
+    }
+    Ok(EXIT_SUCCESS)
+}
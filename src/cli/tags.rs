@@ -0,0 +1,41 @@
+use serde_json::json;
+
+use crate::error::EXIT_SUCCESS;
+use crate::Repository;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(tag: Option<&str>, json: bool) -> CliResult {
+    let repo = Repository::open(".")?;
+
+    let Some(tag) = tag else {
+        let counts = repo.tag_counts()?;
+        if json {
+            let tags: Vec<_> = counts
+                .iter()
+                .map(|(tag, count)| json!({"tag": tag, "count": count}))
+                .collect();
+            print_json(&json!({ "tags": tags }));
+        } else if counts.is_empty() {
+            println!("No tagged entries. Add a `Tags: auth, bugfix` line to the draft.");
+        } else {
+            for (tag, count) in counts {
+                println!("{:>5}  {}", count, tag);
+            }
+        }
+        return Ok(EXIT_SUCCESS);
+    };
+
+    let entries = repo.entries_with_tag(tag)?;
+    if json {
+        let entries: Vec<_> = entries.iter().map(|entry| entry.to_json()).collect();
+        print_json(&json!({ "tag": tag.to_lowercase(), "entries": entries }));
+    } else if entries.is_empty() {
+        println!("No entries tagged '{}'", tag);
+    } else {
+        for entry in entries {
+            println!("{}  {}", entry.filename, entry.content.summary);
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
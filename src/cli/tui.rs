@@ -0,0 +1,8 @@
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::CliResult;
+
+pub fn run() -> CliResult {
+    crate::tui::run(&std::env::current_dir()?)?;
+    Ok(EXIT_SUCCESS)
+}
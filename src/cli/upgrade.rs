@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use crate::commands::upgrade::run_upgrade_in_dir;
+use crate::error::EXIT_SUCCESS;
+use crate::utils::clock;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(json: bool) -> CliResult {
+    let clock = clock::from_env()?;
+    let report = run_upgrade_in_dir(Path::new("."), clock.as_ref())?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!(
+        "Upgrading Engram {} → {}",
+        report.from.as_deref().unwrap_or("(unknown version)"),
+        report.to
+    );
+    for step in &report.steps {
+        if step.changes.is_empty() {
+            println!("✓ {}: nothing to do", step.name);
+            continue;
+        }
+        println!("• {}", step.name);
+        for change in &step.changes {
+            println!("    {}", change);
+        }
+    }
+    for warning in &report.warnings {
+        println!("⚠ {}", warning);
+    }
+
+    if let Some(entry) = &report.entry {
+        println!("Recorded the upgrade in {}", entry);
+    }
+    println!("✓ Installation is at Engram {}", report.to);
+    Ok(EXIT_SUCCESS)
+}
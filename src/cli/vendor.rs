@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use crate::commands::vendor::{run_vendor_in_dir, VendorOptions};
+use crate::error::EXIT_SUCCESS;
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(options: VendorOptions, json: bool) -> CliResult {
+    let binary = std::env::current_exe()?;
+    let report = run_vendor_in_dir(Path::new("."), &binary, &options)?;
+
+    if json {
+        print_json(&report.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!("Vendored Engram {} into {}", report.version, report.path);
+    match &report.verified_asset {
+        Some(asset) => println!("SHA-256: {} (matches {})", report.sha256, asset),
+        None => println!("SHA-256: {}", report.sha256),
+    }
+    Ok(EXIT_SUCCESS)
+}
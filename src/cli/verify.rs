@@ -0,0 +1,144 @@
+use crate::commands::verify::{verify_chain, VerifyError};
+use crate::engram::canonical::{describe, Drift};
+use crate::engram::worklog::DATE_FORMAT;
+use crate::error::{EngramError, EXIT_CHAIN_BROKEN, EXIT_SUCCESS};
+
+use crate::cli::{print_json, CliResult};
+
+pub fn run(json: bool) -> CliResult {
+    let result = match verify_chain() {
+        Ok(result) => result,
+        // Chain failures get a detailed report in text mode
+        Err(EngramError::Chain(error)) if !json => {
+            print_chain_failure(&error);
+            eprintln!();
+            eprintln!("Error code: {}", error.code());
+            return Ok(EXIT_CHAIN_BROKEN);
+        }
+        Err(e) => return Err(e),
+    };
+
+    if json {
+        print_json(&result.to_json());
+        return Ok(EXIT_SUCCESS);
+    }
+
+    println!("✓ Chain verified: {} entries", result.entry_count);
+    if let Some((first_file, first_date)) = result.first_entry {
+        println!("  First: {} ({})", first_file, first_date);
+    }
+    if let Some((latest_file, latest_date)) = result.latest_entry {
+        println!("  Latest: {} ({})", latest_file, latest_date);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn print_chain_failure(error: &VerifyError) {
+    match error {
+        VerifyError::ChainBroken {
+            filename,
+            expected,
+            found,
+        } => {
+            eprintln!("✗ Chain broken at entry {}", filename);
+            eprintln!();
+            eprintln!("Expected Previous: {}", expected);
+            eprintln!("Found Previous:    {}", found);
+            eprintln!();
+            eprintln!("The worklog has been tampered with or corrupted.");
+        }
+        VerifyError::HashMismatch {
+            filename,
+            content_hash,
+            filename_hash,
+        } => {
+            eprintln!("✗ Hash mismatch at {}", filename);
+            eprintln!();
+            eprintln!("Content hashes to: {}", content_hash);
+            eprintln!("Filename claims:   {}", filename_hash);
+            eprintln!();
+            eprintln!("The worklog has been tampered with or corrupted.");
+        }
+        VerifyError::NonCanonical { filename, drift } => {
+            eprintln!("✗ Hash mismatch at {}", filename);
+            eprintln!();
+            eprintln!("The entry differs only by {}.", describe(drift));
+            eprintln!("Its text is intact; the bytes were changed by an editor or checkout.");
+            if *drift == [Drift::Crlf] {
+                eprintln!("Run `engram doctor --fix-eol` to restore LF line endings.");
+            }
+        }
+        VerifyError::InvalidUtf8(filename) => {
+            eprintln!("✗ Invalid entry: {} is not valid UTF-8", filename);
+        }
+        VerifyError::MissingPreviousLine(filename) => {
+            eprintln!("✗ Invalid entry: Missing 'Previous:' line in {}", filename);
+        }
+        VerifyError::SequenceGap {
+            filename,
+            expected,
+            found,
+        } => {
+            eprintln!("✗ Sequence gap at {}", filename);
+            eprintln!();
+            eprintln!("Expected sequence: {:06}", expected);
+            eprintln!("Found sequence:    {:06}", found);
+            eprintln!();
+            eprintln!("An entry is missing from the worklog.");
+        }
+        VerifyError::DuplicateSequence {
+            sequence,
+            filenames,
+        } => {
+            eprintln!("✗ Duplicate sequence {:06}", sequence);
+            eprintln!();
+            for filename in filenames {
+                eprintln!("  {}", filename);
+            }
+            eprintln!();
+            eprintln!("Each sequence number must appear exactly once.");
+        }
+        VerifyError::UnexpectedFile(filename) => {
+            eprintln!("✗ Unexpected file in worklog/: {}", filename);
+            eprintln!();
+            eprintln!("Only SUMMARY.md and NNNNNN_HHHHHHHH.md entries belong in .engram/worklog/.");
+        }
+        VerifyError::InvalidDate { filename, error } => {
+            eprintln!("✗ Invalid entry: {} has {}", filename, error);
+        }
+        VerifyError::DateNotMonotonic {
+            filename,
+            date,
+            previous,
+        } => {
+            eprintln!("✗ Date goes backwards at {}", filename);
+            eprintln!();
+            eprintln!("Entry date:    {}", date.format(DATE_FORMAT));
+            eprintln!("Previous date: {}", previous.format(DATE_FORMAT));
+            eprintln!();
+            eprintln!("The entry was backdated or written with a wrong clock.");
+        }
+        VerifyError::DateInFuture {
+            filename,
+            date,
+            now,
+        } => {
+            eprintln!("✗ Date in the future at {}", filename);
+            eprintln!();
+            eprintln!("Entry date:   {}", date.format(DATE_FORMAT));
+            eprintln!("Current time: {}", now.format(DATE_FORMAT));
+            eprintln!();
+            eprintln!("The entry was future-dated or written with a wrong clock.");
+        }
+        VerifyError::UnresolvedRef {
+            filename,
+            reference,
+        } => {
+            eprintln!("✗ Unresolved reference in {}", filename);
+            eprintln!();
+            eprintln!("Refs: {}", reference);
+            eprintln!();
+            eprintln!("Entry references must name an earlier entry in the chain.");
+        }
+    }
+}
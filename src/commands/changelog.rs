@@ -0,0 +1,542 @@
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::commands::export::{ExportRange, RangeBound};
+use crate::engram::worklog::WorklogEntry;
+use crate::error::EngramError;
+use crate::repository::{Entry, Repository};
+
+const WORKLOG_DIR: &str = ".engram/worklog";
+
+/// Keep a Changelog categories, in the order they are printed
+const CATEGORIES: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+/// Category for entries nothing else classifies
+const DEFAULT_CATEGORY: &str = "Changed";
+
+const DOCUMENT_HEADER: &str = "\
+# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/).
+";
+
+/// Options for `engram changelog`
+#[derive(Debug, Default, Clone)]
+pub struct ChangelogOptions {
+    /// Start at this entry reference or date (inclusive)
+    pub from: Option<String>,
+    /// End at this entry reference or date (inclusive)
+    pub to: Option<String>,
+    /// Start after the last entry committed in this git revision (exclusive)
+    pub from_rev: Option<String>,
+    /// End at the last entry committed in this git revision (inclusive)
+    pub to_rev: Option<String>,
+    /// Section title; "Unreleased" when not set
+    pub version: Option<String>,
+}
+
+/// One changelog line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogItem {
+    pub text: String,
+    pub filename: String,
+}
+
+/// A single changelog section built from worklog entries
+#[derive(Debug)]
+pub struct Changelog {
+    pub version: String,
+    /// Date of the newest entry included, for released versions
+    pub date: Option<String>,
+    /// Non-empty categories in Keep a Changelog order
+    pub groups: Vec<(&'static str, Vec<ChangelogItem>)>,
+}
+
+impl Changelog {
+    /// The `## [version]` heading line
+    pub fn heading(&self) -> String {
+        match &self.date {
+            Some(date) => format!("## [{}] - {}", self.version, date),
+            None => format!("## [{}]", self.version),
+        }
+    }
+
+    /// The section as markdown, starting with its heading
+    pub fn render_section(&self) -> String {
+        let mut section = format!("{}\n", self.heading());
+        for (category, items) in &self.groups {
+            section.push_str(&format!("\n### {}\n\n", category));
+            for item in items {
+                section.push_str(&format!("- {} ({})\n", item.text, item.filename));
+            }
+        }
+        section
+    }
+
+    /// A complete CHANGELOG.md containing only this section
+    pub fn render_document(&self) -> String {
+        format!("{}\n{}", DOCUMENT_HEADER, self.render_section())
+    }
+
+    /// JSON form used by `--json`
+    pub fn to_json(&self) -> Value {
+        let groups: serde_json::Map<String, Value> = self
+            .groups
+            .iter()
+            .map(|(category, items)| {
+                let items: Vec<Value> = items
+                    .iter()
+                    .map(|item| json!({"text": item.text, "filename": item.filename}))
+                    .collect();
+                (category.to_string(), Value::Array(items))
+            })
+            .collect();
+        json!({
+            "version": self.version,
+            "date": self.date,
+            "groups": groups,
+            "markdown": self.render_section(),
+        })
+    }
+}
+
+/// Build a changelog section from the entries between the bounds in `options`.
+///
+/// `from`/`to` take an entry reference (sequence, filename, hash prefix) or a
+/// date and are both inclusive. `from_rev`/`to_rev` take a git revision such
+/// as a release tag, standing for the worklog as committed in it:
+/// `from_rev` v1.0 starts after the last entry v1.0 contains (exclusive),
+/// `to_rev` v1.1 ends at the last entry v1.1 contains (inclusive).
+pub fn build_changelog(
+    base_dir: &Path,
+    options: &ChangelogOptions,
+) -> Result<Changelog, EngramError> {
+    let repo = Repository::open(base_dir)?;
+    let range = ExportRange {
+        from: match (&options.from, &options.from_rev) {
+            (Some(_), Some(_)) => return Err(both_bounds("from")),
+            (Some(reference), None) => Some(resolve_entry_bound(&repo, reference)?),
+            (None, Some(rev)) => Some(RangeBound::Sequence(revision_sequence(&repo, rev)? + 1)),
+            (None, None) => None,
+        },
+        to: match (&options.to, &options.to_rev) {
+            (Some(_), Some(_)) => return Err(both_bounds("to")),
+            (Some(reference), None) => Some(resolve_entry_bound(&repo, reference)?),
+            (None, Some(rev)) => Some(RangeBound::Sequence(revision_sequence(&repo, rev)?)),
+            (None, None) => None,
+        },
+    };
+
+    let entries: Vec<Entry> = repo
+        .entries()?
+        .into_iter()
+        .filter(|entry| range.contains(entry))
+        .collect();
+
+    let mut groups: Vec<(&'static str, Vec<ChangelogItem>)> = CATEGORIES
+        .iter()
+        .map(|category| (*category, Vec::new()))
+        .collect();
+    for entry in &entries {
+        let (category, text) = classify(entry);
+        let group = groups
+            .iter_mut()
+            .find(|(name, _)| *name == category)
+            .expect("classify returns a known category");
+        group.1.push(ChangelogItem {
+            text,
+            filename: entry.filename.trim_end_matches(".md").to_string(),
+        });
+    }
+    groups.retain(|(_, items)| !items.is_empty());
+
+    let version = options
+        .version
+        .clone()
+        .unwrap_or_else(|| "Unreleased".to_string());
+    let date = options
+        .version
+        .as_ref()
+        .and(entries.last())
+        .map(|entry| entry.content.date.format("%Y-%m-%d").to_string());
+
+    Ok(Changelog {
+        version,
+        date,
+        groups,
+    })
+}
+
+/// Insert the section into an existing CHANGELOG.md below any `[Unreleased]`
+/// section and above the newest release, leaving every other byte untouched.
+/// Creates the file if it does not exist.
+pub fn append_to_file(path: &Path, changelog: &Changelog) -> Result<(), EngramError> {
+    if !path.exists() {
+        fs::write(path, changelog.render_document())?;
+        return Ok(());
+    }
+
+    let existing = fs::read_to_string(path)?;
+    let heading = format!("## [{}]", changelog.version);
+    if existing
+        .lines()
+        .any(|line| line == heading || line.starts_with(&format!("{} ", heading)))
+    {
+        return Err(EngramError::Config(format!(
+            "{} already has a section for [{}]",
+            path.display(),
+            changelog.version
+        )));
+    }
+
+    let section = changelog.render_section();
+    let updated = match find_first_release(&existing) {
+        Some(offset) => format!(
+            "{}{}\n{}",
+            &existing[..offset],
+            section,
+            &existing[offset..]
+        ),
+        None => {
+            let separator = if existing.ends_with("\n\n") || existing.is_empty() {
+                ""
+            } else if existing.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            format!("{}{}{}", existing, separator, section)
+        }
+    };
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Byte offset of the first `## ` heading that is not `## [Unreleased]`
+fn find_first_release(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let is_unreleased = line
+            .get(..15)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("## [unreleased]"));
+        if line.starts_with("## ") && !is_unreleased {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn both_bounds(side: &str) -> EngramError {
+    EngramError::Config(format!(
+        "--{} and --{}-rev cannot be used together",
+        side, side
+    ))
+}
+
+/// An entry reference or a date; a number is always a sequence
+fn resolve_entry_bound(repo: &Repository, reference: &str) -> Result<RangeBound, EngramError> {
+    if let Ok(bound) = reference.parse::<RangeBound>() {
+        return Ok(bound);
+    }
+    Ok(RangeBound::Sequence(repo.find_entry(reference)?.sequence))
+}
+
+/// Last entry sequence in git revision `rev`, or a Config error if it is not one
+fn revision_sequence(repo: &Repository, rev: &str) -> Result<u32, EngramError> {
+    last_sequence_at_revision(repo.root(), rev).ok_or_else(|| {
+        EngramError::Config(format!(
+            "'{}' is not a git revision of this repository",
+            rev
+        ))
+    })
+}
+
+/// Highest entry sequence committed in git revision `rev` (0 if it has none).
+/// None when `rev` is not a revision of the repository at `root`.
+fn last_sequence_at_revision(root: &Path, rev: &str) -> Option<u32> {
+    // git would read `-…` as an option rather than a revision
+    if rev.starts_with('-') {
+        return None;
+    }
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "ls-tree",
+            "--name-only",
+            rev,
+            "--",
+            &format!("{}/", WORKLOG_DIR),
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let worklog_dir = root.join(WORKLOG_DIR);
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|path| path.rsplit('/').next())
+            .filter_map(|name| WorklogEntry::from_filename(name, &worklog_dir))
+            .map(|entry| entry.sequence)
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+/// Category and changelog text for an entry: a `Type:` header wins, then the
+/// first tag that names a category, then a conventional-commit prefix in the
+/// summary (`feat(auth): ...`), which is stripped from the text.
+fn classify(entry: &Entry) -> (&'static str, String) {
+    let summary = &entry.content.summary;
+    let prefix = Regex::new(r"^(\w+)(?:\(([^)]*)\))?!?:\s+(.+)$").unwrap();
+    let conventional = prefix.captures(summary).and_then(|caps| {
+        let category = category_for(&caps[1])?;
+        let text = match caps.get(2) {
+            Some(scope) if !scope.as_str().is_empty() => {
+                format!("{}: {}", scope.as_str(), &caps[3])
+            }
+            _ => caps[3].to_string(),
+        };
+        Some((category, text))
+    });
+
+    let from_headers = entry
+        .content
+        .header("Type")
+        .and_then(category_for)
+        .or_else(|| {
+            entry
+                .content
+                .tags()
+                .iter()
+                .find_map(|tag| category_for(tag))
+        });
+
+    match (from_headers, conventional) {
+        (Some(category), Some((_, text))) => (category, text),
+        (Some(category), None) => (category, summary.clone()),
+        (None, Some((category, text))) => (category, text),
+        (None, None) => (DEFAULT_CATEGORY, summary.clone()),
+    }
+}
+
+/// Map a type name, tag or conventional-commit type to a category
+fn category_for(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    if let Some(category) = CATEGORIES.iter().find(|c| c.to_lowercase() == name) {
+        return Some(category);
+    }
+    Some(match name.as_str() {
+        "feat" | "feature" | "add" => "Added",
+        "fix" | "bugfix" | "bug" | "hotfix" => "Fixed",
+        "change" | "refactor" | "perf" | "docs" | "style" | "chore" | "build" | "ci" | "test" => {
+            "Changed"
+        }
+        "deprecate" => "Deprecated",
+        "remove" | "revert" => "Removed",
+        "sec" | "vuln" => "Security",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use crate::utils::clock::FixedClock;
+    use chrono::{TimeZone, Utc};
+    use tempfile::{tempdir, TempDir};
+
+    fn repo_with_entries(summaries: &[&str]) -> TempDir {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        for (day, summary) in summaries.iter().enumerate() {
+            let clock = FixedClock(
+                Utc.with_ymd_and_hms(2025, 6, 10 + day as u32, 9, 0, 0)
+                    .unwrap(),
+            );
+            repo.write_draft(&format!(
+                "<summary>{}</summary>\n\n## Changes\n- Work",
+                summary
+            ))
+            .unwrap();
+            repo.commit_with_clock(&clock).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_groups_by_conventional_prefix() {
+        let dir = repo_with_entries(&[
+            "feat(auth): add login form",
+            "fix: token expiry off by one",
+            "Tidy up the README",
+        ]);
+        let changelog = build_changelog(dir.path(), &ChangelogOptions::default()).unwrap();
+
+        assert_eq!(changelog.heading(), "## [Unreleased]");
+        let section = changelog.render_section();
+        assert!(section.contains("### Added\n\n- auth: add login form (000001_"));
+        assert!(section.contains("### Fixed\n\n- token expiry off by one (000002_"));
+        assert!(section.contains("### Changed\n\n- Tidy up the README (000003_"));
+        assert!(section.find("### Added").unwrap() < section.find("### Changed").unwrap());
+        assert!(section.find("### Changed").unwrap() < section.find("### Fixed").unwrap());
+    }
+
+    #[test]
+    fn test_range_and_version() {
+        let dir = repo_with_entries(&["feat: one", "feat: two", "feat: three"]);
+        let options = ChangelogOptions {
+            from: Some("2".to_string()),
+            to: Some("2025-06-11".to_string()),
+            version: Some("1.1.0".to_string()),
+            ..Default::default()
+        };
+        let changelog = build_changelog(dir.path(), &options).unwrap();
+
+        assert_eq!(changelog.heading(), "## [1.1.0] - 2025-06-11");
+        assert_eq!(
+            changelog.groups,
+            vec![(
+                "Added",
+                vec![ChangelogItem {
+                    text: "two".to_string(),
+                    filename: Repository::open(dir.path())
+                        .unwrap()
+                        .find_entry("2")
+                        .unwrap()
+                        .filename
+                        .trim_end_matches(".md")
+                        .to_string(),
+                }]
+            )]
+        );
+
+        let options = ChangelogOptions {
+            from: Some("no-such-tag".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build_changelog(dir.path(), &options),
+            Err(EngramError::EntryNotFound(_))
+        ));
+
+        let options = ChangelogOptions {
+            from_rev: Some("no-such-tag".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build_changelog(dir.path(), &options),
+            Err(EngramError::Config(_))
+        ));
+
+        let options = ChangelogOptions {
+            to: Some("2".to_string()),
+            to_rev: Some("HEAD".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build_changelog(dir.path(), &options),
+            Err(EngramError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_type_and_tags_headers_win() {
+        let dir = repo_with_entries(&[]);
+        let worklog = dir.path().join(WORKLOG_DIR);
+        let content = crate::engram::worklog::EntryContent {
+            summary: "feat: harden session cookies".to_string(),
+            previous: "none".to_string(),
+            date: Utc.with_ymd_and_hms(2025, 6, 10, 9, 0, 0).unwrap(),
+            headers: vec![("Tags".to_string(), "auth, security".to_string())],
+            body: "## Changes\n- Work".to_string(),
+        }
+        .to_string();
+        let filename = format!("000001_{}.md", crate::utils::hash::sha256_short(&content));
+        fs::write(worklog.join(filename), content).unwrap();
+
+        let changelog = build_changelog(dir.path(), &ChangelogOptions::default()).unwrap();
+        assert_eq!(changelog.groups[0].0, "Security");
+        assert_eq!(changelog.groups[0].1[0].text, "harden session cookies");
+    }
+
+    #[test]
+    fn test_append_keeps_older_sections() {
+        let dir = repo_with_entries(&["feat: new thing"]);
+        let path = dir.path().join("CHANGELOG.md");
+        let older =
+            "# Changelog\n\nIntro.\n\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- Hand-written\n";
+        fs::write(&path, older).unwrap();
+
+        let options = ChangelogOptions {
+            version: Some("1.1.0".to_string()),
+            ..Default::default()
+        };
+        let changelog = build_changelog(dir.path(), &options).unwrap();
+        append_to_file(&path, &changelog).unwrap();
+
+        let updated = fs::read_to_string(&path).unwrap();
+        assert!(updated.starts_with("# Changelog\n\nIntro.\n\n## [1.1.0] - 2025-06-10\n"));
+        assert!(updated.ends_with("\n## [1.0.0] - 2025-01-01\n\n### Added\n\n- Hand-written\n"));
+
+        assert!(matches!(
+            append_to_file(&path, &changelog),
+            Err(EngramError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_append_goes_below_unreleased() {
+        let dir = repo_with_entries(&["feat: new thing"]);
+        let path = dir.path().join("CHANGELOG.md");
+        let options = ChangelogOptions {
+            version: Some("1.1.0".to_string()),
+            ..Default::default()
+        };
+        let changelog = build_changelog(dir.path(), &options).unwrap();
+
+        let unreleased = "# Changelog\n\n## [Unreleased]\n\n- Pending\n\n";
+        let older = "## [1.0.0] - 2025-01-01\n\n- Hand-written\n";
+        fs::write(&path, format!("{}{}", unreleased, older)).unwrap();
+        append_to_file(&path, &changelog).unwrap();
+        let updated = fs::read_to_string(&path).unwrap();
+        assert!(updated.starts_with(&format!("{}## [1.1.0] - 2025-06-10\n", unreleased)));
+        assert!(updated.ends_with(&format!("\n{}", older)));
+
+        // With no release yet the section follows the Unreleased one
+        fs::write(&path, unreleased).unwrap();
+        append_to_file(&path, &changelog).unwrap();
+        let updated = fs::read_to_string(&path).unwrap();
+        assert!(updated.starts_with(&format!("{}## [1.1.0] - 2025-06-10\n", unreleased)));
+    }
+
+    #[test]
+    fn test_append_creates_document() {
+        let dir = repo_with_entries(&["fix: a bug"]);
+        let path = dir.path().join("CHANGELOG.md");
+        let changelog = build_changelog(dir.path(), &ChangelogOptions::default()).unwrap();
+        append_to_file(&path, &changelog).unwrap();
+
+        let created = fs::read_to_string(&path).unwrap();
+        assert!(created.starts_with("# Changelog\n"));
+        assert!(created.contains("## [Unreleased]\n\n### Fixed\n\n- a bug ("));
+    }
+}
@@ -1,12 +1,19 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
 use std::fs;
 use std::io;
 use std::path::Path;
 
-use crate::engram::draft::Draft;
-use crate::engram::summary::append_entry;
-use crate::engram::worklog::{EntryContent, WorklogEntry};
+use crate::commands::verify::collect_entries;
+use crate::engram::canonical::normalize;
+use crate::engram::chain::parse_date;
+use crate::engram::draft::{Draft, DraftError};
+use crate::engram::refs::{Reference, REFS_HEADER};
+use crate::engram::summary::{append_entry, row_text};
+use crate::engram::worklog::{EntryContent, WorklogEntry, TAGS_HEADER, TYPE_HEADER};
+use crate::error::EngramError;
 use crate::templates::DRAFT_TEMPLATE;
+use crate::utils::clock::Clock;
 use crate::utils::hash::{sha256_hex, sha256_short};
 
 const ENGRAM_DIR: &str = ".engram";
@@ -22,62 +29,25 @@ pub struct CommitResult {
     pub previous: String,
 }
 
-pub fn run() -> io::Result<()> {
-    let result = run_commit()?;
-
-    // Output
-    let prev_display = if result.previous == "none" {
-        "none".to_string()
-    } else {
-        format!("{}...", &result.previous[..8])
-    };
-
-    println!("Committed: {}", result.filename);
-    println!("Summary: {}", result.summary);
-    println!("Previous: {}", prev_display);
-
-    Ok(())
-}
-
-/// Internal commit logic that can be tested
-fn run_commit() -> io::Result<CommitResult> {
-    run_commit_in_dir(Path::new("."))
+impl CommitResult {
+    /// JSON form used by `--json` and the MCP server
+    pub fn to_json(&self) -> Value {
+        json!({
+            "filename": self.filename,
+            "summary": self.summary,
+            "previous": self.previous,
+        })
+    }
 }
 
-/// Commit logic with configurable base directory for testing
-fn run_commit_in_dir(base_dir: &Path) -> io::Result<CommitResult> {
-    let engram_dir = base_dir.join(ENGRAM_DIR);
+/// Commit the draft in `base_dir`, dating the entry with `clock`
+pub fn run_commit_in_dir(base_dir: &Path, clock: &dyn Clock) -> Result<CommitResult, EngramError> {
     let draft_file = base_dir.join(DRAFT_FILE);
     let worklog_dir = base_dir.join(WORKLOG_DIR);
     let summary_file = base_dir.join(SUMMARY_FILE);
 
-    // 1. Validate environment
-    if !engram_dir.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "Engram not initialized. Run `engram init` first.",
-        ));
-    }
-
-    if !draft_file.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "draft.md not found",
-        ));
-    }
-
-    // 2. Validate worklog directory exists
-    if !worklog_dir.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "Engram not initialized. Run `engram init` first.",
-        ));
-    }
-
-    // 3. Parse draft.md
-    let draft_content = fs::read_to_string(&draft_file)?;
-    let draft = Draft::parse(&draft_content)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    // 1-3. Validate the environment and parse draft.md
+    let draft = validate_draft_in_dir(base_dir)?;
 
     // 4. Determine sequence number
     let sequence = get_next_sequence(&worklog_dir)?;
@@ -85,27 +55,43 @@ fn run_commit_in_dir(base_dir: &Path) -> io::Result<CommitResult> {
     // 5. Compute previous hash
     let prev_hash = get_previous_hash(&worklog_dir, sequence)?;
 
-    // 5. Build entry content
+    // 6. Refuse to write an entry dated before its predecessor
+    let date = clock.now();
+    if let Some(prev_date) = get_previous_date(&worklog_dir, sequence)? {
+        if date < prev_date {
+            return Err(EngramError::ClockBehind {
+                now: date,
+                latest: prev_date,
+            });
+        }
+    }
+
+    // 7. Build entry content
     let entry = EntryContent {
         summary: draft.summary.clone(),
         previous: prev_hash.clone(),
-        date: Utc::now(),
+        date,
+        headers: entry_headers(&draft),
         body: draft.body.clone(),
     };
     let entry_content = entry.to_string();
 
-    // 6. Compute content hash
+    // 8. Compute content hash
     let short_hash = sha256_short(&entry_content);
 
-    // 8. Write entry file
+    // 9. Write entry file
     let filename = format!("{:06}_{}.md", sequence, short_hash);
     let entry_path = worklog_dir.join(&filename);
     fs::write(&entry_path, &entry_content)?;
 
-    // 8. Append to SUMMARY.md
-    append_entry(&summary_file, &filename, &draft.summary)?;
+    // 10. Append to SUMMARY.md
+    append_entry(
+        &summary_file,
+        &filename,
+        &row_text(&draft.summary, &draft.tags),
+    )?;
 
-    // 9. Reset draft.md
+    // 11. Reset draft.md
     fs::write(&draft_file, DRAFT_TEMPLATE)?;
 
     Ok(CommitResult {
@@ -115,6 +101,41 @@ fn run_commit_in_dir(base_dir: &Path) -> io::Result<CommitResult> {
     })
 }
 
+/// Check the draft in `base_dir` exactly as commit does, without writing
+/// anything
+pub fn validate_draft_in_dir(base_dir: &Path) -> Result<Draft, EngramError> {
+    let engram_dir = base_dir.join(ENGRAM_DIR);
+    let draft_file = base_dir.join(DRAFT_FILE);
+    let worklog_dir = base_dir.join(WORKLOG_DIR);
+    let summary_file = base_dir.join(SUMMARY_FILE);
+
+    // 1. Validate environment
+    if !engram_dir.exists() {
+        return Err(EngramError::NotInitialized);
+    }
+
+    if !draft_file.exists() {
+        return Err(EngramError::DraftMissing);
+    }
+
+    // 2. Validate worklog directory and index exist
+    if !worklog_dir.exists() {
+        return Err(EngramError::NotInitialized);
+    }
+
+    if !summary_file.exists() {
+        return Err(EngramError::SummaryMissing);
+    }
+
+    // 3. Parse draft.md (normalized to LF without BOM so the entry is canonical)
+    let draft_bytes = normalize(&fs::read(&draft_file)?);
+    let draft_content = String::from_utf8(draft_bytes).map_err(|_| DraftError::InvalidUtf8)?;
+    let draft = Draft::parse(&draft_content)?;
+    check_entry_refs(&worklog_dir, &draft.refs)?;
+
+    Ok(draft)
+}
+
 /// Get the next sequence number by finding the highest existing entry
 fn get_next_sequence(history_path: &Path) -> io::Result<u32> {
     if !history_path.exists() {
@@ -138,6 +159,70 @@ fn get_next_sequence(history_path: &Path) -> io::Result<u32> {
     Ok(max_sequence + 1)
 }
 
+/// Extra headers recorded from the draft: Tags, Type, then Refs
+fn entry_headers(draft: &Draft) -> Vec<(String, String)> {
+    let mut headers = Vec::new();
+    if !draft.tags.is_empty() {
+        headers.push((TAGS_HEADER.to_string(), draft.tags.join(", ")));
+    }
+    if let Some(change_type) = &draft.change_type {
+        headers.push((TYPE_HEADER.to_string(), change_type.clone()));
+    }
+    if !draft.refs.is_empty() {
+        let refs: Vec<&str> = draft.refs.iter().map(Reference::as_str).collect();
+        headers.push((REFS_HEADER.to_string(), refs.join(", ")));
+    }
+    headers
+}
+
+/// Every entry hash in `Refs:` must belong to an already committed entry
+fn check_entry_refs(worklog_dir: &Path, refs: &[Reference]) -> Result<(), EngramError> {
+    let hashes: Vec<&String> = refs
+        .iter()
+        .filter_map(|reference| match reference {
+            Reference::Entry(hash) => Some(hash),
+            Reference::External(_) => None,
+        })
+        .collect();
+    if hashes.is_empty() {
+        return Ok(());
+    }
+
+    let mut committed = Vec::new();
+    for entry in collect_entries(worklog_dir)? {
+        committed.push(sha256_hex(&fs::read(&entry.path)?));
+    }
+    match hashes.into_iter().find(|hash| !committed.contains(hash)) {
+        Some(unknown) => Err(DraftError::UnknownRef(unknown.clone()).into()),
+        None => Ok(()),
+    }
+}
+
+/// Get the date of the previous entry (None for the first entry or an unparseable date)
+fn get_previous_date(
+    history_path: &Path,
+    current_sequence: u32,
+) -> io::Result<Option<DateTime<Utc>>> {
+    if current_sequence == 1 {
+        return Ok(None);
+    }
+
+    let prev_sequence = current_sequence - 1;
+    for entry in fs::read_dir(history_path)? {
+        let filename = entry?.file_name();
+        if let Some(worklog_entry) =
+            WorklogEntry::from_filename(&filename.to_string_lossy(), history_path)
+        {
+            if worklog_entry.sequence == prev_sequence {
+                let content = String::from_utf8_lossy(&fs::read(&worklog_entry.path)?).to_string();
+                return Ok(parse_date(&content).ok());
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 /// Get the hash of the previous entry (or "none" if this is the first entry)
 fn get_previous_hash(history_path: &Path, current_sequence: u32) -> io::Result<String> {
     if current_sequence == 1 {
@@ -154,8 +239,8 @@ fn get_previous_hash(history_path: &Path, current_sequence: u32) -> io::Result<S
 
         if let Some(worklog_entry) = WorklogEntry::from_filename(&filename_str, history_path) {
             if worklog_entry.sequence == prev_sequence {
-                // Read the file content and compute its hash
-                let content = fs::read_to_string(&worklog_entry.path)?;
+                // Hash the exact bytes on disk, as verify does
+                let content = fs::read(&worklog_entry.path)?;
                 return Ok(sha256_hex(&content));
             }
         }
@@ -171,6 +256,7 @@ fn get_previous_hash(history_path: &Path, current_sequence: u32) -> io::Result<S
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::clock::{FixedClock, SystemClock};
     use std::fs;
     use tempfile::tempdir;
 
@@ -230,11 +316,9 @@ mod tests {
         let dir = tempdir().unwrap();
         // Don't create .engram directory
 
-        let result = run_commit_in_dir(dir.path());
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert_eq!(err.kind(), io::ErrorKind::NotFound);
-        assert!(err.to_string().contains("not initialized"));
+        let result = run_commit_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(result, Err(EngramError::NotInitialized)));
+        assert!(result.unwrap_err().to_string().contains("not initialized"));
     }
 
     #[test]
@@ -244,11 +328,9 @@ mod tests {
         fs::create_dir(dir.path().join(".engram")).unwrap();
         fs::create_dir(dir.path().join(".engram/worklog")).unwrap();
 
-        let result = run_commit_in_dir(dir.path());
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert_eq!(err.kind(), io::ErrorKind::NotFound);
-        assert!(err.to_string().contains("draft.md"));
+        let result = run_commit_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(result, Err(EngramError::DraftMissing)));
+        assert!(result.unwrap_err().to_string().contains("draft.md"));
     }
 
     #[test]
@@ -260,10 +342,11 @@ mod tests {
         let draft_content = "<summary></summary>\n\n## Intent\nSome content here";
         fs::write(dir.path().join(".engram/draft.md"), draft_content).unwrap();
 
-        let result = run_commit_in_dir(dir.path());
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let result = run_commit_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(
+            result,
+            Err(EngramError::Draft(DraftError::EmptySummary))
+        ));
     }
 
     #[test]
@@ -275,10 +358,11 @@ mod tests {
         let draft_content = "<summary>Test summary</summary>\n\n<!-- just comments -->";
         fs::write(dir.path().join(".engram/draft.md"), draft_content).unwrap();
 
-        let result = run_commit_in_dir(dir.path());
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let result = run_commit_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(
+            result,
+            Err(EngramError::Draft(DraftError::EmptyBody))
+        ));
     }
 
     #[test]
@@ -299,7 +383,7 @@ Setting up the project
 Compiled successfully"#;
         fs::write(dir.path().join(".engram/draft.md"), draft_content).unwrap();
 
-        let result = run_commit_in_dir(dir.path());
+        let result = run_commit_in_dir(dir.path(), &SystemClock);
         assert!(result.is_ok());
 
         let commit_result = result.unwrap();
@@ -356,7 +440,7 @@ Adding more features
 Tests pass"#;
         fs::write(dir.path().join(".engram/draft.md"), draft_content).unwrap();
 
-        let result = run_commit_in_dir(dir.path());
+        let result = run_commit_in_dir(dir.path(), &SystemClock);
         assert!(result.is_ok());
 
         let commit_result = result.unwrap();
@@ -374,6 +458,77 @@ Tests pass"#;
         assert!(entry_content.contains(&format!("Previous: {}", sha256_hex(first_entry_content))));
     }
 
+    #[test]
+    fn test_commit_uses_injected_clock() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        fs::write(
+            dir.path().join(".engram/draft.md"),
+            "<summary>Pinned</summary>\n\n## Intent\nReproducible",
+        )
+        .unwrap();
+
+        let pinned = DateTime::parse_from_rfc3339("2025-06-12T14:32:07Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let commit_result = run_commit_in_dir(dir.path(), &FixedClock(pinned)).unwrap();
+
+        let entry_content = fs::read_to_string(
+            dir.path()
+                .join(".engram/worklog")
+                .join(&commit_result.filename),
+        )
+        .unwrap();
+        assert!(entry_content.contains("Date: 2025-06-12T14:32:07Z"));
+    }
+
+    #[test]
+    fn test_commit_refuses_clock_behind_latest_entry() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        fs::write(
+            dir.path().join(".engram/worklog/000001_a1b2c3d4.md"),
+            "Summary: First\nPrevious: none\nDate: 2025-06-12T14:32:07Z\n\n---\n\nFirst body",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".engram/draft.md"),
+            "<summary>Second</summary>\n\n## Intent\nBackdated",
+        )
+        .unwrap();
+
+        let earlier = DateTime::parse_from_rfc3339("2025-06-11T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let err = run_commit_in_dir(dir.path(), &FixedClock(earlier)).unwrap_err();
+        assert!(matches!(err, EngramError::ClockBehind { .. }));
+        assert!(err.to_string().contains("Clock is behind"));
+
+        // Draft is left untouched so the work is not lost
+        let draft = fs::read_to_string(dir.path().join(".engram/draft.md")).unwrap();
+        assert!(draft.contains("Backdated"));
+    }
+
+    #[test]
+    fn test_commit_normalizes_crlf_draft() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        let draft_content = "\u{feff}<summary>Windows draft</summary>\r\n\r\n## Intent\r\nFrom an editor using CRLF\r\n";
+        fs::write(dir.path().join(".engram/draft.md"), draft_content).unwrap();
+
+        let commit_result = run_commit_in_dir(dir.path(), &SystemClock).unwrap();
+        assert_eq!(commit_result.summary, "Windows draft");
+
+        let entry_path = dir
+            .path()
+            .join(".engram/worklog")
+            .join(&commit_result.filename);
+        let bytes = fs::read(&entry_path).unwrap();
+        assert!(!bytes.contains(&b'\r'));
+        assert!(commit_result.filename.contains(&sha256_short(&bytes)));
+    }
+
     /// Helper to set up a valid .engram directory structure for testing
     fn setup_engram_dir(base: &Path) {
         use crate::templates::SUMMARY_TEMPLATE;
@@ -0,0 +1,475 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::directives::{
+    remove_directive, remove_from_read_list, ReadListEdit, DIRECTIVE_TARGETS,
+};
+use crate::commands::wrappers::{generated_wrapper, WrapperKind};
+use crate::error::EngramError;
+use crate::utils::tar;
+
+const ENGRAM_DIR: &str = ".engram";
+
+/// Options for `engram deinit`
+#[derive(Debug, Clone, Default)]
+pub struct DeinitOptions {
+    /// Report what would be removed without touching anything
+    pub dry_run: bool,
+    /// Write `.engram/` to this tar archive before removing it
+    pub archive: Option<PathBuf>,
+    /// Delete `.engram/` without an archive
+    pub yes: bool,
+}
+
+/// One change made (or, in a dry run, planned) by `deinit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeinitAction {
+    /// The Engram directive was cut out of a file that has other content
+    RemovedDirective(String),
+    /// A file or directory that only held Engram content was deleted
+    Deleted(String),
+    /// Another file was edited to drop its reference to Engram
+    Updated { path: String, change: String },
+    /// `.engram/` was written to a tar archive
+    Archived { path: String, archive: String },
+    /// A file Engram created was left in place
+    Kept { path: String, reason: &'static str },
+}
+
+impl std::fmt::Display for DeinitAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeinitAction::RemovedDirective(path) => write!(f, "Removed directive: {}", path),
+            DeinitAction::Deleted(path) => write!(f, "Deleted: {}", path),
+            DeinitAction::Updated { path, change } => write!(f, "Updated: {} ({})", path, change),
+            DeinitAction::Archived { path, archive } => {
+                write!(f, "Archived: {} -> {}", path, archive)
+            }
+            DeinitAction::Kept { path, reason } => write!(f, "Kept: {} ({})", path, reason),
+        }
+    }
+}
+
+impl DeinitAction {
+    pub fn to_json(&self) -> Value {
+        match self {
+            DeinitAction::RemovedDirective(path) => {
+                json!({ "action": "removed_directive", "path": path })
+            }
+            DeinitAction::Deleted(path) => json!({ "action": "deleted", "path": path }),
+            DeinitAction::Updated { path, change } => {
+                json!({ "action": "updated", "path": path, "change": change })
+            }
+            DeinitAction::Archived { path, archive } => {
+                json!({ "action": "archived", "path": path, "archive": archive })
+            }
+            DeinitAction::Kept { path, reason } => {
+                json!({ "action": "kept", "path": path, "reason": reason })
+            }
+        }
+    }
+}
+
+/// Result of `deinit`
+#[derive(Debug, Clone)]
+pub struct DeinitReport {
+    pub dry_run: bool,
+    pub actions: Vec<DeinitAction>,
+}
+
+impl DeinitReport {
+    pub fn to_json(&self) -> Value {
+        let actions: Vec<_> = self.actions.iter().map(DeinitAction::to_json).collect();
+        json!({
+            "dry_run": self.dry_run,
+            "actions": actions,
+        })
+    }
+}
+
+/// Undo `engram init` under `root`: cut the directive out of every agent
+/// file, delete the wrappers unless they were customized, and remove
+/// `.engram/`. The worklog is only deleted once it has been archived or the
+/// caller confirmed with `yes`; the archive is written before anything else
+/// changes.
+pub fn run_deinit_in_dir(
+    root: &Path,
+    options: &DeinitOptions,
+) -> Result<DeinitReport, EngramError> {
+    let engram_dir = root.join(ENGRAM_DIR);
+    if !engram_dir.exists() {
+        return Err(EngramError::NotInitialized);
+    }
+
+    if !options.dry_run && options.archive.is_none() && !options.yes {
+        return Err(EngramError::Config(
+            "deinit deletes the worklog; pass --archive <FILE> to keep a copy or --yes to delete it"
+                .to_string(),
+        ));
+    }
+
+    // Check the archive destination before changing anything
+    let archive = options.archive.as_ref().map(|archive| root.join(archive));
+    if let Some(archive) = &archive {
+        if archive.exists() {
+            return Err(EngramError::Config(format!(
+                "archive {} already exists",
+                archive.display()
+            )));
+        }
+        // Compare resolved paths so `./.engram/x.tar` or a symlinked parent
+        // cannot put the archive inside the directory it copies. A missing
+        // parent fails when the archive is created, before anything changes.
+        let parent = archive.parent().unwrap_or(root).canonicalize();
+        if let (Ok(parent), Some(file_name)) = (parent, archive.file_name()) {
+            if parent
+                .join(file_name)
+                .starts_with(engram_dir.canonicalize()?)
+            {
+                return Err(EngramError::Config(
+                    "the archive must be written outside .engram/".to_string(),
+                ));
+            }
+        }
+    }
+
+    let mut actions = Vec::new();
+    if let (Some(archive), Some(name)) = (&archive, &options.archive) {
+        if !options.dry_run {
+            write_archive(archive, &engram_dir)?;
+        }
+        actions.push(DeinitAction::Archived {
+            path: format!("{}/", ENGRAM_DIR),
+            archive: name.display().to_string(),
+        });
+    }
+
+    remove_directives(root, options.dry_run, &mut actions)?;
+    remove_wrappers(root, options.dry_run, &mut actions)?;
+
+    if !options.dry_run {
+        fs::remove_dir_all(&engram_dir)?;
+    }
+    actions.push(DeinitAction::Deleted(format!("{}/", ENGRAM_DIR)));
+
+    Ok(DeinitReport {
+        dry_run: options.dry_run,
+        actions,
+    })
+}
+
+/// Write `engram_dir` to a new tar file and flush it to disk. A partly
+/// written archive is removed.
+fn write_archive(archive: &Path, engram_dir: &Path) -> Result<(), EngramError> {
+    let mut file = fs::File::create_new(archive)?;
+    let written =
+        tar::write_dir(&mut file, engram_dir, ENGRAM_DIR, &[]).and_then(|()| file.sync_all());
+    drop(file);
+    if let Err(error) = written {
+        let _ = fs::remove_file(archive);
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Remove the directive from each agent file, deleting files that held
+/// nothing else
+fn remove_directives(
+    root: &Path,
+    dry_run: bool,
+    actions: &mut Vec<DeinitAction>,
+) -> Result<(), EngramError> {
+    for target in DIRECTIVE_TARGETS {
+        let relative = target.relative_path(root);
+        let path = root.join(relative);
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(remaining) = remove_directive(&content) else {
+            continue;
+        };
+
+        if !target.is_scaffolding(&remaining) {
+            if !dry_run {
+                fs::write(&path, remaining)?;
+            }
+            actions.push(DeinitAction::RemovedDirective(relative.to_string()));
+            continue;
+        }
+
+        if !dry_run {
+            fs::remove_file(&path)?;
+            remove_empty_parents(root, &path)?;
+        }
+        actions.push(DeinitAction::Deleted(relative.to_string()));
+
+        // The tool's config only needs to read the file while it exists
+        if let Some(config) = target.read_list {
+            let config_path = root.join(config);
+            let Ok(config_content) = fs::read_to_string(&config_path) else {
+                continue;
+            };
+            let updated = match remove_from_read_list(&config_content, relative) {
+                ReadListEdit::Updated(updated) => updated,
+                ReadListEdit::Unchanged => continue,
+                ReadListEdit::Unsupported => {
+                    actions.push(DeinitAction::Kept {
+                        path: config.to_string(),
+                        reason: "read: is not a plain list; remove the entry by hand",
+                    });
+                    continue;
+                }
+            };
+            if updated.trim().is_empty() {
+                if !dry_run {
+                    fs::remove_file(&config_path)?;
+                }
+                actions.push(DeinitAction::Deleted(config.to_string()));
+            } else {
+                if !dry_run {
+                    fs::write(&config_path, updated)?;
+                }
+                actions.push(DeinitAction::Updated {
+                    path: config.to_string(),
+                    change: format!("removed {} from read", relative),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Delete the wrappers when they are exactly what `init` wrote
+fn remove_wrappers(
+    root: &Path,
+    dry_run: bool,
+    actions: &mut Vec<DeinitAction>,
+) -> Result<(), EngramError> {
+    for kind in WrapperKind::ALL {
+        let name = kind.file_name();
+        let path = root.join(name);
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if generated_wrapper(kind, &content).is_none() {
+            actions.push(DeinitAction::Kept {
+                path: name.to_string(),
+                reason: "has local changes",
+            });
+            continue;
+        }
+        if !dry_run {
+            fs::remove_file(&path)?;
+        }
+        actions.push(DeinitAction::Deleted(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Remove directories `init` created for `path` that are now empty
+/// (`.cursor/rules/`). Top-level directories such as `.cursor/` or
+/// `.github/` are what selected the tool, so they stay.
+fn remove_empty_parents(root: &Path, path: &Path) -> std::io::Result<()> {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current.parent() == Some(root)
+            || current == root
+            || fs::read_dir(current)?.next().is_some()
+        {
+            break;
+        }
+        fs::remove_dir(current)?;
+        dir = current.parent();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_deinit_requires_init() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            run_deinit_in_dir(dir.path(), &DeinitOptions::default()),
+            Err(EngramError::NotInitialized)
+        ));
+    }
+
+    fn confirmed() -> DeinitOptions {
+        DeinitOptions {
+            yes: true,
+            ..DeinitOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_deinit_needs_archive_or_confirmation() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+
+        assert!(matches!(
+            run_deinit_in_dir(dir.path(), &DeinitOptions::default()),
+            Err(EngramError::Config(_))
+        ));
+        assert!(dir.path().join(".engram").exists());
+        assert!(dir.path().join("engram").exists());
+    }
+
+    #[test]
+    fn test_failed_archive_changes_nothing() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let options = DeinitOptions {
+            archive: Some(PathBuf::from("missing/engram.tar")),
+            ..DeinitOptions::default()
+        };
+
+        assert!(matches!(
+            run_deinit_in_dir(dir.path(), &options),
+            Err(EngramError::Io(_))
+        ));
+        assert!(dir.path().join("AGENTS.md").exists());
+        assert!(dir.path().join("engram").exists());
+        assert!(dir.path().join("engram.cmd").exists());
+        assert!(dir.path().join(".engram").exists());
+    }
+
+    #[test]
+    fn test_archive_inside_engram_is_refused() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+
+        for archive in ["./.engram/x.tar", ".engram/worklog/../x.tar"] {
+            let options = DeinitOptions {
+                archive: Some(PathBuf::from(archive)),
+                ..DeinitOptions::default()
+            };
+            assert!(matches!(
+                run_deinit_in_dir(dir.path(), &options),
+                Err(EngramError::Config(_))
+            ));
+        }
+        assert!(!dir.path().join(".engram/x.tar").exists());
+        assert!(dir.path().join(".engram/worklog").exists());
+        assert!(dir.path().join("AGENTS.md").exists());
+    }
+
+    #[test]
+    fn test_deinit_restores_existing_files_and_removes_created_ones() {
+        let dir = tempdir().unwrap();
+        let original = "# My Warp\n\nSome existing content.\n";
+        fs::write(dir.path().join("WARP.md"), original).unwrap();
+        fs::create_dir(dir.path().join(".cursor")).unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        assert!(dir.path().join(".cursor/rules/engram.mdc").exists());
+
+        let report = run_deinit_in_dir(dir.path(), &confirmed()).unwrap();
+
+        assert!(report
+            .actions
+            .contains(&DeinitAction::RemovedDirective("WARP.md".to_string())));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("WARP.md")).unwrap(),
+            original
+        );
+        assert!(!dir.path().join(".cursor/rules").exists());
+        assert!(dir.path().join(".cursor").exists());
+        assert!(!dir.path().join("engram").exists());
+        assert!(!dir.path().join("engram.cmd").exists());
+        assert!(!dir.path().join(".engram").exists());
+    }
+
+    #[test]
+    fn test_deinit_keeps_customized_wrapper() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let wrapper = dir.path().join("engram");
+        let customized = fs::read_to_string(&wrapper).unwrap() + "# local tweak\n";
+        fs::write(&wrapper, &customized).unwrap();
+
+        let report = run_deinit_in_dir(dir.path(), &confirmed()).unwrap();
+
+        assert!(report.actions.contains(&DeinitAction::Kept {
+            path: "engram".to_string(),
+            reason: "has local changes",
+        }));
+        assert_eq!(fs::read_to_string(&wrapper).unwrap(), customized);
+        assert!(!dir.path().join("engram.cmd").exists());
+    }
+
+    #[test]
+    fn test_deinit_removes_aider_read_entry() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".aider.conf.yml"), "model: gpt\n").unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+
+        run_deinit_in_dir(dir.path(), &confirmed()).unwrap();
+
+        assert!(!dir.path().join("CONVENTIONS.md").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".aider.conf.yml")).unwrap(),
+            "model: gpt\n"
+        );
+    }
+
+    #[test]
+    fn test_dry_run_changes_nothing() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let options = DeinitOptions {
+            dry_run: true,
+            archive: Some(PathBuf::from("engram.tar")),
+            yes: false,
+        };
+
+        let report = run_deinit_in_dir(dir.path(), &options).unwrap();
+
+        assert_eq!(
+            report.actions,
+            vec![
+                DeinitAction::Archived {
+                    path: ".engram/".to_string(),
+                    archive: "engram.tar".to_string(),
+                },
+                DeinitAction::Deleted("AGENTS.md".to_string()),
+                DeinitAction::Deleted("engram".to_string()),
+                DeinitAction::Deleted("engram.cmd".to_string()),
+                DeinitAction::Deleted(".engram/".to_string()),
+            ]
+        );
+        assert!(dir.path().join("AGENTS.md").exists());
+        assert!(dir.path().join(".engram").exists());
+        assert!(!dir.path().join("engram.tar").exists());
+    }
+
+    #[test]
+    fn test_archive_keeps_the_worklog() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let options = DeinitOptions {
+            dry_run: false,
+            archive: Some(PathBuf::from("engram.tar")),
+            yes: false,
+        };
+
+        run_deinit_in_dir(dir.path(), &options).unwrap();
+
+        let archive = fs::read(dir.path().join("engram.tar")).unwrap();
+        let listing = String::from_utf8_lossy(&archive);
+        assert!(listing.contains(".engram/worklog/SUMMARY.md"));
+        assert!(!dir.path().join(".engram").exists());
+
+        // An existing archive is never overwritten
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        assert!(matches!(
+            run_deinit_in_dir(dir.path(), &options),
+            Err(EngramError::Config(_))
+        ));
+        assert!(dir.path().join(".engram").exists());
+    }
+}
@@ -0,0 +1,935 @@
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::commands::init::{InitAction, ENGRAM_MARKER};
+use crate::error::EngramError;
+use crate::templates::ROOT_DIRECTIVE_TEMPLATE;
+
+/// Opening marker of the directive block, followed by ` v<version> -->`
+const BLOCK_BEGIN: &str = "<!-- engram:begin";
+/// Closing marker of the directive block
+const BLOCK_END: &str = "<!-- engram:end -->";
+
+/// Front matter for the Cursor rule, so Cursor attaches it to every request
+const CURSOR_FRONT_MATTER: &str = "\
+---
+description: Engram protocol for recording work in .engram/
+globs:
+alwaysApply: true
+---
+";
+
+/// How a tool expects its instruction file to be laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveFormat {
+    /// Markdown; new files start with `header`, existing files get the
+    /// directive after their first level-1 heading
+    Markdown { header: &'static str },
+    /// A rules file with no heading of its own
+    Plain,
+    /// A Cursor `.mdc` rule: YAML front matter followed by the directive
+    CursorRule,
+}
+
+/// One agent instruction file `init` can write the Engram directive to
+#[derive(Debug)]
+pub struct DirectiveTarget {
+    /// Flag name (`--cursor`) and identifier in reports
+    pub name: &'static str,
+    /// File that receives the directive, relative to the project root
+    pub path: &'static str,
+    /// File used instead when `path` is a directory (`.clinerules/`)
+    pub dir_path: Option<&'static str>,
+    pub format: DirectiveFormat,
+    /// Paths whose presence selects this target when `init` runs without flags
+    pub detect: &'static [&'static str],
+    /// YAML config whose `read:` list must name `path` (Aider)
+    pub read_list: Option<&'static str>,
+}
+
+/// Every supported target, in the order `init` writes them
+pub const DIRECTIVE_TARGETS: &[DirectiveTarget] = &[
+    DirectiveTarget {
+        name: "warp",
+        path: "WARP.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# Warp AI Instructions",
+        },
+        detect: &["WARP.md"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "claude",
+        path: "CLAUDE.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# Claude AI Instructions",
+        },
+        detect: &["CLAUDE.md"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "junie",
+        path: ".junie/guidelines.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# Junie AI Guidelines",
+        },
+        detect: &[".junie"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "agents",
+        path: "AGENTS.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# AI Agent Instructions",
+        },
+        // Written when nothing else is detected
+        detect: &[],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "cursor",
+        path: ".cursor/rules/engram.mdc",
+        dir_path: None,
+        format: DirectiveFormat::CursorRule,
+        detect: &[".cursor", ".cursorrules"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "copilot",
+        path: ".github/copilot-instructions.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# Copilot Instructions",
+        },
+        detect: &[".github/copilot-instructions.md"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "windsurf",
+        path: ".windsurfrules",
+        dir_path: None,
+        format: DirectiveFormat::Plain,
+        detect: &[".windsurfrules", ".windsurf"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "cline",
+        path: ".clinerules",
+        dir_path: Some(".clinerules/engram.md"),
+        format: DirectiveFormat::Plain,
+        detect: &[".clinerules"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "gemini",
+        path: "GEMINI.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# Gemini Instructions",
+        },
+        detect: &["GEMINI.md", ".gemini"],
+        read_list: None,
+    },
+    DirectiveTarget {
+        name: "aider",
+        path: "CONVENTIONS.md",
+        dir_path: None,
+        format: DirectiveFormat::Markdown {
+            header: "# Conventions",
+        },
+        detect: &[".aider.conf.yml"],
+        read_list: Some(".aider.conf.yml"),
+    },
+];
+
+/// Target written when detection finds no agent files
+pub const FALLBACK_TARGET: &str = "agents";
+
+/// Where a file stands relative to the current directive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveState {
+    /// No Engram directive in the file
+    Missing,
+    /// The versioned block matches the current template
+    Current,
+    /// The versioned block was written from an older template
+    Stale,
+    /// An unmarked directive section from a release before the markers
+    Legacy,
+}
+
+/// The directive between versioned begin/end markers, ending with a blank
+/// line like the bare template
+pub fn directive_block() -> String {
+    format!("{}\n", marked_block())
+}
+
+fn marked_block() -> String {
+    format!(
+        "{} v{} -->\n{}\n{}\n",
+        BLOCK_BEGIN,
+        env!("CARGO_PKG_VERSION"),
+        ROOT_DIRECTIVE_TEMPLATE.trim_end(),
+        BLOCK_END
+    )
+}
+
+/// Replace the directive block, or an unmarked directive section written by
+/// an older release, with the current block. Text around it is kept.
+pub fn refresh_directive(content: &str) -> (DirectiveState, String) {
+    if let Some(range) = find_block(content) {
+        let block = marked_block();
+        if content[range.clone()] == block {
+            return (DirectiveState::Current, content.to_string());
+        }
+        let mut updated = content.to_string();
+        updated.replace_range(range, &block);
+        return (DirectiveState::Stale, updated);
+    }
+
+    if let Some(range) = find_legacy_section(content) {
+        let mut updated = content.to_string();
+        updated.replace_range(range, &directive_block());
+        return (DirectiveState::Legacy, updated);
+    }
+
+    (DirectiveState::Missing, content.to_string())
+}
+
+/// The file without its Engram directive, or None when it has none. The
+/// blank lines `init` added around a marked block are removed with it.
+pub fn remove_directive(content: &str) -> Option<String> {
+    let mut range = find_block(content).map(|mut range| {
+        if content[range.end..].starts_with('\n') {
+            range.end += 1;
+        }
+        if range.start == 0 {
+            // Prepended to a file without a heading
+            if content[range.end..].starts_with('\n') {
+                range.end += 1;
+            }
+        } else if content[..range.start].ends_with("\n\n") {
+            // Inserted after a heading or front matter
+            range.start -= 1;
+        }
+        range
+    });
+    if range.is_none() {
+        range = find_legacy_section(content);
+    }
+
+    let mut remaining = content.to_string();
+    remaining.replace_range(range?, "");
+    Some(remaining)
+}
+
+/// Byte range from the begin marker line through the end marker line
+fn find_block(content: &str) -> Option<Range<usize>> {
+    let start = content
+        .match_indices(BLOCK_BEGIN)
+        .map(|(index, _)| index)
+        .find(|&index| index == 0 || content[..index].ends_with('\n'))?;
+    let mut end = start + content[start..].find(BLOCK_END)? + BLOCK_END.len();
+    if content[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some(start..end)
+}
+
+/// Byte range of an unmarked `## … Engram Protocol` section, up to the next
+/// level-1 or level-2 heading
+fn find_legacy_section(content: &str) -> Option<Range<usize>> {
+    let mut start = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        match start {
+            Some(start) if line.starts_with("# ") || line.starts_with("## ") => {
+                return Some(start..offset);
+            }
+            None if line.starts_with("## ") && line.contains(ENGRAM_MARKER) => {
+                start = Some(offset);
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    start.map(|start| start..content.len())
+}
+
+/// Look up a target by name
+pub fn target(name: &str) -> Option<&'static DirectiveTarget> {
+    DIRECTIVE_TARGETS.iter().find(|target| target.name == name)
+}
+
+/// Targets whose detection paths exist under `root`
+pub fn detect_targets(root: &Path) -> Vec<&'static DirectiveTarget> {
+    DIRECTIVE_TARGETS
+        .iter()
+        .filter(|target| target.is_detected(root))
+        .collect()
+}
+
+impl DirectiveTarget {
+    /// True when any of the target's detection paths exists
+    pub fn is_detected(&self, root: &Path) -> bool {
+        self.detect.iter().any(|path| root.join(path).exists())
+    }
+
+    /// The file this target writes, relative to `root`
+    pub fn relative_path(&self, root: &Path) -> &'static str {
+        match self.dir_path {
+            Some(dir_path) if root.join(self.path).is_dir() => dir_path,
+            _ => self.path,
+        }
+    }
+
+    /// True when the tool is in use and its instruction file exists, so
+    /// `doctor` should expect the directive in it
+    pub fn in_use(&self, root: &Path) -> bool {
+        root.join(self.relative_path(root)).is_file()
+            && (self.detect.is_empty() || self.is_detected(root))
+    }
+
+    /// Contents of a newly created instruction file
+    pub fn new_file(&self) -> String {
+        match self.format {
+            DirectiveFormat::Markdown { header } => {
+                format!("{}\n\n{}", header, directive_block())
+            }
+            DirectiveFormat::Plain => directive_block(),
+            DirectiveFormat::CursorRule => {
+                format!("{}\n{}", CURSOR_FRONT_MATTER, directive_block())
+            }
+        }
+    }
+
+    /// Existing file contents with the directive inserted
+    pub fn insert_directive(&self, content: &str) -> String {
+        if self.format == DirectiveFormat::CursorRule {
+            if let Some(end) = front_matter_end(content) {
+                let (front_matter, rest) = content.split_at(end);
+                return format!("{}\n{}{}", front_matter, directive_block(), rest);
+            }
+        }
+        append_directive_after_heading(content)
+    }
+
+    /// Create the instruction file or add the directive to it, then register
+    /// it with the tool's config when the tool needs that. With `refresh`, an
+    /// existing directive is replaced with the current block.
+    pub fn install(
+        &self,
+        root: &Path,
+        refresh: bool,
+        actions: &mut Vec<InitAction>,
+    ) -> Result<(), EngramError> {
+        let relative = self.relative_path(root);
+        let path = root.join(relative);
+
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let (state, refreshed) = refresh_directive(&content);
+
+            if refresh && matches!(state, DirectiveState::Stale | DirectiveState::Legacy) {
+                fs::write(&path, refreshed)?;
+                actions.push(InitAction::Updated {
+                    path: relative.to_string(),
+                    change: "refreshed the Engram directive".to_string(),
+                });
+            } else if content.contains(ENGRAM_MARKER) {
+                // Idempotency check: don't append if directive already exists
+                actions.push(InitAction::Skipped {
+                    path: relative.to_string(),
+                    reason: "Engram directive already present",
+                });
+            } else {
+                fs::write(&path, self.insert_directive(&content))?;
+                actions.push(InitAction::Appended(relative.to_string()));
+            }
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, self.new_file())?;
+            actions.push(InitAction::Created(relative.to_string()));
+        }
+
+        if let Some(config) = self.read_list {
+            record_read_entry(root, config, relative, actions)?;
+        }
+        Ok(())
+    }
+
+    /// True when `content` holds nothing but what `new_file` puts around the
+    /// directive, so the file can go once the directive is removed
+    pub fn is_scaffolding(&self, content: &str) -> bool {
+        let content = content.trim();
+        content.is_empty()
+            || match self.format {
+                DirectiveFormat::Markdown { header } => content == header,
+                DirectiveFormat::Plain => false,
+                DirectiveFormat::CursorRule => content == CURSOR_FRONT_MATTER.trim(),
+            }
+    }
+
+    /// What adding the instruction file to the tool's config `read:` list
+    /// would do. None when the tool has no such config or it cannot be read.
+    pub(crate) fn read_entry_edit(&self, root: &Path) -> Option<ReadListEdit> {
+        let config = self.read_list?;
+        let content = fs::read_to_string(root.join(config)).ok()?;
+        Some(add_to_read_list(&content, self.relative_path(root)))
+    }
+
+    /// Add the instruction file to the tool's config `read:` list
+    pub fn register_read(&self, root: &Path) -> std::io::Result<()> {
+        let Some(config) = self.read_list else {
+            return Ok(());
+        };
+        let path = root.join(config);
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        match add_to_read_list(&content, self.relative_path(root)) {
+            ReadListEdit::Updated(updated) => fs::write(path, updated),
+            ReadListEdit::Unchanged | ReadListEdit::Unsupported => Ok(()),
+        }
+    }
+}
+
+/// Make `config` list `item` under `read:`, creating the config if needed
+fn record_read_entry(
+    root: &Path,
+    config: &str,
+    item: &str,
+    actions: &mut Vec<InitAction>,
+) -> Result<(), EngramError> {
+    let path = root.join(config);
+    if !path.exists() {
+        fs::write(&path, format!("read:\n  - {}\n", item))?;
+        actions.push(InitAction::Created(config.to_string()));
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    match add_to_read_list(&content, item) {
+        ReadListEdit::Updated(updated) => {
+            fs::write(&path, updated)?;
+            actions.push(InitAction::Updated {
+                path: config.to_string(),
+                change: format!("added {} to read", item),
+            });
+        }
+        ReadListEdit::Unchanged => actions.push(InitAction::Skipped {
+            path: config.to_string(),
+            reason: "already in read list",
+        }),
+        ReadListEdit::Unsupported => actions.push(InitAction::Skipped {
+            path: config.to_string(),
+            reason: "read: is not a plain list; add the entry by hand",
+        }),
+    }
+    Ok(())
+}
+
+/// Outcome of editing the `read:` key of a YAML config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReadListEdit {
+    /// The config with the edit applied
+    Updated(String),
+    /// Nothing to change: the item is already listed (or, when removing, not listed)
+    Unchanged,
+    /// `read:` is not a plain scalar, flow list or block list; it has to be
+    /// edited by hand
+    Unsupported,
+}
+
+/// Split a YAML value from its trailing `# comment` (a `#` at the start or
+/// after whitespace, outside quotes). The comment keeps its `#`.
+fn split_yaml_comment(value: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in value.char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => {
+                return (value[..index].trim(), value[index..].trim_end());
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    (value.trim(), "")
+}
+
+fn unquote_yaml(value: &str) -> String {
+    value
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+/// Items of an inline `read:` value: a flow list (`[a, b]`) or a single
+/// plain or quoted scalar. None for anything else (nested collections,
+/// anchors, aliases, tags, block scalars).
+fn inline_read_items(value: &str) -> Option<Vec<String>> {
+    let nested = |value: &str| value.contains(['[', ']', '{', '}']);
+    if let Some(inner) = value.strip_prefix('[') {
+        let inner = inner.strip_suffix(']')?;
+        if nested(inner) {
+            return None;
+        }
+        return Some(
+            inner
+                .split(',')
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect(),
+        );
+    }
+    let indicator = value.starts_with(['{', '&', '*', '!', '|', '>', '%', '@', '`']);
+    (!indicator && !nested(value)).then(|| vec![value.to_string()])
+}
+
+/// `read: [a, b]`, keeping a trailing comment
+fn flow_read_line(items: &[String], comment: &str) -> String {
+    let line = format!("read: [{}]", items.join(", "));
+    if comment.is_empty() {
+        line
+    } else {
+        format!("{} {}", line, comment)
+    }
+}
+
+/// The item of a block list line (`  - item # comment`), if it is one
+fn block_item(line: &str) -> Option<String> {
+    let item = line.trim_start().strip_prefix("- ")?;
+    Some(unquote_yaml(split_yaml_comment(item).0))
+}
+
+/// Add `item` to the top-level `read:` key of a YAML config, keeping the rest
+/// of the file as written. Handles block lists, flow lists and a single
+/// scalar, each with or without a trailing comment.
+pub(crate) fn add_to_read_list(config: &str, item: &str) -> ReadListEdit {
+    let lines: Vec<&str> = config.lines().collect();
+
+    let Some(index) = lines.iter().position(|line| line.starts_with("read:")) else {
+        let mut updated = config.to_string();
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&format!("read:\n  - {}\n", item));
+        return ReadListEdit::Updated(updated);
+    };
+
+    let (value, comment) = split_yaml_comment(&lines[index]["read:".len()..]);
+    let mut updated: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+
+    if value.is_empty() {
+        // Block list: `read:` followed by indented `- item` lines
+        let items: Vec<&str> = lines[index + 1..]
+            .iter()
+            .take_while(|line| line.trim_start().starts_with("- "))
+            .copied()
+            .collect();
+        if items
+            .iter()
+            .any(|line| block_item(line).as_deref() == Some(item))
+        {
+            return ReadListEdit::Unchanged;
+        }
+        let indent = items
+            .first()
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .unwrap_or("  ");
+        updated.insert(index + 1 + items.len(), format!("{}- {}", indent, item));
+    } else {
+        let Some(mut items) = inline_read_items(value) else {
+            return ReadListEdit::Unsupported;
+        };
+        if items.iter().any(|value| unquote_yaml(value) == item) {
+            return ReadListEdit::Unchanged;
+        }
+        items.push(item.to_string());
+        updated[index] = flow_read_line(&items, comment);
+    }
+
+    let mut updated = updated.join("\n");
+    updated.push('\n');
+    ReadListEdit::Updated(updated)
+}
+
+/// Remove `item` from the top-level `read:` key, dropping the key when the
+/// list becomes empty
+pub(crate) fn remove_from_read_list(config: &str, item: &str) -> ReadListEdit {
+    let mut lines: Vec<String> = config.lines().map(str::to_string).collect();
+    let Some(index) = lines.iter().position(|line| line.starts_with("read:")) else {
+        return ReadListEdit::Unchanged;
+    };
+    let (value, comment) = split_yaml_comment(&lines[index]["read:".len()..]);
+    let (value, comment) = (value.to_string(), comment.to_string());
+
+    if value.is_empty() {
+        let count = lines[index + 1..]
+            .iter()
+            .take_while(|line| line.trim_start().starts_with("- "))
+            .count();
+        let Some(position) = lines[index + 1..index + 1 + count]
+            .iter()
+            .position(|line| block_item(line).as_deref() == Some(item))
+        else {
+            return ReadListEdit::Unchanged;
+        };
+        lines.remove(index + 1 + position);
+        if count == 1 {
+            lines.remove(index);
+        }
+    } else {
+        let Some(items) = inline_read_items(&value) else {
+            return ReadListEdit::Unsupported;
+        };
+        let kept: Vec<String> = items
+            .iter()
+            .filter(|value| unquote_yaml(value) != item)
+            .cloned()
+            .collect();
+        if kept.len() == items.len() {
+            return ReadListEdit::Unchanged;
+        }
+        if kept.is_empty() {
+            lines.remove(index);
+        } else {
+            lines[index] = flow_read_line(&kept, &comment);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    ReadListEdit::Updated(updated)
+}
+
+/// Byte offset just past the closing `---` line of leading front matter
+fn front_matter_end(content: &str) -> Option<usize> {
+    let rest = content.strip_prefix("---\n")?;
+    let close = rest.find("\n---\n")?;
+    Some("---\n".len() + close + "\n---\n".len())
+}
+
+/// Append the directive after the first level-1 heading, or at the start if no heading found
+pub(crate) fn append_directive_after_heading(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+
+    // Find the first level-1 heading (starts with "# ")
+    let heading_index = lines.iter().position(|line| line.starts_with("# "));
+
+    match heading_index {
+        Some(idx) => {
+            // Insert directive after the heading line
+            let mut result = String::new();
+
+            // Add lines up to and including the heading
+            for line in &lines[..=idx] {
+                result.push_str(line);
+                result.push('\n');
+            }
+
+            // Add blank line and directive
+            result.push('\n');
+            result.push_str(&directive_block());
+
+            // Add remaining content
+            if idx + 1 < lines.len() {
+                for line in &lines[idx + 1..] {
+                    result.push_str(line);
+                    result.push('\n');
+                }
+            }
+
+            result
+        }
+        None => {
+            // No heading found, prepend directive
+            format!("{}\n{}", directive_block(), content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        for (index, target) in DIRECTIVE_TARGETS.iter().enumerate() {
+            assert!(DIRECTIVE_TARGETS[index + 1..]
+                .iter()
+                .all(|other| other.name != target.name));
+        }
+        assert!(target(FALLBACK_TARGET).unwrap().detect.is_empty());
+    }
+
+    #[test]
+    fn test_append_directive_after_heading() {
+        let content = "# My Title\n\nSome content here.\n\n## Section\n\nMore content.\n";
+        let result = append_directive_after_heading(content);
+
+        // Should have heading first, then directive, then rest of content
+        assert!(result.starts_with("# My Title\n"));
+        assert!(result.contains("Engram Protocol"));
+        assert!(result.contains("Some content here."));
+    }
+
+    #[test]
+    fn test_append_directive_no_heading() {
+        let content = "Just some content without a heading.\n";
+        let result = append_directive_after_heading(content);
+
+        // Directive should be prepended
+        assert!(result.starts_with("<!-- engram:begin v"));
+        assert!(result.contains("-->\n## 🔒 Engram Protocol"));
+        assert!(result.contains("Just some content without a heading."));
+    }
+
+    #[test]
+    fn test_directive_block_is_versioned() {
+        let block = directive_block();
+        assert!(block.starts_with(&format!(
+            "<!-- engram:begin v{} -->\n## 🔒 Engram Protocol",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(block.ends_with("<!-- engram:end -->\n\n"));
+        assert!(block.contains(ENGRAM_MARKER));
+    }
+
+    #[test]
+    fn test_refresh_directive_replaces_stale_block() {
+        let current = format!("# Notes\n\n{}Keep this.\n", directive_block());
+        assert_eq!(
+            refresh_directive(&current),
+            (DirectiveState::Current, current.clone())
+        );
+
+        let stale = "# Notes\n\n<!-- engram:begin v0.0.1 -->\n## 🔒 Engram Protocol\nOld.\n<!-- engram:end -->\n\nKeep this.\n";
+        assert_eq!(refresh_directive(stale), (DirectiveState::Stale, current));
+
+        assert_eq!(refresh_directive("# Notes\n").0, DirectiveState::Missing);
+    }
+
+    #[test]
+    fn test_refresh_directive_migrates_legacy_section() {
+        let legacy = format!(
+            "# Warp\n\n{}## Build\n\ncargo build\n",
+            ROOT_DIRECTIVE_TEMPLATE
+        );
+        let (state, updated) = refresh_directive(&legacy);
+        assert_eq!(state, DirectiveState::Legacy);
+        assert_eq!(
+            updated,
+            format!("# Warp\n\n{}## Build\n\ncargo build\n", directive_block())
+        );
+        assert_eq!(refresh_directive(&updated).0, DirectiveState::Current);
+    }
+
+    #[test]
+    fn test_remove_directive_restores_surrounding_content() {
+        let warp = target("warp").unwrap();
+        let original = "# My Warp Instructions\n\nSome existing content.\n";
+        let installed = warp.insert_directive(original);
+        assert_eq!(remove_directive(&installed).unwrap(), original);
+
+        let original = "Just some content without a heading.\n";
+        let installed = warp.insert_directive(original);
+        assert_eq!(remove_directive(&installed).unwrap(), original);
+
+        let cursor = target("cursor").unwrap();
+        let original = "---\nalwaysApply: false\n---\nUse tabs.\n";
+        let installed = cursor.insert_directive(original);
+        assert_eq!(remove_directive(&installed).unwrap(), original);
+
+        let legacy = format!("# Warp\n\n{}## Build\n", ROOT_DIRECTIVE_TEMPLATE);
+        assert_eq!(remove_directive(&legacy).unwrap(), "# Warp\n\n## Build\n");
+
+        assert!(remove_directive(original).is_none());
+    }
+
+    #[test]
+    fn test_new_files_are_scaffolding() {
+        for target in DIRECTIVE_TARGETS {
+            let remaining = remove_directive(&target.new_file()).unwrap();
+            assert!(target.is_scaffolding(&remaining), "{}", target.name);
+        }
+        assert!(!target("warp")
+            .unwrap()
+            .is_scaffolding("# Warp AI Instructions\n\nMine.\n"));
+    }
+
+    #[test]
+    fn test_remove_from_read_list() {
+        let updated = |config: &str| ReadListEdit::Updated(config.to_string());
+        assert_eq!(
+            remove_from_read_list("model: x\nread:\n  - CONVENTIONS.md\n", "CONVENTIONS.md"),
+            updated("model: x\n")
+        );
+        assert_eq!(
+            remove_from_read_list(
+                "read:\n  - NOTES.md\n  - CONVENTIONS.md # engram\n",
+                "CONVENTIONS.md"
+            ),
+            updated("read:\n  - NOTES.md\n")
+        );
+        assert_eq!(
+            remove_from_read_list("read: [NOTES.md, CONVENTIONS.md]\n", "CONVENTIONS.md"),
+            updated("read: [NOTES.md]\n")
+        );
+        assert_eq!(
+            remove_from_read_list(
+                "read: [NOTES.md, CONVENTIONS.md] # docs\n",
+                "CONVENTIONS.md"
+            ),
+            updated("read: [NOTES.md] # docs\n")
+        );
+        assert_eq!(
+            remove_from_read_list("read: CONVENTIONS.md\n", "CONVENTIONS.md"),
+            updated("")
+        );
+        assert_eq!(
+            remove_from_read_list("read: [NOTES.md]\n", "CONVENTIONS.md"),
+            ReadListEdit::Unchanged
+        );
+        assert_eq!(
+            remove_from_read_list("read: *docs\n", "CONVENTIONS.md"),
+            ReadListEdit::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_cursor_rule_keeps_front_matter_first() {
+        let cursor = target("cursor").unwrap();
+        assert!(cursor.new_file().starts_with("---\ndescription:"));
+        assert!(cursor
+            .new_file()
+            .contains("alwaysApply: true\n---\n\n<!-- engram:begin"));
+
+        let existing = "---\nalwaysApply: false\n---\nUse tabs.\n";
+        let updated = cursor.insert_directive(existing);
+        assert!(updated.starts_with("---\nalwaysApply: false\n---\n\n<!-- engram:begin"));
+        assert!(updated.ends_with("Use tabs.\n"));
+    }
+
+    #[test]
+    fn test_add_to_read_list() {
+        let updated = |config: &str| ReadListEdit::Updated(config.to_string());
+        assert_eq!(
+            add_to_read_list("model: gpt-4o\n", "CONVENTIONS.md"),
+            updated("model: gpt-4o\nread:\n  - CONVENTIONS.md\n")
+        );
+        assert_eq!(
+            add_to_read_list("read:\n    - NOTES.md\nmodel: x\n", "CONVENTIONS.md"),
+            updated("read:\n    - NOTES.md\n    - CONVENTIONS.md\nmodel: x\n")
+        );
+        assert_eq!(
+            add_to_read_list("read: [NOTES.md]\n", "CONVENTIONS.md"),
+            updated("read: [NOTES.md, CONVENTIONS.md]\n")
+        );
+        assert_eq!(
+            add_to_read_list("read: NOTES.md\n", "CONVENTIONS.md"),
+            updated("read: [NOTES.md, CONVENTIONS.md]\n")
+        );
+        assert_eq!(
+            add_to_read_list("read: [NOTES.md] # docs\n", "CONVENTIONS.md"),
+            updated("read: [NOTES.md, CONVENTIONS.md] # docs\n")
+        );
+        assert_eq!(
+            add_to_read_list("read: \"a #1.md\" # docs\n", "CONVENTIONS.md"),
+            updated("read: [\"a #1.md\", CONVENTIONS.md] # docs\n")
+        );
+        assert_eq!(
+            add_to_read_list("read: # files\n  - NOTES.md\n", "CONVENTIONS.md"),
+            updated("read: # files\n  - NOTES.md\n  - CONVENTIONS.md\n")
+        );
+        for listed in [
+            "read:\n  - \"CONVENTIONS.md\"\n",
+            "read:\n  - CONVENTIONS.md # engram\n",
+            "read: [a, CONVENTIONS.md]\n",
+            "read: CONVENTIONS.md # engram\n",
+        ] {
+            assert_eq!(
+                add_to_read_list(listed, "CONVENTIONS.md"),
+                ReadListEdit::Unchanged
+            );
+        }
+        for unsupported in [
+            "read: [[a], b]\n",
+            "read: &docs [a]\n",
+            "read: |\n  a\n",
+            "read: {a: b}\n",
+        ] {
+            assert_eq!(
+                add_to_read_list(unsupported, "CONVENTIONS.md"),
+                ReadListEdit::Unsupported,
+                "{}",
+                unsupported
+            );
+        }
+    }
+
+    #[test]
+    fn test_cline_rules_directory() {
+        let dir = TempDir::new().unwrap();
+        let cline = target("cline").unwrap();
+        assert_eq!(cline.relative_path(dir.path()), ".clinerules");
+
+        fs::create_dir(dir.path().join(".clinerules")).unwrap();
+        let mut actions = Vec::new();
+        cline.install(dir.path(), false, &mut actions).unwrap();
+
+        assert_eq!(
+            actions,
+            vec![InitAction::Created(".clinerules/engram.md".to_string())]
+        );
+        let content = fs::read_to_string(dir.path().join(".clinerules/engram.md")).unwrap();
+        assert!(content.starts_with("<!-- engram:begin"));
+    }
+
+    #[test]
+    fn test_aider_registers_conventions() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".aider.conf.yml"), "model: sonnet\n").unwrap();
+        let aider = target("aider").unwrap();
+        assert!(matches!(
+            aider.read_entry_edit(dir.path()),
+            Some(ReadListEdit::Updated(_))
+        ));
+
+        let mut actions = Vec::new();
+        aider.install(dir.path(), false, &mut actions).unwrap();
+        aider.install(dir.path(), false, &mut actions).unwrap();
+
+        assert_eq!(
+            actions[..2],
+            [
+                InitAction::Created("CONVENTIONS.md".to_string()),
+                InitAction::Updated {
+                    path: ".aider.conf.yml".to_string(),
+                    change: "added CONVENTIONS.md to read".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".aider.conf.yml")).unwrap(),
+            "model: sonnet\nread:\n  - CONVENTIONS.md\n"
+        );
+        assert_eq!(
+            aider.read_entry_edit(dir.path()),
+            Some(ReadListEdit::Unchanged)
+        );
+        assert!(matches!(actions[3], InitAction::Skipped { .. }));
+    }
+}
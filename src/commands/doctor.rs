@@ -0,0 +1,825 @@
+use chrono::Utc;
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use crate::commands::directives::{self, ReadListEdit, DIRECTIVE_TARGETS, FALLBACK_TARGET};
+use crate::commands::init::{
+    set_executable, write_wrappers, ENGRAM_MARKER, GITATTRIBUTES_CONTENT, GITIGNORE_CONTENT,
+};
+use crate::commands::upgrade::legacy_entry_name;
+use crate::commands::vendor::vendored_binary;
+use crate::commands::verify::{collect_entries, verify_entries, VerifyError};
+use crate::commands::wrappers::{read_wrapper, WrapperKind, WrapperMode, WrapperSpec};
+use crate::engram::canonical::{describe, diagnose_drift, undo, Drift};
+use crate::engram::chain::parse_summary;
+use crate::engram::draft::{Draft, DraftError};
+use crate::engram::summary::{parse_rows, rebuild, row_text};
+use crate::engram::worklog::{EntryContent, WorklogEntry};
+use crate::error::EngramError;
+use crate::templates::{AGENTS_TEMPLATE, DRAFT_TEMPLATE, SUMMARY_TEMPLATE};
+use crate::utils::hash::sha256_short;
+
+const ENGRAM_DIR: &str = ".engram";
+const WORKLOG_DIR: &str = ".engram/worklog";
+
+#[derive(Debug, Clone, Default)]
+pub struct DoctorOptions {
+    /// Apply every automatic fix
+    pub fix: bool,
+    /// Rewrite entries whose only difference from their hash is CRLF line endings
+    pub fix_eol: bool,
+}
+
+impl DoctorOptions {
+    fn fix_eol(&self) -> bool {
+        self.fix || self.fix_eol
+    }
+}
+
+/// What happened to a detected problem
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// The problem was repaired during this run
+    Fixed,
+    /// The problem can be repaired by re-running with the given flag
+    Fixable(&'static str),
+    /// The problem needs a human to look at it
+    Manual(String),
+}
+
+/// A single problem found by the doctor
+#[derive(Debug)]
+pub struct Finding {
+    pub subject: String,
+    pub problem: String,
+    pub resolution: Resolution,
+}
+
+/// Findings grouped under one named check
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    /// Returns true if any finding was not fixed during this run
+    pub fn has_unresolved(&self) -> bool {
+        self.findings().any(|f| f.resolution != Resolution::Fixed)
+    }
+
+    fn findings(&self) -> impl Iterator<Item = &Finding> {
+        self.checks.iter().flat_map(|c| c.findings.iter())
+    }
+}
+
+/// Run every check against the repository in `base_dir`, applying fixes enabled by `options`
+pub fn run_doctor_in_dir(
+    base_dir: &Path,
+    options: &DoctorOptions,
+) -> Result<DoctorReport, EngramError> {
+    if !base_dir.join(ENGRAM_DIR).exists() {
+        return Err(EngramError::NotInitialized);
+    }
+
+    // Checks run in order and later checks see earlier fixes
+    let checks: [(&'static str, CheckFn); 9] = [
+        ("Layout", check_layout),
+        ("Git hygiene files", check_hygiene_files),
+        ("Worklog contents", check_stray_files),
+        ("Entry encoding", check_entry_encoding),
+        ("Hash chain", check_chain),
+        ("SUMMARY.md", check_summary),
+        ("Draft", check_draft),
+        ("Wrappers", check_wrappers),
+        ("Agent directives", check_directives),
+    ];
+
+    let mut report = DoctorReport::default();
+    for (name, check) in checks {
+        let findings = check(base_dir, options)?;
+        report.checks.push(Check { name, findings });
+    }
+
+    // Only meaningful inside a git work tree
+    if let Some(findings) = check_git_attributes(base_dir) {
+        report.checks.push(Check {
+            name: "Git attributes in effect",
+            findings,
+        });
+    }
+
+    Ok(report)
+}
+
+type CheckFn = fn(&Path, &DoctorOptions) -> io::Result<Vec<Finding>>;
+
+/// Apply `fix` when fixing is enabled, otherwise point at the flag that would
+fn resolve(
+    enabled: bool,
+    flag: &'static str,
+    fix: impl FnOnce() -> io::Result<()>,
+) -> io::Result<Resolution> {
+    if enabled {
+        fix()?;
+        Ok(Resolution::Fixed)
+    } else {
+        Ok(Resolution::Fixable(flag))
+    }
+}
+
+/// Required directories and files under .engram/
+fn check_layout(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+
+    let worklog_dir = base_dir.join(WORKLOG_DIR);
+    if !worklog_dir.is_dir() {
+        findings.push(Finding {
+            subject: ".engram/worklog/".to_string(),
+            problem: "missing".to_string(),
+            resolution: resolve(options.fix, "--fix", || fs::create_dir_all(&worklog_dir))?,
+        });
+    }
+
+    let files = [
+        (".engram/AGENTS.md", AGENTS_TEMPLATE),
+        (".engram/worklog/SUMMARY.md", SUMMARY_TEMPLATE),
+    ];
+    for (relative, template) in files {
+        let path = base_dir.join(relative);
+        if !path.exists() {
+            findings.push(Finding {
+                subject: relative.to_string(),
+                problem: "missing".to_string(),
+                resolution: resolve(options.fix, "--fix", || fs::write(&path, template))?,
+            });
+        }
+    }
+
+    Ok(findings)
+}
+
+/// .engram/.gitignore and .engram/.gitattributes
+fn check_hygiene_files(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+
+    let files = [
+        (".engram/.gitignore", GITIGNORE_CONTENT),
+        (".engram/.gitattributes", GITATTRIBUTES_CONTENT),
+    ];
+    for (relative, content) in files {
+        let path = base_dir.join(relative);
+        if !path.exists() {
+            findings.push(Finding {
+                subject: relative.to_string(),
+                problem: "missing".to_string(),
+                resolution: resolve(options.fix, "--fix", || fs::write(&path, content))?,
+            });
+        }
+    }
+
+    let attributes_path = base_dir.join(".engram/.gitattributes");
+    if let Ok(attributes) = fs::read_to_string(&attributes_path) {
+        if !attributes.contains("eol=lf") {
+            findings.push(Finding {
+                subject: ".engram/.gitattributes".to_string(),
+                problem: "does not force eol=lf".to_string(),
+                resolution: Resolution::Manual(format!(
+                    "add `{}` so entries keep stable hashes",
+                    GITATTRIBUTES_CONTENT.trim_end()
+                )),
+            });
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Anything in worklog/ other than SUMMARY.md and well-formed entries
+fn check_stray_files(base_dir: &Path, _options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let worklog_dir = base_dir.join(WORKLOG_DIR);
+    let mut findings = Vec::new();
+    if !worklog_dir.is_dir() {
+        return Ok(findings);
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for dir_entry in fs::read_dir(&worklog_dir)? {
+        names.push(dir_entry?.file_name().to_string_lossy().to_string());
+    }
+    names.sort();
+
+    for name in names {
+        if name == "SUMMARY.md" || WorklogEntry::from_filename(&name, &worklog_dir).is_some() {
+            continue;
+        }
+        if let Some(renamed) = legacy_entry_name(&name) {
+            findings.push(Finding {
+                subject: format!("worklog/{}", name),
+                problem: format!("uses the legacy entry name format (now {})", renamed),
+                resolution: Resolution::Manual("run `engram upgrade`".to_string()),
+            });
+            continue;
+        }
+        findings.push(Finding {
+            subject: format!("worklog/{}", name),
+            problem: "unexpected file in worklog/".to_string(),
+            resolution: Resolution::Manual(
+                "move it out of .engram/worklog/ or restore its original entry name".to_string(),
+            ),
+        });
+    }
+
+    Ok(findings)
+}
+
+/// Entries whose bytes differ from their recorded hash only by encoding noise
+fn check_entry_encoding(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let worklog_dir = base_dir.join(WORKLOG_DIR);
+    let mut findings = Vec::new();
+    if !worklog_dir.is_dir() {
+        return Ok(findings);
+    }
+
+    let mut entries = collect_entries(&worklog_dir)?;
+    entries.sort_by_key(|e| e.sequence);
+
+    for entry in &entries {
+        let bytes = fs::read(&entry.path)?;
+        if sha256_short(&bytes) == entry.short_hash {
+            continue;
+        }
+
+        // Real content changes are reported by the chain check
+        let Some(drift) = diagnose_drift(&bytes, &entry.short_hash) else {
+            continue;
+        };
+
+        let resolution = if drift == [Drift::Crlf] {
+            resolve(options.fix_eol(), "--fix-eol", || {
+                fs::write(&entry.path, undo(&bytes, &drift))
+            })?
+        } else {
+            Resolution::Manual(
+                "restore the entry from version control; its text is intact".to_string(),
+            )
+        };
+
+        findings.push(Finding {
+            subject: entry.filename.clone(),
+            problem: format!("differs only by {}", describe(&drift)),
+            resolution,
+        });
+    }
+
+    Ok(findings)
+}
+
+/// Sequence and hash-chain verification, minus the stray files and encoding
+/// drift reported by their own checks
+fn check_chain(base_dir: &Path, _options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let worklog_dir = base_dir.join(WORKLOG_DIR);
+    if !worklog_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let problem = match verify_entries(collect_entries(&worklog_dir)?, Utc::now()) {
+        Ok(_) | Err(EngramError::Chain(VerifyError::NonCanonical { .. })) => return Ok(Vec::new()),
+        Err(e) => e.to_string().replace('\n', "; "),
+    };
+
+    Ok(vec![Finding {
+        subject: ".engram/worklog/".to_string(),
+        problem,
+        resolution: Resolution::Manual(
+            "run `engram verify` and restore the affected entries from version control".to_string(),
+        ),
+    }])
+}
+
+/// SUMMARY.md rows must match the entries on disk
+fn check_summary(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let worklog_dir = base_dir.join(WORKLOG_DIR);
+    let summary_path = worklog_dir.join("SUMMARY.md");
+    if !summary_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = collect_entries(&worklog_dir)?;
+    entries.sort_by_key(|e| e.sequence);
+
+    let mut expected: Vec<(String, String)> = Vec::new();
+    for entry in &entries {
+        let content = String::from_utf8_lossy(&fs::read(&entry.path)?).to_string();
+        let summary = match EntryContent::parse(&content) {
+            Some(parsed) => row_text(&parsed.summary, &parsed.tags()),
+            None => parse_summary(&content).unwrap_or_else(|| "No summary".to_string()),
+        };
+        expected.push((entry.filename.clone(), summary));
+    }
+
+    let actual = parse_rows(&fs::read_to_string(&summary_path)?);
+    if actual == expected {
+        return Ok(Vec::new());
+    }
+
+    let missing = expected.iter().filter(|row| !actual.contains(row)).count();
+    let unknown = actual.iter().filter(|row| !expected.contains(row)).count();
+    let problem = if missing == 0 && unknown == 0 {
+        "rows are out of order".to_string()
+    } else {
+        format!(
+            "{} entries missing or different, {} rows without a matching entry",
+            missing, unknown
+        )
+    };
+
+    Ok(vec![Finding {
+        subject: ".engram/worklog/SUMMARY.md".to_string(),
+        problem,
+        resolution: resolve(options.fix, "--fix", || rebuild(&summary_path, &expected))?,
+    }])
+}
+
+/// draft.md must exist and keep its <summary> tag
+fn check_draft(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let draft_path = base_dir.join(ENGRAM_DIR).join("draft.md");
+    let subject = ".engram/draft.md".to_string();
+
+    if !draft_path.exists() {
+        return Ok(vec![Finding {
+            subject,
+            problem: "missing".to_string(),
+            resolution: resolve(options.fix, "--fix", || {
+                fs::write(&draft_path, DRAFT_TEMPLATE)
+            })?,
+        }]);
+    }
+
+    let Ok(content) = fs::read_to_string(&draft_path) else {
+        return Ok(vec![Finding {
+            subject,
+            problem: "is not valid UTF-8".to_string(),
+            resolution: Resolution::Manual("re-save the draft as UTF-8".to_string()),
+        }]);
+    };
+
+    // Empty summary or body is the normal state between commits
+    match Draft::parse(&content) {
+        Err(DraftError::MissingSummaryTag) => Ok(vec![Finding {
+            subject,
+            problem: "has no <summary> tag, so it can never be committed".to_string(),
+            resolution: resolve(options.fix, "--fix", || {
+                fs::write(&draft_path, format!("<summary></summary>\n\n{}", content))
+            })?,
+        }]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Wrapper scripts exist and pin the same version as this binary
+fn check_wrappers(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let current = env!("CARGO_PKG_VERSION");
+    let spec = WrapperSpec::installed(base_dir);
+    let mut findings = Vec::new();
+
+    for kind in WrapperKind::ALL {
+        let name = kind.file_name();
+        let path = base_dir.join(name);
+        if !path.exists() {
+            findings.push(Finding {
+                subject: name.to_string(),
+                problem: "missing".to_string(),
+                resolution: resolve(options.fix, "--fix", || {
+                    write_wrappers(base_dir, &spec, current).map(|_| ())
+                })?,
+            });
+            continue;
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let Some(pinned) = wrapper_version(&content) else {
+            findings.push(Finding {
+                subject: name.to_string(),
+                problem: "does not declare ENGRAM_VERSION".to_string(),
+                resolution: Resolution::Manual(format!("restore {} from `engram init`", name)),
+            });
+            continue;
+        };
+
+        if pinned == current {
+            continue;
+        }
+
+        let problem = format!("pins Engram {} but this binary is {}", pinned, current);
+        let resolution = match read_wrapper(kind, &content) {
+            Some(wrapper) if wrapper.spec.can_repin() => resolve(options.fix, "--fix", || {
+                fs::write(&path, wrapper.render(kind, &wrapper.spec, current))?;
+                if kind == WrapperKind::Sh {
+                    set_executable(&path)?;
+                }
+                Ok(())
+            })?,
+            Some(_) => Resolution::Manual(format!(
+                "{} pins release checksums; run `engram pin {} --checksums <FILE>`",
+                name, current
+            )),
+            None => Resolution::Manual(format!(
+                "{} has local changes outside engram:custom markers; update ENGRAM_VERSION by hand",
+                name
+            )),
+        };
+
+        findings.push(Finding {
+            subject: name.to_string(),
+            problem,
+            resolution,
+        });
+    }
+
+    // Local-mode wrappers only run a vendored binary
+    if spec.mode == WrapperMode::Local {
+        let vendored = vendored_binary(current);
+        if !base_dir.join(&vendored).exists() {
+            findings.push(Finding {
+                subject: vendored,
+                problem: "missing, and the wrappers run in local mode".to_string(),
+                resolution: Resolution::Manual("run `engram vendor`".to_string()),
+            });
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Extract the pinned version from a generated wrapper script
+pub(crate) fn wrapper_version(content: &str) -> Option<String> {
+    let re = Regex::new(r#"(?m)^(?:set )?ENGRAM_VERSION="?([0-9A-Za-z.+-]+)"?\s*$"#).unwrap();
+    re.captures(content).map(|caps| caps[1].to_string())
+}
+
+/// Instruction files of the agent tools in use should carry the Engram protocol section
+fn check_directives(base_dir: &Path, options: &DoctorOptions) -> io::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let mut any_exists = false;
+
+    for target in DIRECTIVE_TARGETS {
+        if !target.in_use(base_dir) {
+            continue;
+        }
+        any_exists = true;
+        let relative = target.relative_path(base_dir);
+        let path = base_dir.join(relative);
+        // One unreadable agent file must not hide the state of the others
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) => {
+                let (problem, hint) = if err.kind() == io::ErrorKind::InvalidData {
+                    ("is not valid UTF-8".to_string(), "re-save it as UTF-8")
+                } else {
+                    (format!("cannot be read: {}", err), "check its permissions")
+                };
+                findings.push(Finding {
+                    subject: relative.to_string(),
+                    problem,
+                    resolution: Resolution::Manual(hint.to_string()),
+                });
+                continue;
+            }
+        };
+
+        if !content.contains(ENGRAM_MARKER) {
+            findings.push(Finding {
+                subject: relative.to_string(),
+                problem: "exists but has no Engram directive".to_string(),
+                resolution: resolve(options.fix, "--fix", || {
+                    fs::write(&path, target.insert_directive(&content))
+                })?,
+            });
+        }
+
+        if let Some(config) = target.read_list {
+            match target.read_entry_edit(base_dir) {
+                Some(ReadListEdit::Updated(_)) => findings.push(Finding {
+                    subject: config.to_string(),
+                    problem: format!("does not list {} under read:", relative),
+                    resolution: resolve(options.fix, "--fix", || target.register_read(base_dir))?,
+                }),
+                Some(ReadListEdit::Unsupported) => findings.push(Finding {
+                    subject: config.to_string(),
+                    problem: "has a read: value Engram cannot edit".to_string(),
+                    resolution: Resolution::Manual(format!("make sure read: lists {}", relative)),
+                }),
+                Some(ReadListEdit::Unchanged) | None => {}
+            }
+        }
+    }
+
+    if !any_exists {
+        let fallback = directives::target(FALLBACK_TARGET).expect("fallback target is registered");
+        let path = base_dir.join(fallback.path);
+        findings.push(Finding {
+            subject: fallback.path.to_string(),
+            problem: "no agent directive file found".to_string(),
+            resolution: resolve(options.fix, "--fix", || {
+                fs::write(&path, fallback.new_file())
+            })?,
+        });
+    }
+
+    Ok(findings)
+}
+
+/// Ask git which eol attribute actually applies under .engram/.
+/// Returns None when git is unavailable or this is not a work tree.
+fn check_git_attributes(base_dir: &Path) -> Option<Vec<Finding>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(base_dir)
+        .args(["check-attr", "eol", "--", ".engram/worklog/SUMMARY.md"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Output format: "<path>: eol: <value>"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = stdout.trim().rsplit(": ").next().unwrap_or("").to_string();
+    if value == "lf" {
+        return Some(Vec::new());
+    }
+
+    Some(vec![Finding {
+        subject: ".engram/".to_string(),
+        problem: format!("git applies eol={} instead of eol=lf", value),
+        resolution: Resolution::Manual(
+            "check for .gitattributes or core.attributesFile rules overriding .engram/.gitattributes"
+                .to_string(),
+        ),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{render_wrapper, run_init_in_dir, InitOptions};
+    use crate::templates::{WRAPPER_CMD_TEMPLATE, WRAPPER_SH_TEMPLATE};
+    use tempfile::tempdir;
+
+    const ENTRY: &str =
+        "Summary: First entry\nPrevious: none\nDate: 2025-06-12T14:32:07Z\n\n---\n\nBody";
+
+    fn fix_all() -> DoctorOptions {
+        DoctorOptions {
+            fix: true,
+            ..Default::default()
+        }
+    }
+
+    fn findings_for<'a>(report: &'a DoctorReport, name: &str) -> &'a [Finding] {
+        &report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap()
+            .findings
+    }
+
+    fn write_entry(base: &Path, content: &[u8]) -> std::path::PathBuf {
+        let path = base
+            .join(WORKLOG_DIR)
+            .join(format!("000001_{}.md", sha256_short(ENTRY)));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn init(base: &Path) {
+        run_init_in_dir(base, InitOptions::default()).unwrap();
+    }
+
+    #[test]
+    fn test_doctor_requires_init() {
+        let dir = tempdir().unwrap();
+        assert!(run_doctor_in_dir(dir.path(), &DoctorOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_doctor_fresh_install_is_healthy() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+
+        let report = run_doctor_in_dir(dir.path(), &DoctorOptions::default()).unwrap();
+        assert!(!report.has_unresolved(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_doctor_reports_crlf_without_fixing() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        let path = write_entry(dir.path(), ENTRY.replace('\n', "\r\n").as_bytes());
+
+        let report = run_doctor_in_dir(dir.path(), &DoctorOptions::default()).unwrap();
+        let findings = findings_for(&report, "Entry encoding");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].resolution, Resolution::Fixable("--fix-eol"));
+        assert!(findings_for(&report, "Hash chain").is_empty());
+        assert!(fs::read(&path).unwrap().contains(&b'\r'));
+    }
+
+    #[test]
+    fn test_doctor_fix_eol_restores_canonical_bytes() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        let path = write_entry(dir.path(), ENTRY.replace('\n', "\r\n").as_bytes());
+
+        let options = DoctorOptions {
+            fix_eol: true,
+            ..Default::default()
+        };
+        run_doctor_in_dir(dir.path(), &options).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), ENTRY);
+    }
+
+    #[test]
+    fn test_doctor_does_not_fix_bom() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        write_entry(dir.path(), format!("\u{feff}{}", ENTRY).as_bytes());
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        let findings = findings_for(&report, "Entry encoding");
+        assert!(matches!(findings[0].resolution, Resolution::Manual(_)));
+    }
+
+    #[test]
+    fn test_doctor_restores_missing_files() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        fs::remove_file(dir.path().join(".engram/.gitattributes")).unwrap();
+        fs::remove_file(dir.path().join(".engram/draft.md")).unwrap();
+        fs::remove_file(dir.path().join("engram.cmd")).unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &DoctorOptions::default()).unwrap();
+        assert!(report.has_unresolved());
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        assert!(!report.has_unresolved(), "{:?}", report);
+        assert!(dir.path().join(".engram/.gitattributes").exists());
+        assert!(dir.path().join(".engram/draft.md").exists());
+        assert!(dir.path().join("engram.cmd").exists());
+    }
+
+    #[test]
+    fn test_doctor_reports_stray_files() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        fs::write(
+            dir.path().join(WORKLOG_DIR).join("000003_deadbeef.md.bak"),
+            "x",
+        )
+        .unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        let findings = findings_for(&report, "Worklog contents");
+        assert_eq!(findings[0].subject, "worklog/000003_deadbeef.md.bak");
+        assert!(report.has_unresolved());
+    }
+
+    #[test]
+    fn test_doctor_rebuilds_summary() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        write_entry(dir.path(), ENTRY.as_bytes());
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        assert_eq!(findings_for(&report, "SUMMARY.md").len(), 1);
+
+        let summary = fs::read_to_string(dir.path().join(WORKLOG_DIR).join("SUMMARY.md")).unwrap();
+        assert!(summary.contains("| First entry |"));
+
+        let report = run_doctor_in_dir(dir.path(), &DoctorOptions::default()).unwrap();
+        assert!(findings_for(&report, "SUMMARY.md").is_empty());
+    }
+
+    #[test]
+    fn test_doctor_restores_summary_tag_without_losing_content() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        let draft_path = dir.path().join(".engram/draft.md");
+        fs::write(&draft_path, "## Intent\nWork in progress\n").unwrap();
+
+        run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+
+        let draft = fs::read_to_string(&draft_path).unwrap();
+        assert!(draft.starts_with("<summary></summary>"));
+        assert!(draft.contains("Work in progress"));
+    }
+
+    #[test]
+    fn test_doctor_updates_unmodified_stale_wrapper() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        let sh_path = dir.path().join("engram");
+        fs::write(&sh_path, render_wrapper(WRAPPER_SH_TEMPLATE, "0.0.1")).unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        assert_eq!(
+            findings_for(&report, "Wrappers")[0].resolution,
+            Resolution::Fixed
+        );
+        let content = fs::read_to_string(&sh_path).unwrap();
+        assert_eq!(
+            wrapper_version(&content).as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[test]
+    fn test_doctor_leaves_customized_wrapper() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        let sh_path = dir.path().join("engram");
+        let custom = format!(
+            "{}# local tweak\n",
+            render_wrapper(WRAPPER_SH_TEMPLATE, "0.0.1")
+        );
+        fs::write(&sh_path, &custom).unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        assert!(matches!(
+            findings_for(&report, "Wrappers")[0].resolution,
+            Resolution::Manual(_)
+        ));
+        assert_eq!(fs::read_to_string(&sh_path).unwrap(), custom);
+    }
+
+    #[test]
+    fn test_wrapper_version() {
+        assert_eq!(
+            wrapper_version(&render_wrapper(WRAPPER_SH_TEMPLATE, "1.2.3")).as_deref(),
+            Some("1.2.3")
+        );
+        assert_eq!(
+            wrapper_version(&render_wrapper(WRAPPER_CMD_TEMPLATE, "1.2.3")).as_deref(),
+            Some("1.2.3")
+        );
+        assert_eq!(wrapper_version("#!/bin/sh\n"), None);
+    }
+
+    #[test]
+    fn test_doctor_adds_missing_directive() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        let claude_path = dir.path().join("CLAUDE.md");
+        fs::write(&claude_path, "# Claude\n\nExisting content.\n").unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        assert_eq!(findings_for(&report, "Agent directives").len(), 1);
+
+        let content = fs::read_to_string(&claude_path).unwrap();
+        assert!(content.contains(ENGRAM_MARKER));
+        assert!(content.contains("Existing content."));
+    }
+
+    #[test]
+    fn test_doctor_registers_aider_conventions() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        fs::write(dir.path().join(".aider.conf.yml"), "model: sonnet\n").unwrap();
+        fs::write(dir.path().join("CONVENTIONS.md"), "# Conventions\n").unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        assert_eq!(findings_for(&report, "Agent directives").len(), 2);
+
+        let conventions = fs::read_to_string(dir.path().join("CONVENTIONS.md")).unwrap();
+        assert!(conventions.contains(ENGRAM_MARKER));
+        let config = fs::read_to_string(dir.path().join(".aider.conf.yml")).unwrap();
+        assert!(config.ends_with("read:\n  - CONVENTIONS.md\n"));
+    }
+
+    #[test]
+    fn test_doctor_reports_unreadable_directive_and_checks_the_rest() {
+        let dir = tempdir().unwrap();
+        init(dir.path());
+        fs::write(dir.path().join("CLAUDE.md"), b"# Claude\n\xff\xfe\n").unwrap();
+        fs::write(dir.path().join("AGENTS.md"), "# Agents\n").unwrap();
+
+        let report = run_doctor_in_dir(dir.path(), &fix_all()).unwrap();
+        let findings = findings_for(&report, "Agent directives");
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().any(|finding| finding.subject == "CLAUDE.md"
+            && finding.problem == "is not valid UTF-8"
+            && matches!(finding.resolution, Resolution::Manual(_))));
+        let agents = fs::read_to_string(dir.path().join("AGENTS.md")).unwrap();
+        assert!(agents.contains(ENGRAM_MARKER));
+    }
+}
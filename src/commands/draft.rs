@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use crate::engram::draft::{append_to_section, set_summary};
+use crate::error::EngramError;
+use crate::repository::Repository;
+use crate::templates::DRAFT_TEMPLATE;
+
+pub use crate::commands::commit::validate_draft_in_dir;
+
+/// Set the draft's `<summary>` text, returning the new draft
+pub fn run_set_summary_in_dir(base_dir: &Path, summary: &str) -> Result<String, EngramError> {
+    edit_draft(base_dir, |draft| Ok(set_summary(draft, summary)?))
+}
+
+/// Append `text` to a `## {section}` of the draft, returning the new draft
+pub fn run_append_in_dir(
+    base_dir: &Path,
+    section: &str,
+    text: &str,
+) -> Result<String, EngramError> {
+    if section.trim().is_empty() {
+        return Err(EngramError::Config("the section name is empty".to_string()));
+    }
+    if text.trim().is_empty() {
+        return Err(EngramError::Config(
+            "nothing to append: the text is empty".to_string(),
+        ));
+    }
+    edit_draft(base_dir, |draft| {
+        Ok(append_to_section(draft, section, text))
+    })
+}
+
+/// Reset the draft to the template
+pub fn run_clear_in_dir(base_dir: &Path) -> Result<(), EngramError> {
+    Repository::open(base_dir)?.write_draft(DRAFT_TEMPLATE)
+}
+
+/// Apply `edit` to the draft, starting from the template if draft.md was deleted
+fn edit_draft(
+    base_dir: &Path,
+    edit: impl FnOnce(&str) -> Result<String, EngramError>,
+) -> Result<String, EngramError> {
+    let repo = Repository::open(base_dir)?;
+    let draft = match repo.read_draft() {
+        Err(EngramError::DraftMissing) => DRAFT_TEMPLATE.to_string(),
+        draft => draft?,
+    };
+    let draft = edit(&draft)?;
+    repo.write_draft(&draft)?;
+    Ok(draft)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use crate::engram::draft::DraftError;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_edits_produce_a_committable_draft() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+
+        assert!(matches!(
+            validate_draft_in_dir(dir.path()),
+            Err(EngramError::Draft(DraftError::EmptySummary))
+        ));
+        run_set_summary_in_dir(dir.path(), "Fix login").unwrap();
+        run_append_in_dir(dir.path(), "Changes", "- src/login.rs").unwrap();
+
+        let draft = validate_draft_in_dir(dir.path()).unwrap();
+        assert_eq!(draft.summary, "Fix login");
+        assert!(draft.body.contains(
+            "## Changes\n<!-- List specific files and functions modified -->\n- src/login.rs"
+        ));
+
+        run_clear_in_dir(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        assert_eq!(repo.read_draft().unwrap(), DRAFT_TEMPLATE);
+    }
+
+    #[test]
+    fn test_edit_recreates_a_deleted_draft() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        fs::remove_file(repo.draft_path()).unwrap();
+
+        let draft = run_set_summary_in_dir(dir.path(), "Restore").unwrap();
+        assert_eq!(
+            draft,
+            DRAFT_TEMPLATE.replace("<summary></summary>", "<summary>Restore</summary>")
+        );
+        assert!(matches!(
+            run_append_in_dir(dir.path(), "Changes", "  "),
+            Err(EngramError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,578 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::commands::verify::VerifyResult;
+use crate::engram::worklog::DATE_FORMAT;
+use crate::error::EngramError;
+use crate::html;
+use crate::repository::{Entry, Repository};
+
+const ENTRIES_DIR: &str = "entries";
+const INDEX_PAGE: &str = "index.html";
+const CHAIN_PAGE: &str = "chain.html";
+const SEARCH_INDEX: &str = "search-index.json";
+/// The same index as a script, since browsers block `fetch` on `file://` pages
+const SEARCH_SCRIPT_INDEX: &str = "search-index.js";
+
+/// Filters the timeline using search-index.js (summary and body text)
+const SEARCH_SCRIPT: &str = "<script src=\"search-index.js\"></script>
+<script>
+(function (index) {
+  var input = document.getElementById('search');
+  input.addEventListener('input', function () {
+    var query = input.value.toLowerCase();
+    var hits = {};
+    index.forEach(function (e) {
+      if ((e.summary + '\\n' + e.body).toLowerCase().indexOf(query) >= 0) { hits[e.sequence] = true; }
+    });
+    document.querySelectorAll('.entry').forEach(function (div) {
+      div.style.display = hits[div.dataset.sequence] ? '' : 'none';
+    });
+  });
+})(window.ENGRAM_SEARCH_INDEX || []);
+</script>
+";
+
+/// Version of the `export --format json` document layout
+const DATA_EXPORT_VERSION: u32 = 1;
+
+/// CSV columns, in order. `sections` and `headers` hold JSON.
+const CSV_COLUMNS: [&str; 10] = [
+    "sequence", "filename", "hash", "previous", "date", "summary", "body", "sections", "headers",
+    "content",
+];
+
+/// Serialization for `engram export --format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// One JSON object per line
+    Jsonl,
+    /// A single JSON document with the entries and how to verify them
+    Json,
+    /// RFC 4180 CSV with a header row
+    Csv,
+}
+
+/// One end of an export range: a sequence number or a date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeBound {
+    Sequence(u32),
+    /// A calendar day (UTC); covers the whole day
+    Day(NaiveDate),
+    /// An exact instant (RFC 3339)
+    Instant(DateTime<Utc>),
+}
+
+impl FromStr for RangeBound {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(sequence) = value.parse::<u32>() {
+            return Ok(RangeBound::Sequence(sequence));
+        }
+        if let Ok(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Ok(RangeBound::Day(day));
+        }
+        DateTime::parse_from_rfc3339(value)
+            .map(|date| RangeBound::Instant(date.with_timezone(&Utc)))
+            .map_err(|_| {
+                format!(
+                    "'{}' is not a sequence number, YYYY-MM-DD date or RFC 3339 timestamp",
+                    value
+                )
+            })
+    }
+}
+
+impl fmt::Display for RangeBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeBound::Sequence(sequence) => write!(f, "{}", sequence),
+            RangeBound::Day(day) => write!(f, "{}", day.format("%Y-%m-%d")),
+            RangeBound::Instant(date) => write!(f, "{}", date.format(DATE_FORMAT)),
+        }
+    }
+}
+
+/// Inclusive range of entries to export; open ends include everything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportRange {
+    pub from: Option<RangeBound>,
+    pub to: Option<RangeBound>,
+}
+
+impl ExportRange {
+    pub fn contains(&self, entry: &Entry) -> bool {
+        let date = entry.content.date;
+        let after_from = match self.from {
+            None => true,
+            Some(RangeBound::Sequence(sequence)) => entry.sequence >= sequence,
+            Some(RangeBound::Day(day)) => date.date_naive() >= day,
+            Some(RangeBound::Instant(instant)) => date >= instant,
+        };
+        let before_to = match self.to {
+            None => true,
+            Some(RangeBound::Sequence(sequence)) => entry.sequence <= sequence,
+            Some(RangeBound::Day(day)) => date.date_naive() < day + Duration::days(1),
+            Some(RangeBound::Instant(instant)) => date <= instant,
+        };
+        after_from && before_to
+    }
+}
+
+/// Export entries in `range` from the worklog in `base_dir`, in chain order.
+///
+/// Every record carries the exact entry text (`content`) next to its parsed
+/// fields, so a consumer can re-verify the chain without Engram: each
+/// record's SHA-256 of `content` equals `hash`, and `previous` equals the
+/// preceding record's `hash` (`none` for entry 1).
+pub fn export_data(
+    base_dir: &Path,
+    format: DataFormat,
+    range: ExportRange,
+) -> Result<String, EngramError> {
+    let repo = Repository::open(base_dir)?;
+    let mut records = Vec::new();
+    for entry in repo.entries()? {
+        if range.contains(&entry) {
+            records.push(data_record(&entry)?);
+        }
+    }
+
+    let mut output = match format {
+        DataFormat::Jsonl => records
+            .iter()
+            .map(|record| record.to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        DataFormat::Json => {
+            let document = json!({
+                "engram_export": DATA_EXPORT_VERSION,
+                "hash_algorithm": "sha256",
+                "verification": "sha256(content) == hash for every entry; previous == hash of the preceding entry, or \"none\" for sequence 1",
+                "range": {
+                    "from": range.from.map(|bound| bound.to_string()),
+                    "to": range.to.map(|bound| bound.to_string()),
+                },
+                "entries": records,
+            });
+            serde_json::to_string_pretty(&document).expect("JSON values always serialize")
+        }
+        DataFormat::Csv => {
+            let mut lines = vec![CSV_COLUMNS.join(",")];
+            for record in &records {
+                let fields: Vec<String> = CSV_COLUMNS
+                    .iter()
+                    .map(|column| match &record[*column] {
+                        Value::String(text) => csv_field(text),
+                        other => csv_field(&other.to_string()),
+                    })
+                    .collect();
+                lines.push(fields.join(","));
+            }
+            lines.join("\r\n")
+        }
+    };
+
+    if !output.is_empty() {
+        output.push_str(if format == DataFormat::Csv {
+            "\r\n"
+        } else {
+            "\n"
+        });
+    }
+    Ok(output)
+}
+
+fn data_record(entry: &Entry) -> Result<Value, EngramError> {
+    let sections: Vec<Value> = entry
+        .content
+        .sections()
+        .into_iter()
+        .map(|(heading, content)| json!({"heading": heading, "content": content}))
+        .collect();
+
+    Ok(json!({
+        "sequence": entry.sequence,
+        "filename": entry.filename,
+        "hash": entry.hash,
+        "previous": entry.content.previous,
+        "date": entry.content.date.format(DATE_FORMAT).to_string(),
+        "summary": entry.content.summary,
+        "body": entry.content.body,
+        "sections": sections,
+        "headers": entry.headers_json(),
+        "content": fs::read_to_string(&entry.path)?,
+    }))
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Result of `engram export html`
+#[derive(Debug)]
+pub struct HtmlExport {
+    pub out: PathBuf,
+    /// Files written, relative to `out`, in a stable order
+    pub files: Vec<String>,
+    /// Verification result embedded in the pages
+    pub verify: Result<VerifyResult, EngramError>,
+}
+
+impl HtmlExport {
+    /// JSON form used by `--json`
+    pub fn to_json(&self) -> Value {
+        let verify = match &self.verify {
+            Ok(result) => result.to_json(),
+            Err(e) => json!({"verified": false, "error": e.to_json()["error"]}),
+        };
+        json!({
+            "out": self.out.display().to_string(),
+            "files": self.files,
+            "verify": verify,
+        })
+    }
+}
+
+/// Render the worklog in `base_dir` as a static site under `out`.
+///
+/// Output depends only on the entries and their verification result (no
+/// export timestamp), so two exports of the same worklog are byte-identical.
+/// A broken chain does not stop the export; it is shown on every page.
+/// `out` must be missing, empty or a previous export, which is replaced.
+pub fn export_html(base_dir: &Path, out: &Path) -> Result<HtmlExport, EngramError> {
+    let repo = Repository::open(base_dir)?;
+    let entries = repo.entries()?;
+    let verify = repo.verify();
+    let badge = html::verify_badge(&verify);
+
+    clear_previous_export(out)?;
+    fs::create_dir_all(out.join(ENTRIES_DIR))?;
+    let mut files = Vec::new();
+    let mut write = |relative: String, content: String| -> Result<(), EngramError> {
+        fs::write(out.join(&relative), content)?;
+        files.push(relative);
+        Ok(())
+    };
+
+    let header = format!(
+        "{}<input id=\"search\" type=\"search\" placeholder=\"Search entries\">",
+        badge
+    );
+    let footer = format!(
+        "<p><a href=\"{}\">Chain integrity</a></p>\n{}",
+        CHAIN_PAGE, SEARCH_SCRIPT
+    );
+    write(
+        INDEX_PAGE.to_string(),
+        html::timeline(
+            &entries,
+            &header,
+            |entry| format!("{}/{}", ENTRIES_DIR, page_name(entry)),
+            &footer,
+        ),
+    )?;
+
+    write(
+        CHAIN_PAGE.to_string(),
+        html::chain_page(&entries, &badge, INDEX_PAGE, |entry| {
+            format!("{}/{}", ENTRIES_DIR, page_name(entry))
+        }),
+    )?;
+
+    let index = search_index(&entries);
+    write(
+        SEARCH_SCRIPT_INDEX.to_string(),
+        format!("window.ENGRAM_SEARCH_INDEX = {}", index.trim_end()) + ";\n",
+    )?;
+    write(SEARCH_INDEX.to_string(), index)?;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|i| &entries[i]);
+        let next = entries.get(index + 1);
+        write(
+            format!("{}/{}", ENTRIES_DIR, page_name(entry)),
+            html::entry_page(entry, previous, next, "../index.html", page_name),
+        )?;
+    }
+
+    Ok(HtmlExport {
+        out: out.to_path_buf(),
+        files,
+        verify,
+    })
+}
+
+/// Remove an earlier export from `out` so pages of entries that no longer
+/// exist do not linger. Anything that does not look like export output is
+/// refused rather than deleted.
+fn clear_previous_export(out: &Path) -> Result<(), EngramError> {
+    if !out.exists() {
+        return Ok(());
+    }
+    let site_files = [INDEX_PAGE, CHAIN_PAGE, SEARCH_INDEX, SEARCH_SCRIPT_INDEX];
+    let mut names = Vec::new();
+    for item in fs::read_dir(out)? {
+        names.push(item?.file_name().to_string_lossy().into_owned());
+    }
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let entries_dir = out.join(ENTRIES_DIR);
+    let mut pages = Vec::new();
+    if entries_dir.is_dir() {
+        for item in fs::read_dir(&entries_dir)? {
+            pages.push(item?.path());
+        }
+    }
+    let is_export = names.iter().any(|name| name == INDEX_PAGE)
+        && names
+            .iter()
+            .all(|name| name == ENTRIES_DIR || site_files.contains(&name.as_str()))
+        && pages
+            .iter()
+            .all(|page| page.is_file() && page.extension().is_some_and(|ext| ext == "html"));
+    if !is_export {
+        return Err(EngramError::Config(format!(
+            "{} is not empty and does not hold a previous export; choose an empty or new directory",
+            out.display()
+        )));
+    }
+
+    for name in names.iter().filter(|name| *name != ENTRIES_DIR) {
+        fs::remove_file(out.join(name))?;
+    }
+    for page in pages {
+        fs::remove_file(page)?;
+    }
+    Ok(())
+}
+
+/// "000002_e5f6a7b8.md" -> "000002_e5f6a7b8.html"
+fn page_name(entry: &Entry) -> String {
+    format!("{}.html", entry.filename.trim_end_matches(".md"))
+}
+
+fn search_index(entries: &[Entry]) -> String {
+    let index: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "sequence": entry.sequence,
+                "href": format!("{}/{}", ENTRIES_DIR, page_name(entry)),
+                "date": entry.content.date.format(DATE_FORMAT).to_string(),
+                "summary": entry.content.summary,
+                "body": entry.content.body,
+            })
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&index).expect("JSON values always serialize");
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use crate::utils::clock::FixedClock;
+    use chrono::{TimeZone, Utc};
+    use tempfile::tempdir;
+
+    fn repo_with_entries(summaries: &[&str]) -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap());
+        for summary in summaries {
+            repo.write_draft(&format!(
+                "<summary>{}</summary>\n\n## Changes\n- Work on {}",
+                summary, summary
+            ))
+            .unwrap();
+            repo.commit_with_clock(&clock).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_export_jsonl_records_reverify() {
+        let dir = repo_with_entries(&["First", "Second"]);
+        let output = export_data(dir.path(), DataFormat::Jsonl, ExportRange::default()).unwrap();
+
+        let records: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        let mut previous = "none".to_string();
+        for record in &records {
+            let content = record["content"].as_str().unwrap();
+            assert_eq!(
+                crate::utils::hash::sha256_hex(content.as_bytes()),
+                record["hash"]
+            );
+            assert_eq!(record["previous"], previous.as_str());
+            previous = record["hash"].as_str().unwrap().to_string();
+        }
+        assert_eq!(records[1]["sections"][0]["heading"], "Changes");
+        assert_eq!(records[1]["sections"][0]["content"], "- Work on Second");
+    }
+
+    #[test]
+    fn test_export_json_and_csv() {
+        let dir = repo_with_entries(&["First, with comma", "Second"]);
+
+        let document: Value = serde_json::from_str(
+            &export_data(dir.path(), DataFormat::Json, ExportRange::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(document["engram_export"], 1);
+        assert_eq!(document["entries"].as_array().unwrap().len(), 2);
+
+        let csv = export_data(dir.path(), DataFormat::Csv, ExportRange::default()).unwrap();
+        let mut lines = csv.split("\r\n");
+        assert_eq!(
+            lines.next().unwrap(),
+            "sequence,filename,hash,previous,date,summary,body,sections,headers,content"
+        );
+        assert!(lines.next().unwrap().contains(",\"First, with comma\","));
+    }
+
+    #[test]
+    fn test_export_range_filters() {
+        let dir = repo_with_entries(&["One", "Two", "Three"]);
+        let export = |from: &str, to: &str| {
+            let range = ExportRange {
+                from: Some(from.parse().unwrap()),
+                to: Some(to.parse().unwrap()),
+            };
+            export_data(dir.path(), DataFormat::Jsonl, range)
+                .unwrap()
+                .lines()
+                .count()
+        };
+
+        assert_eq!(export("2", "3"), 2);
+        assert_eq!(export("2", "2"), 1);
+        assert_eq!(export("2025-06-12", "2025-06-12"), 3);
+        assert_eq!(export("2025-06-13", "2025-06-20"), 0);
+        assert_eq!(export("2025-06-12T14:32:07Z", "3"), 3);
+        assert!("yesterday".parse::<RangeBound>().is_err());
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\"\n"), "\"say \"\"hi\"\"\n\"");
+    }
+
+    #[test]
+    fn test_export_html_writes_site() {
+        let dir = repo_with_entries(&["First", "Second"]);
+        let out = dir.path().join("site");
+        let export = export_html(dir.path(), &out).unwrap();
+        let entries = Repository::open(dir.path()).unwrap().entries().unwrap();
+
+        assert_eq!(export.files.len(), 6);
+        assert!(export.verify.is_ok());
+
+        let index = fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains(&format!("href=\"entries/{}\"", page_name(&entries[1]))));
+        assert!(index.contains("Chain verified (2 entries)"));
+
+        let second = fs::read_to_string(out.join("entries").join(page_name(&entries[1]))).unwrap();
+        assert!(second.contains("<li>Work on Second</li>"));
+        assert!(second.contains(&format!("href=\"{}\"", page_name(&entries[0]))));
+        assert!(second.contains("href=\"../index.html\""));
+
+        let chain = fs::read_to_string(out.join("chain.html")).unwrap();
+        assert_eq!(chain.matches("<td>✓</td>").count(), 2);
+        assert!(chain.contains(&entries[0].hash));
+
+        let search: Value =
+            serde_json::from_str(&fs::read_to_string(out.join("search-index.json")).unwrap())
+                .unwrap();
+        assert_eq!(search[1]["summary"], "Second");
+
+        // The page loads the index as a script so search works from file://
+        assert!(index.contains("<script src=\"search-index.js\"></script>"));
+        let script = fs::read_to_string(out.join("search-index.js")).unwrap();
+        let json = script
+            .strip_prefix("window.ENGRAM_SEARCH_INDEX = ")
+            .and_then(|rest| rest.strip_suffix(";\n"))
+            .unwrap();
+        assert_eq!(serde_json::from_str::<Value>(json).unwrap(), search);
+    }
+
+    #[test]
+    fn test_export_html_is_deterministic() {
+        let dir = repo_with_entries(&["First", "Second"]);
+        let first = dir.path().join("a");
+        let second = dir.path().join("b");
+        let export = export_html(dir.path(), &first).unwrap();
+        export_html(dir.path(), &second).unwrap();
+
+        for file in &export.files {
+            assert_eq!(
+                fs::read(first.join(file)).unwrap(),
+                fs::read(second.join(file)).unwrap(),
+                "{} differs",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_export_html_replaces_previous_export() {
+        let dir = repo_with_entries(&["First"]);
+        let out = dir.path().join("site");
+        export_html(dir.path(), &out).unwrap();
+        let stale = out.join("entries").join("000009_deadbeef.html");
+        fs::write(&stale, "old").unwrap();
+
+        export_html(dir.path(), &out).unwrap();
+        assert!(!stale.exists());
+        assert_eq!(fs::read_dir(out.join("entries")).unwrap().count(), 1);
+
+        // A directory holding anything else is left alone
+        let other = dir.path().join("docs");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("notes.md"), "keep").unwrap();
+        assert!(matches!(
+            export_html(dir.path(), &other),
+            Err(EngramError::Config(_))
+        ));
+        assert_eq!(fs::read_to_string(other.join("notes.md")).unwrap(), "keep");
+        assert!(!other.join("index.html").exists());
+    }
+
+    #[test]
+    fn test_export_html_embeds_broken_chain() {
+        let dir = repo_with_entries(&["First"]);
+        fs::write(dir.path().join(".engram/worklog/stray.txt"), "x").unwrap();
+        let out = dir.path().join("site");
+
+        let export = export_html(dir.path(), &out).unwrap();
+        assert!(export.verify.is_err());
+        assert_eq!(
+            export.to_json()["verify"]["error"]["code"],
+            "E_UNEXPECTED_FILE"
+        );
+        let chain = fs::read_to_string(out.join("chain.html")).unwrap();
+        assert!(chain.contains("badge broken"));
+    }
+}
@@ -0,0 +1,721 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::commands::verify::{collect_entries, verify_entries};
+use crate::engram::refs::{Reference, REFS_HEADER};
+use crate::engram::worklog::{EntryContent, WorklogEntry, DATE_FORMAT};
+use crate::error::EngramError;
+use crate::repository::{Entry, Repository};
+use crate::utils::clock::Clock;
+use crate::utils::hash::{sha256_hex, sha256_short};
+
+/// Entries are written here and verified before they move into the worklog
+const STAGING_DIR: &str = ".engram/staging";
+
+/// Provenance headers `--rechain` writes; a record's own are replaced, not stacked
+const SOURCE_HEADERS: [&str; 3] = ["Source-Entry", "Source-Hash", "Source-Date"];
+
+/// How `engram import` places records in the worklog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Write the original entry bytes; hashes, dates and sequence numbers must
+    /// continue the existing chain
+    Preserve,
+    /// Commit each record as a new entry on top of the chain, recording where
+    /// it came from in Source-Entry/Source-Hash/Source-Date headers
+    Rechain,
+}
+
+/// Result of a successful import
+#[derive(Debug)]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    /// Filenames written, in order
+    pub imported: Vec<String>,
+    /// Records already present in the worklog (same hash and sequence)
+    pub skipped: usize,
+}
+
+impl ImportReport {
+    /// JSON form used by `--json`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mode": match self.mode {
+                ImportMode::Preserve => "preserve",
+                ImportMode::Rechain => "rechain",
+            },
+            "imported": self.imported,
+            "skipped": self.skipped,
+        })
+    }
+}
+
+/// A record from the import file, resolved to entry text
+struct Record {
+    number: usize, // 1-based, for error messages
+    sequence: Option<u32>,
+    filename: Option<String>,
+    text: String,
+    content: EntryContent,
+}
+
+/// Replay records exported by `engram export` (JSON Lines, or the `--format
+/// json` document) into the worklog in `base_dir`.
+///
+/// The existing worklog must verify, and every record is checked before
+/// anything is written, so a rejected import leaves the worklog untouched.
+/// SUMMARY.md is rebuilt from the entries on disk afterwards.
+pub fn run_import_in_dir(
+    base_dir: &Path,
+    source: &Path,
+    mode: ImportMode,
+    clock: &dyn Clock,
+) -> Result<ImportReport, EngramError> {
+    let repo = Repository::open(base_dir)?;
+    repo.verify()?;
+    let records = parse_records(&fs::read_to_string(source)?)?;
+    let existing = repo.entries()?;
+
+    let (planned, skipped) = match mode {
+        ImportMode::Preserve => plan_preserve(&existing, records)?,
+        ImportMode::Rechain => (plan_rechain(&existing, records, clock)?, 0),
+    };
+
+    Ok(ImportReport {
+        mode,
+        imported: write_entries(&repo, planned)?,
+        skipped,
+    })
+}
+
+/// Write planned (filename, text) entries and rebuild SUMMARY.md from the
+/// entries on disk. The entries are staged outside the worklog and verified
+/// together with the existing ones first, so a plan that would break the
+/// chain (a future date, say) writes nothing. Returns the filenames written.
+pub(crate) fn write_entries(
+    repo: &Repository,
+    planned: Vec<(String, String)>,
+) -> Result<Vec<String>, EngramError> {
+    let worklog_dir = repo.worklog_path();
+    let staging_dir = repo.root().join(STAGING_DIR);
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    fs::create_dir_all(&staging_dir)?;
+
+    let staged = stage_entries(&worklog_dir, &staging_dir, &planned).and_then(|()| {
+        for (filename, _) in &planned {
+            fs::rename(staging_dir.join(filename), worklog_dir.join(filename))?;
+        }
+        Ok(())
+    });
+    fs::remove_dir_all(&staging_dir)?;
+    staged?;
+
+    repo.rebuild_summary()?;
+    Ok(planned.into_iter().map(|(filename, _)| filename).collect())
+}
+
+/// Write `planned` into `staging_dir` and verify them after the entries in
+/// `worklog_dir`
+fn stage_entries(
+    worklog_dir: &Path,
+    staging_dir: &Path,
+    planned: &[(String, String)],
+) -> Result<(), EngramError> {
+    let mut entries = collect_entries(worklog_dir)?;
+    for (filename, text) in planned {
+        fs::write(staging_dir.join(filename), text)?;
+        entries.push(
+            WorklogEntry::from_filename(filename, staging_dir)
+                .ok_or_else(|| EngramError::InvalidEntry(filename.clone()))?,
+        );
+    }
+    verify_entries(entries, Utc::now())?;
+    Ok(())
+}
+
+/// Sequence number of the entry after `existing`, which verified as
+/// numbered 1..=n
+pub(crate) fn next_sequence(existing: &[Entry]) -> Result<u32, EngramError> {
+    existing
+        .last()
+        .map_or(0, |entry| entry.sequence)
+        .checked_add(1)
+        .ok_or_else(|| EngramError::Import("the worklog has no sequence numbers left".to_string()))
+}
+
+/// Link `contents` onto the end of the chain: fills in each `previous` and
+/// returns the (filename, text) pairs to write, in order
+pub(crate) fn chain_entries(
+    existing: &[Entry],
+    contents: Vec<EntryContent>,
+) -> Result<Vec<(String, String)>, EngramError> {
+    let mut tip = existing
+        .last()
+        .map_or("none".to_string(), |entry| entry.hash.clone());
+    let first_sequence = next_sequence(existing)?;
+    let mut planned = Vec::with_capacity(contents.len());
+
+    for (sequence, mut content) in (first_sequence..).zip(contents) {
+        content.previous = tip;
+        let text = content.to_string();
+        tip = sha256_hex(text.as_bytes());
+        planned.push((format!("{:06}_{}.md", sequence, sha256_short(&text)), text));
+    }
+    Ok(planned)
+}
+
+/// Entries to write for a hash-preserving import, and how many records were
+/// already present
+fn plan_preserve(
+    existing: &[Entry],
+    records: Vec<Record>,
+) -> Result<(Vec<(String, String)>, usize), EngramError> {
+    let mut tip = existing
+        .last()
+        .map_or("none".to_string(), |entry| entry.hash.clone());
+    let mut latest_date = existing.last().map(|entry| entry.content.date);
+    let mut sequence = next_sequence(existing)?;
+    let mut planned = Vec::new();
+    let mut planned_hashes = Vec::new();
+    let mut skipped = 0;
+
+    for record in records {
+        let hash = sha256_hex(record.text.as_bytes());
+
+        if let Some(present) = existing.iter().find(|entry| entry.hash == hash) {
+            if planned.is_empty() && record.sequence.is_none_or(|s| s == present.sequence) {
+                skipped += 1;
+                continue;
+            }
+        }
+
+        let filename = format!("{:06}_{}.md", sequence, &hash[..8]);
+        if record.sequence.is_some_and(|s| s != sequence) {
+            return Err(reject(
+                &record,
+                format!(
+                    "sequence does not continue the worklog (expected {})",
+                    sequence
+                ),
+            ));
+        }
+        if record.filename.as_ref().is_some_and(|f| *f != filename) {
+            return Err(reject(
+                &record,
+                format!(
+                    "filename does not match its content (expected {})",
+                    filename
+                ),
+            ));
+        }
+        if record.content.previous != tip {
+            return Err(reject(
+                &record,
+                format!(
+                    "Previous: {} does not match the preceding entry ({})",
+                    record.content.previous, tip
+                ),
+            ));
+        }
+        if latest_date.is_some_and(|latest| record.content.date < latest) {
+            return Err(reject(
+                &record,
+                "date is earlier than the preceding entry".to_string(),
+            ));
+        }
+
+        for reference in record.content.refs() {
+            if let Reference::Entry(target) = reference {
+                let earlier = existing.iter().any(|entry| entry.hash == target)
+                    || planned_hashes.contains(&target);
+                if !earlier {
+                    return Err(reject(
+                        &record,
+                        format!("Refs: {} does not name an earlier entry", target),
+                    ));
+                }
+            }
+        }
+
+        planned_hashes.push(hash.clone());
+        tip = hash;
+        latest_date = Some(record.content.date);
+        sequence += 1;
+        planned.push((filename, record.text));
+    }
+
+    Ok((planned, skipped))
+}
+
+/// Entries to write when re-chaining records as new entries dated by `clock`
+fn plan_rechain(
+    existing: &[Entry],
+    records: Vec<Record>,
+    clock: &dyn Clock,
+) -> Result<Vec<(String, String)>, EngramError> {
+    let date = clock.now();
+    if let Some(latest) = existing.last().map(|entry| entry.content.date) {
+        if date < latest {
+            return Err(EngramError::ClockBehind { now: date, latest });
+        }
+    }
+
+    let mut tip = existing
+        .last()
+        .map_or("none".to_string(), |entry| entry.hash.clone());
+    let first_sequence = next_sequence(existing)?;
+    // Source hash -> new hash, so references between imported records follow them
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let mut planned = Vec::with_capacity(records.len());
+
+    for (sequence, record) in (first_sequence..).zip(records) {
+        check_header_text(&record)?;
+        let source_hash = sha256_hex(record.text.as_bytes());
+        let mut headers = Vec::with_capacity(record.content.headers.len() + 3);
+        for (name, value) in &record.content.headers {
+            if SOURCE_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            if name != REFS_HEADER {
+                headers.push((name.clone(), value.clone()));
+                continue;
+            }
+            let mut refs = Vec::new();
+            for reference in record.content.refs() {
+                refs.push(match reference {
+                    Reference::Entry(hash) => match renamed.get(&hash) {
+                        Some(new_hash) => new_hash.clone(),
+                        None if existing.iter().any(|entry| entry.hash == hash) => hash,
+                        None => {
+                            return Err(reject(
+                                &record,
+                                format!(
+                                    "Refs: {} is neither imported earlier nor in the worklog",
+                                    hash
+                                ),
+                            ))
+                        }
+                    },
+                    Reference::External(id) => id,
+                });
+            }
+            headers.push((name.clone(), refs.join(", ")));
+        }
+        if let Some(filename) = &record.filename {
+            headers.push(("Source-Entry".to_string(), filename.clone()));
+        }
+        headers.push(("Source-Hash".to_string(), source_hash.clone()));
+        headers.push((
+            "Source-Date".to_string(),
+            record.content.date.format(DATE_FORMAT).to_string(),
+        ));
+
+        let text = EntryContent {
+            summary: record.content.summary,
+            previous: tip,
+            date,
+            headers,
+            body: record.content.body,
+        }
+        .to_string();
+
+        tip = sha256_hex(text.as_bytes());
+        renamed.insert(source_hash, tip.clone());
+        planned.push((format!("{:06}_{}.md", sequence, sha256_short(&text)), text));
+    }
+
+    Ok(planned)
+}
+
+/// Summary, header names and values become single lines of the new entry, so
+/// a line break or control character could forge a header or the separator.
+/// Commit never produces them; reject them the same way.
+fn check_header_text(record: &Record) -> Result<(), EngramError> {
+    let has_control = |text: &str| text.chars().any(char::is_control);
+    if has_control(&record.content.summary) {
+        return Err(reject(
+            record,
+            "summary contains a line break or control character".to_string(),
+        ));
+    }
+    if let Some(filename) = record.filename.as_deref().filter(|name| has_control(name)) {
+        return Err(reject(
+            record,
+            format!("filename {:?} contains a control character", filename),
+        ));
+    }
+    for (name, value) in &record.content.headers {
+        let bad_name = name.is_empty()
+            || name.contains(':')
+            || name.chars().any(|c| c.is_whitespace() || c.is_control());
+        if bad_name || has_control(value) {
+            return Err(reject(
+                record,
+                format!("header {:?} is not a single-line header", name),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn reject(record: &Record, problem: String) -> EngramError {
+    EngramError::Import(format!("record {}: {}", record.number, problem))
+}
+
+/// Read JSON Lines, or a single document with an `entries` array
+fn parse_records(input: &str) -> Result<Vec<Record>, EngramError> {
+    let values: Vec<Value> = match serde_json::from_str::<Value>(input) {
+        Ok(Value::Object(mut document)) if document.contains_key("entries") => {
+            match document.remove("entries") {
+                Some(Value::Array(entries)) => entries,
+                _ => return Err(EngramError::Import("entries is not an array".to_string())),
+            }
+        }
+        _ => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    EngramError::Import(format!("line {}: invalid JSON ({})", index + 1, e))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| parse_record(index + 1, value))
+        .collect()
+}
+
+fn parse_record(number: usize, value: &Value) -> Result<Record, EngramError> {
+    let invalid = |problem: &str| EngramError::Import(format!("record {}: {}", number, problem));
+    let field = |name: &str| value.get(name).and_then(Value::as_str);
+
+    // The exact entry text wins; otherwise rebuild it from the parsed fields
+    let content = match field("content") {
+        Some(text) => {
+            EntryContent::parse(text).ok_or_else(|| invalid("content is not a valid entry"))?
+        }
+        None => {
+            let date = field("date")
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                .ok_or_else(|| invalid("missing or invalid date"))?
+                .with_timezone(&Utc);
+            let headers = match value.get("headers") {
+                Some(Value::Object(headers)) => headers
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            EntryContent {
+                summary: field("summary")
+                    .ok_or_else(|| invalid("missing summary"))?
+                    .to_string(),
+                previous: field("previous").unwrap_or("none").to_string(),
+                date,
+                headers,
+                body: field("body")
+                    .ok_or_else(|| invalid("missing body"))?
+                    .to_string(),
+            }
+        }
+    };
+    let text = field("content").map_or_else(|| content.to_string(), str::to_string);
+
+    if let Some(hash) = field("hash") {
+        if hash != sha256_hex(text.as_bytes()) {
+            return Err(invalid("hash does not match content"));
+        }
+    }
+
+    Ok(Record {
+        number,
+        sequence: value
+            .get("sequence")
+            .and_then(Value::as_u64)
+            .map(|s| u32::try_from(s).map_err(|_| invalid("sequence is out of range")))
+            .transpose()?,
+        filename: field("filename").map(str::to_string),
+        text,
+        content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::{export_data, DataFormat, ExportRange};
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use crate::utils::clock::FixedClock;
+    use chrono::TimeZone;
+    use tempfile::{tempdir, TempDir};
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap())
+    }
+
+    fn repo_with_entries(summaries: &[&str]) -> TempDir {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        for summary in summaries {
+            repo.write_draft(&format!(
+                "<summary>{}</summary>\n\n## Changes\n- Work",
+                summary
+            ))
+            .unwrap();
+            repo.commit_with_clock(&clock()).unwrap();
+        }
+        dir
+    }
+
+    fn export_to_file(source: &TempDir, format: DataFormat) -> std::path::PathBuf {
+        let path = source.path().join("export.jsonl");
+        let output = export_data(source.path(), format, ExportRange::default()).unwrap();
+        fs::write(&path, output).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_preserve_replays_identical_entries() {
+        let source = repo_with_entries(&["First", "Second"]);
+        let file = export_to_file(&source, DataFormat::Jsonl);
+        let target = repo_with_entries(&[]);
+
+        let report =
+            run_import_in_dir(target.path(), &file, ImportMode::Preserve, &clock()).unwrap();
+        assert_eq!(report.imported.len(), 2);
+
+        let original = Repository::open(source.path()).unwrap().entries().unwrap();
+        let imported = Repository::open(target.path()).unwrap().entries().unwrap();
+        assert_eq!(
+            original.iter().map(|e| &e.hash).collect::<Vec<_>>(),
+            imported.iter().map(|e| &e.hash).collect::<Vec<_>>()
+        );
+        let summary = fs::read_to_string(target.path().join(".engram/worklog/SUMMARY.md")).unwrap();
+        assert!(summary.contains(&format!("| {} | Second |", imported[1].filename)));
+
+        // Importing again is a no-op
+        let report =
+            run_import_in_dir(target.path(), &file, ImportMode::Preserve, &clock()).unwrap();
+        assert_eq!((report.imported.len(), report.skipped), (0, 2));
+    }
+
+    #[test]
+    fn test_preserve_accepts_json_document_and_continues_chain() {
+        let source = repo_with_entries(&["First", "Second", "Third"]);
+        let target = repo_with_entries(&[]);
+        let head = source.path().join("head.jsonl");
+        let range = |from, to| ExportRange {
+            from: Some(crate::commands::export::RangeBound::Sequence(from)),
+            to: Some(crate::commands::export::RangeBound::Sequence(to)),
+        };
+        fs::write(
+            &head,
+            export_data(source.path(), DataFormat::Json, range(1, 1)).unwrap(),
+        )
+        .unwrap();
+        run_import_in_dir(target.path(), &head, ImportMode::Preserve, &clock()).unwrap();
+
+        let tail = source.path().join("tail.jsonl");
+        fs::write(
+            &tail,
+            export_data(source.path(), DataFormat::Jsonl, range(2, 3)).unwrap(),
+        )
+        .unwrap();
+        let report =
+            run_import_in_dir(target.path(), &tail, ImportMode::Preserve, &clock()).unwrap();
+        assert_eq!(report.imported.len(), 2);
+        assert_eq!(
+            Repository::open(target.path())
+                .unwrap()
+                .verify()
+                .unwrap()
+                .entry_count,
+            3
+        );
+    }
+
+    #[test]
+    fn test_preserve_rejects_entries_that_do_not_chain() {
+        let source = repo_with_entries(&["First", "Second"]);
+        let file = export_to_file(&source, DataFormat::Jsonl);
+        let target = repo_with_entries(&["Unrelated"]);
+
+        match run_import_in_dir(target.path(), &file, ImportMode::Preserve, &clock()) {
+            Err(EngramError::Import(message)) => assert!(message.starts_with("record 1:")),
+            other => panic!("expected Import error, got {:?}", other.map(|r| r.imported)),
+        }
+        // Nothing was written
+        assert_eq!(
+            Repository::open(target.path())
+                .unwrap()
+                .entries()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_future_dated_record_writes_nothing() {
+        let target = repo_with_entries(&["First"]);
+        let repo = Repository::open(target.path()).unwrap();
+        let record = json!({
+            "summary": "From the future",
+            "previous": repo.entries().unwrap()[0].hash,
+            "date": "2099-01-01T00:00:00Z",
+            "body": "## Changes\n- Work",
+        });
+        let file = target.path().join("future.jsonl");
+        fs::write(&file, record.to_string()).unwrap();
+
+        assert!(matches!(
+            run_import_in_dir(target.path(), &file, ImportMode::Preserve, &clock()),
+            Err(EngramError::Chain(_))
+        ));
+        assert_eq!(repo.entries().unwrap().len(), 1);
+        assert!(!target.path().join(STAGING_DIR).exists());
+        repo.verify().unwrap();
+    }
+
+    #[test]
+    fn test_rejects_tampered_record() {
+        let source = repo_with_entries(&["First"]);
+        let file = export_to_file(&source, DataFormat::Jsonl);
+        let tampered = fs::read_to_string(&file)
+            .unwrap()
+            .replace("- Work", "- Play");
+        fs::write(&file, tampered).unwrap();
+        let target = repo_with_entries(&[]);
+
+        assert!(matches!(
+            run_import_in_dir(target.path(), &file, ImportMode::Preserve, &clock()),
+            Err(EngramError::Import(_))
+        ));
+    }
+
+    #[test]
+    fn test_rechain_follows_refs_between_records() {
+        let source = repo_with_entries(&["First"]);
+        let repo = Repository::open(source.path()).unwrap();
+        let first = repo.find_entry("1").unwrap();
+        repo.write_draft(&format!(
+            "<summary>Revert first</summary>\nRefs: {}, #7\n\n## Changes\n- Work",
+            first.hash
+        ))
+        .unwrap();
+        repo.commit_with_clock(&clock()).unwrap();
+        let file = export_to_file(&source, DataFormat::Jsonl);
+        let target = repo_with_entries(&["Already here"]);
+
+        run_import_in_dir(target.path(), &file, ImportMode::Rechain, &clock()).unwrap();
+
+        let target = Repository::open(target.path()).unwrap();
+        let imported_first = target.find_entry("2").unwrap();
+        let revert = target.find_entry("3").unwrap();
+        assert_eq!(
+            revert.content.header("Refs"),
+            Some(format!("{}, #7", imported_first.hash).as_str())
+        );
+        assert!(target.verify().is_ok());
+    }
+
+    #[test]
+    fn test_rechain_adds_provenance_headers() {
+        let source = repo_with_entries(&["First", "Second"]);
+        let file = export_to_file(&source, DataFormat::Jsonl);
+        let target = repo_with_entries(&["Already here"]);
+
+        let report =
+            run_import_in_dir(target.path(), &file, ImportMode::Rechain, &clock()).unwrap();
+        assert_eq!(report.imported.len(), 2);
+
+        let original = Repository::open(source.path()).unwrap().entries().unwrap();
+        let entries = Repository::open(target.path()).unwrap().entries().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].content.summary, "First");
+        assert_eq!(entries[1].content.previous, entries[0].hash);
+        assert_eq!(
+            entries[1].content.header("Source-Hash"),
+            Some(original[0].hash.as_str())
+        );
+        assert_eq!(
+            entries[2].content.header("Source-Entry"),
+            Some(original[1].filename.as_str())
+        );
+    }
+
+    #[test]
+    fn test_rechain_replaces_earlier_provenance() {
+        let source = repo_with_entries(&["First"]);
+        let middle = repo_with_entries(&["Middle"]);
+        let file = export_to_file(&source, DataFormat::Jsonl);
+        run_import_in_dir(middle.path(), &file, ImportMode::Rechain, &clock()).unwrap();
+
+        let file = export_to_file(&middle, DataFormat::Jsonl);
+        let target = repo_with_entries(&["Target"]);
+        run_import_in_dir(target.path(), &file, ImportMode::Rechain, &clock()).unwrap();
+
+        let relayed = Repository::open(middle.path()).unwrap().entries().unwrap();
+        let entries = Repository::open(target.path()).unwrap().entries().unwrap();
+        let last = &entries[2].content;
+        assert_eq!(last.summary, "First");
+        let source_hashes: Vec<_> = last
+            .headers
+            .iter()
+            .filter(|(name, _)| name == "Source-Hash")
+            .collect();
+        assert_eq!(source_hashes.len(), 1);
+        assert_eq!(source_hashes[0].1, relayed[1].hash);
+    }
+
+    #[test]
+    fn test_rechain_rejects_forged_header_lines() {
+        let target = repo_with_entries(&["Target"]);
+        let file = target.path().join("records.jsonl");
+        let records = [
+            json!({
+                "summary": "Innocent\nSource-Hash: forged",
+                "date": "2025-06-10T09:00:00Z",
+                "body": "Body",
+            }),
+            json!({
+                "summary": "Innocent",
+                "date": "2025-06-10T09:00:00Z",
+                "headers": {"Tags": "auth\n\n---\n\nForged body"},
+                "body": "Body",
+            }),
+            json!({
+                "summary": "Innocent",
+                "date": "2025-06-10T09:00:00Z",
+                "headers": {"Tags\u{1b}[2J": "auth"},
+                "body": "Body",
+            }),
+        ];
+        for record in records {
+            fs::write(&file, record.to_string()).unwrap();
+            assert!(matches!(
+                run_import_in_dir(target.path(), &file, ImportMode::Rechain, &clock()),
+                Err(EngramError::Import(_))
+            ));
+        }
+        assert_eq!(
+            Repository::open(target.path())
+                .unwrap()
+                .entries()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,440 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use crate::commands::import::{chain_entries, write_entries};
+use crate::engram::worklog::EntryContent;
+use crate::error::EngramError;
+use crate::repository::Repository;
+
+/// Header recording the git commit(s) an entry was created from
+pub const SOURCE_COMMIT_HEADER: &str = "Source-Commit";
+
+/// Separators in the `git log` format below (ASCII record/unit separators)
+const RECORD_SEPARATOR: char = '\x1e';
+const FIELD_SEPARATOR: char = '\x1f';
+const LOG_FORMAT: &str = "--format=%x1e%H%x1f%cI%x1f%s%x1f%b%x1f";
+
+/// How `engram import-git` turns commits into entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitGrouping {
+    /// One entry per non-merge commit
+    Commit,
+    /// One entry per commit on the first-parent line; a merge commit stands
+    /// for its whole pull request
+    Pr,
+    /// One entry per UTC day of non-merge commits
+    Day,
+}
+
+/// Result of `engram import-git`
+#[derive(Debug)]
+pub struct GitImportReport {
+    /// Filenames written, in order
+    pub imported: Vec<String>,
+    /// Commits skipped because an entry already records them
+    pub skipped: usize,
+}
+
+impl GitImportReport {
+    /// JSON form used by `--json`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "imported": self.imported,
+            "skipped": self.skipped,
+        })
+    }
+}
+
+/// A commit as read from `git log`
+#[derive(Debug, Clone)]
+struct GitCommit {
+    hash: String,
+    date: DateTime<Utc>,
+    subject: String,
+    body: String,
+    /// "M src/main.rs", "R old.rs -> new.rs"
+    files: Vec<String>,
+}
+
+/// Create worklog entries from the git history of the repository at
+/// `base_dir`, oldest first, starting after `since` (or at the root commit).
+///
+/// Entries are dated by commit date, raised where needed so dates never go
+/// backwards along the chain and capped at the current time, since a
+/// committer's clock may run ahead. Commits already recorded in a `Source-Commit:`
+/// header are skipped, so the import can be re-run as history grows.
+pub fn run_import_git_in_dir(
+    base_dir: &Path,
+    since: Option<&str>,
+    grouping: GitGrouping,
+) -> Result<GitImportReport, EngramError> {
+    let repo = Repository::open(base_dir)?;
+    repo.verify()?;
+    let existing = repo.entries()?;
+
+    let recorded: HashSet<String> = existing
+        .iter()
+        .filter_map(|entry| entry.content.header(SOURCE_COMMIT_HEADER))
+        .flat_map(|value| value.split(',').map(|hash| hash.trim().to_string()))
+        .collect();
+
+    let commits = git_log(base_dir, since, grouping)?;
+    let total = commits.len();
+    let commits: Vec<GitCommit> = commits
+        .into_iter()
+        .filter(|commit| !recorded.contains(&commit.hash))
+        .collect();
+    let skipped = total - commits.len();
+
+    let mut contents = match grouping {
+        GitGrouping::Commit => commits.iter().map(commit_entry).collect(),
+        GitGrouping::Pr => commits.iter().map(pr_entry).collect(),
+        GitGrouping::Day => day_entries(&commits),
+    };
+
+    let now = Utc::now();
+    let mut floor = existing.last().map(|entry| entry.content.date);
+    for content in &mut contents {
+        content.date = content.date.min(now);
+        if let Some(floor) = floor.filter(|floor| content.date < *floor) {
+            content.date = floor;
+        }
+        floor = Some(content.date);
+    }
+
+    let planned = chain_entries(&existing, contents)?;
+    Ok(GitImportReport {
+        imported: write_entries(&repo, planned)?,
+        skipped,
+    })
+}
+
+fn git_log(
+    base_dir: &Path,
+    since: Option<&str>,
+    grouping: GitGrouping,
+) -> Result<Vec<GitCommit>, EngramError> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(base_dir)
+        .args(["log", "--reverse", LOG_FORMAT, "--name-status"]);
+    match grouping {
+        GitGrouping::Pr => command.args(["--first-parent", "-m"]),
+        GitGrouping::Commit | GitGrouping::Day => command.arg("--no-merges"),
+    };
+    match since {
+        // git would read `--since=-…` as another option
+        Some(since) if since.starts_with('-') => {
+            return Err(EngramError::Import(format!(
+                "'{}' is not a revision",
+                since
+            )))
+        }
+        Some(since) => command.arg(format!("{}..HEAD", since)),
+        None => command.arg("HEAD"),
+    };
+    command.arg("--");
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(EngramError::Import(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(parse_commit)
+        .collect()
+}
+
+fn parse_commit(record: &str) -> Result<GitCommit, EngramError> {
+    let fields: Vec<&str> = record.splitn(5, FIELD_SEPARATOR).collect();
+    let [hash, date, subject, body, files] = fields[..] else {
+        return Err(EngramError::Import(format!(
+            "unexpected git log output: {}",
+            record.trim()
+        )));
+    };
+
+    let date = DateTime::parse_from_rfc3339(date)
+        .map_err(|_| EngramError::Import(format!("commit {} has an invalid date {}", hash, date)))?
+        .with_timezone(&Utc);
+
+    let files = files
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let parts: Vec<&str> = line.split('\t').collect();
+            match parts[..] {
+                [status, from, to] => format!("{} {} -> {}", &status[..1], from, to),
+                [status, path] => format!("{} {}", status, path),
+                _ => line.to_string(),
+            }
+        })
+        .collect();
+
+    Ok(GitCommit {
+        hash: hash.to_string(),
+        date,
+        subject: subject.trim().to_string(),
+        body: body.trim().to_string(),
+        files,
+    })
+}
+
+fn commit_entry(commit: &GitCommit) -> EntryContent {
+    entry(commit, summary_of(commit), &commit.body)
+}
+
+/// Like `commit_entry`, but a GitHub-style merge commit is summarized by the
+/// pull request title (the first line of its body) and number
+fn pr_entry(commit: &GitCommit) -> EntryContent {
+    let pr_number = commit
+        .subject
+        .strip_prefix("Merge pull request #")
+        .and_then(|rest| rest.split_whitespace().next());
+    match pr_number {
+        Some(number) if !commit.body.is_empty() => {
+            let (title, rest) = commit
+                .body
+                .split_once('\n')
+                .unwrap_or((commit.body.as_str(), ""));
+            entry(
+                commit,
+                format!("{} (#{})", title.trim(), number),
+                rest.trim(),
+            )
+        }
+        _ => commit_entry(commit),
+    }
+}
+
+fn entry(commit: &GitCommit, summary: String, description: &str) -> EntryContent {
+    let mut body = String::new();
+    if !description.is_empty() {
+        body.push_str(&format!("## Changes\n{}\n\n", description));
+    }
+    body.push_str(&files_section(commit.files.iter()));
+
+    EntryContent {
+        summary,
+        previous: String::new(),
+        date: commit.date,
+        headers: vec![(SOURCE_COMMIT_HEADER.to_string(), commit.hash.clone())],
+        body,
+    }
+}
+
+fn day_entries(commits: &[GitCommit]) -> Vec<EntryContent> {
+    let mut days: Vec<Vec<&GitCommit>> = Vec::new();
+    for commit in commits {
+        match days.last_mut() {
+            Some(day) if day[0].date.date_naive() == commit.date.date_naive() => day.push(commit),
+            _ => days.push(vec![commit]),
+        }
+    }
+
+    days.into_iter()
+        .map(|day| {
+            let summary = match day.len() {
+                1 => summary_of(day[0]),
+                n => format!("{} (+{} more)", summary_of(day[0]), n - 1),
+            };
+
+            let mut body = String::from("## Commits\n");
+            for commit in &day {
+                body.push_str(&format!("- {} {}\n", &commit.hash[..8], commit.subject));
+            }
+            body.push('\n');
+            let files: BTreeSet<&String> = day.iter().flat_map(|commit| &commit.files).collect();
+            body.push_str(&files_section(files.into_iter()));
+
+            let hashes: Vec<&str> = day.iter().map(|commit| commit.hash.as_str()).collect();
+            EntryContent {
+                summary,
+                previous: String::new(),
+                date: day.last().expect("days are never empty").date,
+                headers: vec![(SOURCE_COMMIT_HEADER.to_string(), hashes.join(", "))],
+                body,
+            }
+        })
+        .collect()
+}
+
+fn summary_of(commit: &GitCommit) -> String {
+    if commit.subject.is_empty() {
+        format!("Commit {}", &commit.hash[..8])
+    } else {
+        commit.subject.clone()
+    }
+}
+
+fn files_section<'a>(files: impl Iterator<Item = &'a String>) -> String {
+    let mut section = String::from("## Files\n");
+    let mut any = false;
+    for file in files {
+        section.push_str(&format!("- {}\n", file));
+        any = true;
+    }
+    if !any {
+        section.push_str("(no files changed)\n");
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use std::fs;
+    use tempfile::{tempdir, TempDir};
+
+    fn git(dir: &Path, args: &[&str], date: &str) {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    /// A git repository with three commits over two days, then engram init
+    fn history() -> TempDir {
+        let dir = tempdir().unwrap();
+        git(
+            dir.path(),
+            &["init", "-q", "-b", "main"],
+            "2025-06-10T09:00:00Z",
+        );
+        let commits = [
+            ("a.txt", "Add a\n\nWhy a matters", "2025-06-10T09:00:00Z"),
+            ("b.txt", "Add b", "2025-06-10T10:00:00Z"),
+            ("c.txt", "Add c", "2025-06-11T09:00:00Z"),
+        ];
+        for (file, message, date) in commits {
+            fs::write(dir.path().join(file), file).unwrap();
+            git(dir.path(), &["add", file], date);
+            git(dir.path(), &["commit", "-qm", message], date);
+        }
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_one_entry_per_commit() {
+        let dir = history();
+        let report = run_import_git_in_dir(dir.path(), None, GitGrouping::Commit).unwrap();
+        assert_eq!(report.imported.len(), 3);
+
+        let repo = Repository::open(dir.path()).unwrap();
+        assert_eq!(repo.verify().unwrap().entry_count, 3);
+        let entries = repo.entries().unwrap();
+        assert_eq!(entries[0].content.summary, "Add a");
+        assert!(entries[0]
+            .content
+            .body
+            .contains("## Changes\nWhy a matters"));
+        assert!(entries[0].content.body.contains("## Files\n- A a.txt"));
+        assert_eq!(
+            entries[0]
+                .content
+                .header(SOURCE_COMMIT_HEADER)
+                .unwrap()
+                .len(),
+            40
+        );
+        assert_eq!(
+            entries[2].content.date.to_rfc3339(),
+            "2025-06-11T09:00:00+00:00"
+        );
+
+        // Re-running imports nothing new
+        let report = run_import_git_in_dir(dir.path(), None, GitGrouping::Commit).unwrap();
+        assert_eq!((report.imported.len(), report.skipped), (0, 3));
+    }
+
+    #[test]
+    fn test_future_commit_dates_are_capped() {
+        let dir = history();
+        fs::write(dir.path().join("d.txt"), "d").unwrap();
+        git(dir.path(), &["add", "d.txt"], "2030-01-01T00:00:00Z");
+        git(
+            dir.path(),
+            &["commit", "-qm", "Add d"],
+            "2030-01-01T00:00:00Z",
+        );
+
+        let report = run_import_git_in_dir(dir.path(), None, GitGrouping::Commit).unwrap();
+        assert_eq!(report.imported.len(), 4);
+        let repo = Repository::open(dir.path()).unwrap();
+        assert_eq!(repo.verify().unwrap().entry_count, 4);
+        assert!(repo.entries().unwrap()[3].content.date <= Utc::now());
+    }
+
+    #[test]
+    fn test_since_and_day_grouping() {
+        let dir = history();
+        let report = run_import_git_in_dir(dir.path(), None, GitGrouping::Day).unwrap();
+        assert_eq!(report.imported.len(), 2);
+
+        let entries = Repository::open(dir.path()).unwrap().entries().unwrap();
+        assert_eq!(entries[0].content.summary, "Add a (+1 more)");
+        assert_eq!(
+            entries[0]
+                .content
+                .header(SOURCE_COMMIT_HEADER)
+                .unwrap()
+                .matches(", ")
+                .count(),
+            1
+        );
+
+        let dir = history();
+        let report =
+            run_import_git_in_dir(dir.path(), Some("HEAD~1"), GitGrouping::Commit).unwrap();
+        assert_eq!(report.imported.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_revision_is_rejected() {
+        let dir = history();
+        assert!(matches!(
+            run_import_git_in_dir(dir.path(), Some("no-such-rev"), GitGrouping::Commit),
+            Err(EngramError::Import(_))
+        ));
+
+        let output = dir.path().join("written-by-git");
+        let option = format!("--output={}", output.display());
+        assert!(matches!(
+            run_import_git_in_dir(dir.path(), Some(&option), GitGrouping::Commit),
+            Err(EngramError::Import(_))
+        ));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_pr_entry_uses_merge_title() {
+        let commit = GitCommit {
+            hash: "a".repeat(40),
+            date: Utc::now(),
+            subject: "Merge pull request #42 from team/login".to_string(),
+            body: "Add login form\n\nWith validation".to_string(),
+            files: vec!["M src/login.rs".to_string()],
+        };
+        let content = pr_entry(&commit);
+        assert_eq!(content.summary, "Add login form (#42)");
+        assert!(content
+            .body
+            .starts_with("## Changes\nWith validation\n\n## Files\n- M src/login.rs"));
+    }
+}
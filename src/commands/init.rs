@@ -2,325 +2,378 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
-use crate::templates::{
-    AGENTS_TEMPLATE, DRAFT_TEMPLATE, ROOT_DIRECTIVE_TEMPLATE, SUMMARY_TEMPLATE,
-    WRAPPER_CMD_TEMPLATE, WRAPPER_SH_TEMPLATE,
+use crate::commands::directives::{
+    self, detect_targets, DirectiveTarget, DIRECTIVE_TARGETS, FALLBACK_TARGET,
 };
+use crate::commands::upgrade::installed_version;
+use crate::commands::wrappers::{WrapperKind, WrapperMode, WrapperSpec};
+use crate::error::EngramError;
+use crate::repository::Repository;
+use crate::templates::{AGENTS_TEMPLATE, DRAFT_TEMPLATE};
 
 /// Directory name for engram data
 const ENGRAM_DIR: &str = ".engram";
 /// Directory name for worklog entries
 const WORKLOG_DIR: &str = "worklog";
 /// Marker to detect if Engram directive already exists in a file
-const ENGRAM_MARKER: &str = "Engram Protocol";
+pub(crate) const ENGRAM_MARKER: &str = "Engram Protocol";
+/// Contents of .engram/.gitignore (ignore downloaded binaries)
+pub(crate) const GITIGNORE_CONTENT: &str = "bin/\n";
+/// Release that initialized or last upgraded the installation
+pub(crate) const VERSION_FILE: &str = ".engram/VERSION";
+/// Contents of .engram/.gitattributes (force LF line endings for stable hashing)
+pub(crate) const GITATTRIBUTES_CONTENT: &str = "* text eol=lf\n";
+
+/// How `init` treats an existing `.engram/`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InitMode {
+    /// Refuse with `AlreadyInitialized`
+    #[default]
+    Fresh,
+    /// Create whatever is missing and leave existing files alone
+    Repair,
+    /// Like `Repair`, but also reset generated files (AGENTS.md, wrappers,
+    /// hygiene files, SUMMARY.md, directive blocks) to the current templates
+    Force,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct InitOptions {
+    pub mode: InitMode,
     pub warp: bool,
     pub claude: bool,
     pub junie: bool,
     pub agents: bool,
+    pub cursor: bool,
+    pub copilot: bool,
+    pub windsurf: bool,
+    pub cline: bool,
+    pub gemini: bool,
+    pub aider: bool,
     pub all: bool,
+    /// `--wrapper-mode`; when unset, repair and force keep the installed mode
+    pub wrapper_mode: Option<WrapperMode>,
+    /// Default `ENGRAM_MIRROR` for mirror-mode wrappers
+    pub mirror: Option<String>,
+    /// checksums.txt whose SHA-256s mirror-mode wrappers pin
+    pub checksums: Option<PathBuf>,
 }
 
 impl InitOptions {
     /// Returns true if any flag is set
     fn any_flag_set(&self) -> bool {
-        self.warp || self.claude || self.junie || self.agents || self.all
+        DIRECTIVE_TARGETS
+            .iter()
+            .any(|target| self.selects(target.name))
+    }
+
+    /// Returns true if the directive target with this name was requested
+    fn selects(&self, name: &str) -> bool {
+        self.all
+            || match name {
+                "warp" => self.warp,
+                "claude" => self.claude,
+                "junie" => self.junie,
+                "agents" => self.agents,
+                "cursor" => self.cursor,
+                "copilot" => self.copilot,
+                "windsurf" => self.windsurf,
+                "cline" => self.cline,
+                "gemini" => self.gemini,
+                "aider" => self.aider,
+                _ => false,
+            }
     }
 }
 
-/// Error type for init command with specific exit codes
-#[derive(Debug)]
-pub enum InitError {
-    /// Already initialized (exit code 1)
-    AlreadyInitialized,
-    /// File I/O error (exit code 2)
-    IoError(io::Error),
+/// One file-level action taken by `init`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitAction {
+    /// A file was created
+    Created(String),
+    /// The Engram directive was inserted into an existing file
+    Appended(String),
+    /// A tool's configuration was edited, e.g. to read the directive file
+    Updated { path: String, change: String },
+    /// A file was left alone
+    Skipped { path: String, reason: &'static str },
 }
 
-impl std::fmt::Display for InitError {
+impl std::fmt::Display for InitAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            InitError::AlreadyInitialized => {
-                write!(f, "Engram already initialized (found .engram/).")
-            }
-            InitError::IoError(e) => write!(f, "I/O error: {}", e),
+            InitAction::Created(path) => write!(f, "Created: {}", path),
+            InitAction::Appended(path) => write!(f, "Appended Engram directive to: {}", path),
+            InitAction::Updated { path, change } => write!(f, "Updated: {} ({})", path, change),
+            InitAction::Skipped { path, reason } => write!(f, "Skipped: {} ({})", path, reason),
         }
     }
 }
 
-impl std::error::Error for InitError {}
-
-impl From<io::Error> for InitError {
-    fn from(error: io::Error) -> Self {
-        InitError::IoError(error)
-    }
-}
-
-pub fn run(options: InitOptions) -> io::Result<()> {
-    let cwd = std::env::current_dir()?;
-    match run_init_in_dir(&cwd, options) {
-        Ok(()) => Ok(()),
-        Err(InitError::AlreadyInitialized) => {
-            eprintln!("Error: Engram already initialized (found .engram/).");
-            std::process::exit(1);
-        }
-        Err(InitError::IoError(e)) => {
-            eprintln!("Error: I/O error: {}", e);
-            std::process::exit(2);
-        }
-    }
+/// What `init` did, in order
+#[derive(Debug, Clone, Default)]
+pub struct InitReport {
+    pub actions: Vec<InitAction>,
 }
 
-/// Internal implementation that accepts a base directory path.
-/// This is used by tests to avoid race conditions with `set_current_dir`.
-fn run_init_in_dir(cwd: &Path, options: InitOptions) -> Result<(), InitError> {
+/// Initialize Engram in `cwd` and report every file created or skipped.
+///
+/// With [`InitMode::Repair`] or [`InitMode::Force`] an existing (possibly
+/// half-created) `.engram/` is completed instead of refused. Neither mode
+/// touches worklog entries or a draft that has content.
+pub fn run_init_in_dir(cwd: &Path, options: InitOptions) -> Result<InitReport, EngramError> {
     let engram_dir = cwd.join(ENGRAM_DIR);
     let worklog_dir = engram_dir.join(WORKLOG_DIR);
 
     // Check idempotency: if .engram/ already exists, return error
-    if engram_dir.exists() {
-        return Err(InitError::AlreadyInitialized);
+    let existing = engram_dir.exists();
+    if existing && options.mode == InitMode::Fresh {
+        return Err(EngramError::AlreadyInitialized);
     }
 
+    let current = env!("CARGO_PKG_VERSION");
+    let wrappers = match options.wrapper_mode {
+        Some(mode) => WrapperSpec::new(
+            mode,
+            options.mirror.clone(),
+            options.checksums.clone(),
+            current,
+        )?,
+        None if options.mirror.is_some() || options.checksums.is_some() => WrapperSpec::new(
+            WrapperMode::Github,
+            options.mirror.clone(),
+            options.checksums.clone(),
+            current,
+        )?,
+        None => WrapperSpec::installed(cwd),
+    };
+
+    let mut report = InitReport::default();
+    let actions = &mut report.actions;
+    let force = options.mode == InitMode::Force;
+
     // Create directory structure
-    fs::create_dir(&engram_dir)?;
-    fs::create_dir(&worklog_dir)?;
+    for dir in [&engram_dir, &worklog_dir] {
+        if !dir.is_dir() {
+            fs::create_dir_all(dir)?;
+            if existing {
+                actions.push(InitAction::Created(format!("{}/", relative_path(cwd, dir))));
+            }
+        }
+    }
+
+    // .engram/AGENTS.md with full protocol instructions
+    write_template(cwd, ".engram/AGENTS.md", AGENTS_TEMPLATE, force, actions)?;
 
-    // Create .engram/AGENTS.md with full protocol instructions
-    let agents_path = engram_dir.join("AGENTS.md");
-    fs::write(&agents_path, AGENTS_TEMPLATE)?;
+    // .engram/draft.md with empty template, unless it holds unfinished work
+    write_draft(cwd, actions)?;
 
-    // Create .engram/draft.md with empty template
-    let draft_path = engram_dir.join("draft.md");
-    fs::write(&draft_path, DRAFT_TEMPLATE)?;
+    // .engram/worklog/SUMMARY.md, rebuilt from any entries already on disk
+    write_summary(cwd, force, actions)?;
 
-    // Create .engram/worklog/SUMMARY.md with header only
-    let summary_path = worklog_dir.join("SUMMARY.md");
-    fs::write(&summary_path, SUMMARY_TEMPLATE)?;
+    // .engram/.gitignore (ignore downloaded binaries)
+    write_template(cwd, ".engram/.gitignore", GITIGNORE_CONTENT, force, actions)?;
 
-    // Create .engram/.gitignore (ignore downloaded binaries)
-    let engram_gitignore_path = engram_dir.join(".gitignore");
-    fs::write(&engram_gitignore_path, "bin/\n")?;
+    // .engram/.gitattributes (force LF line endings for stable hashing)
+    write_template(
+        cwd,
+        ".engram/.gitattributes",
+        GITATTRIBUTES_CONTENT,
+        force,
+        actions,
+    )?;
 
-    // Create .engram/.gitattributes (force LF line endings for stable hashing)
-    let engram_gitattributes_path = engram_dir.join(".gitattributes");
-    fs::write(&engram_gitattributes_path, "* text eol=lf\n")?;
+    // Record the release so `engram upgrade` knows where to start. An
+    // existing installation keeps the version it was made with, so upgrade
+    // still runs its migrations.
+    let version = existing
+        .then(|| installed_version(cwd))
+        .flatten()
+        .unwrap_or_else(|| current.to_string());
+    write_template(cwd, VERSION_FILE, &format!("{}\n", version), false, actions)?;
 
     // Create per-repo wrapper scripts (so fresh clones can run `./engram ...`)
-    let wrapper_report = write_wrappers(cwd)?;
-
-    // Print success output
-    println!("Initialized Engram in {}", cwd.display());
-    println!("Created: {}", relative_path(cwd, &agents_path));
-    println!("Created: {}", relative_path(cwd, &draft_path));
-    println!("Created: {}", relative_path(cwd, &summary_path));
-    println!("Created: {}", relative_path(cwd, &engram_gitignore_path));
-    println!(
-        "Created: {}",
-        relative_path(cwd, &engram_gitattributes_path)
-    );
-
-    match wrapper_report.sh_status {
-        WriteStatus::Created => {
-            println!("Created: {}", relative_path(cwd, &wrapper_report.sh_path))
+    if force {
+        for kind in WrapperKind::ALL {
+            let content = wrappers.render(kind, current);
+            write_template(cwd, kind.file_name(), &content, true, actions)?;
         }
-        WriteStatus::SkippedAlreadyExists => println!(
-            "Skipped: {} (already exists)",
-            relative_path(cwd, &wrapper_report.sh_path)
-        ),
-    }
-
-    match wrapper_report.cmd_status {
-        WriteStatus::Created => {
-            println!("Created: {}", relative_path(cwd, &wrapper_report.cmd_path))
+        set_executable(&cwd.join("engram"))?;
+    } else {
+        let wrapper_report = write_wrappers(cwd, &wrappers, current)?;
+        for (path, status) in [
+            (&wrapper_report.sh_path, &wrapper_report.sh_status),
+            (&wrapper_report.cmd_path, &wrapper_report.cmd_status),
+        ] {
+            let path = relative_path(cwd, path);
+            actions.push(match status {
+                WriteStatus::Created => InitAction::Created(path),
+                WriteStatus::SkippedAlreadyExists => InitAction::Skipped {
+                    path,
+                    reason: "already exists",
+                },
+            });
         }
-        WriteStatus::SkippedAlreadyExists => println!(
-            "Skipped: {} (already exists)",
-            relative_path(cwd, &wrapper_report.cmd_path)
-        ),
     }
 
     // Handle root-level AI agent instruction files
-    handle_root_level_files(cwd, &options)?;
+    handle_root_level_files(cwd, &options, actions)?;
 
-    Ok(())
+    Ok(report)
 }
 
-/// Handle creation/appending of root-level AI agent instruction files
-fn handle_root_level_files(cwd: &Path, options: &InitOptions) -> Result<(), InitError> {
-    if options.any_flag_set() {
-        // Flag mode: create/append to specified files
-        if options.warp {
-            handle_warp_file(cwd)?;
-        }
-        if options.claude {
-            handle_claude_file(cwd)?;
-        }
-        if options.junie {
-            handle_junie_file(cwd)?;
-        }
-        if options.agents {
-            handle_root_agents_file(cwd)?;
-        }
+/// Create `relative` with `content` if missing; with `overwrite`, also reset
+/// an existing file that differs
+fn write_template(
+    cwd: &Path,
+    relative: &str,
+    content: &str,
+    overwrite: bool,
+    actions: &mut Vec<InitAction>,
+) -> Result<(), EngramError> {
+    let path = cwd.join(relative);
+    if !path.exists() {
+        fs::write(&path, content)?;
+        actions.push(InitAction::Created(relative.to_string()));
+    } else if !overwrite {
+        actions.push(InitAction::Skipped {
+            path: relative.to_string(),
+            reason: "already exists",
+        });
+    } else if fs::read(&path)? == content.as_bytes() {
+        actions.push(InitAction::Skipped {
+            path: relative.to_string(),
+            reason: "up to date",
+        });
     } else {
-        // Detection mode: check for existing files and apply defaults
-        let warp_exists = cwd.join("WARP.md").exists();
-        let claude_exists = cwd.join("CLAUDE.md").exists();
-        let junie_dir_exists = cwd.join(".junie").exists();
-
-        if warp_exists {
-            // WARP.md exists, append to it
-            handle_warp_file(cwd)?;
-        }
-
-        if claude_exists {
-            // CLAUDE.md exists, append to it
-            handle_claude_file(cwd)?;
-        }
-
-        if junie_dir_exists {
-            // .junie/ directory exists, append to guidelines.md
-            handle_junie_file(cwd)?;
-        }
-
-        if !warp_exists && !claude_exists && !junie_dir_exists {
-            // None exist, create AGENTS.md in project root by default
-            handle_root_agents_file(cwd)?;
-        }
+        fs::write(&path, content)?;
+        actions.push(InitAction::Updated {
+            path: relative.to_string(),
+            change: "reset to the current template".to_string(),
+        });
     }
-
     Ok(())
 }
 
-/// Handle WARP.md file (create or append)
-fn handle_warp_file(cwd: &Path) -> Result<(), InitError> {
-    let warp_path = cwd.join("WARP.md");
-    handle_directive_file(&warp_path, "WARP.md", "# Warp AI Instructions")
-}
-
-/// Handle CLAUDE.md file (create or append)
-fn handle_claude_file(cwd: &Path) -> Result<(), InitError> {
-    let claude_path = cwd.join("CLAUDE.md");
-    handle_directive_file(&claude_path, "CLAUDE.md", "# Claude AI Instructions")
-}
-
-/// Handle .junie/guidelines.md file (create or append)
-fn handle_junie_file(cwd: &Path) -> Result<(), InitError> {
-    let junie_dir = cwd.join(".junie");
-    let guidelines_path = junie_dir.join("guidelines.md");
-
-    // Create .junie directory if it doesn't exist
-    if !junie_dir.exists() {
-        fs::create_dir(&junie_dir)?;
+/// Create the draft, or restore the template over a blank one. A draft with
+/// anything else in it is unfinished work and is never replaced.
+fn write_draft(cwd: &Path, actions: &mut Vec<InitAction>) -> Result<(), EngramError> {
+    let relative = ".engram/draft.md";
+    let path = cwd.join(relative);
+    if !path.exists() {
+        fs::write(&path, DRAFT_TEMPLATE)?;
+        actions.push(InitAction::Created(relative.to_string()));
+        return Ok(());
     }
 
-    handle_directive_file(
-        &guidelines_path,
-        ".junie/guidelines.md",
-        "# Junie AI Guidelines",
-    )
+    let content = String::from_utf8_lossy(&fs::read(&path)?).to_string();
+    if content.trim().is_empty() {
+        fs::write(&path, DRAFT_TEMPLATE)?;
+        actions.push(InitAction::Updated {
+            path: relative.to_string(),
+            change: "restored the empty draft template".to_string(),
+        });
+    } else {
+        actions.push(InitAction::Skipped {
+            path: relative.to_string(),
+            reason: if content.trim() == DRAFT_TEMPLATE.trim() {
+                "already exists"
+            } else {
+                "draft has content"
+            },
+        });
+    }
+    Ok(())
 }
 
-/// Handle root AGENTS.md file (create or append)
-fn handle_root_agents_file(cwd: &Path) -> Result<(), InitError> {
-    let agents_path = cwd.join("AGENTS.md");
-    handle_directive_file(&agents_path, "AGENTS.md", "# AI Agent Instructions")
-}
+/// Create SUMMARY.md from the entries on disk; with `force`, rebuild an
+/// existing one that is out of step with them
+fn write_summary(
+    cwd: &Path,
+    force: bool,
+    actions: &mut Vec<InitAction>,
+) -> Result<(), EngramError> {
+    let relative = ".engram/worklog/SUMMARY.md";
+    let path = cwd.join(relative);
+    let exists = path.exists();
+    if exists && !force {
+        actions.push(InitAction::Skipped {
+            path: relative.to_string(),
+            reason: "already exists",
+        });
+        return Ok(());
+    }
 
-/// Generic handler for directive files - creates or appends as needed
-fn handle_directive_file(
-    path: &Path,
-    display_name: &str,
-    default_header: &str,
-) -> Result<(), InitError> {
-    if path.exists() {
-        // File exists - check for existing directive and append if not present
-        let content = fs::read_to_string(path)?;
-
-        // Idempotency check: don't append if directive already exists
-        if content.contains(ENGRAM_MARKER) {
-            println!(
-                "Skipped: {} (Engram directive already present)",
-                display_name
-            );
-            return Ok(());
+    let before = fs::read(&path).ok();
+    Repository::open(cwd)?.rebuild_summary()?;
+    actions.push(if !exists {
+        InitAction::Created(relative.to_string())
+    } else if before == fs::read(&path).ok() {
+        InitAction::Skipped {
+            path: relative.to_string(),
+            reason: "up to date",
         }
-
-        // Append directive after the first heading (if any)
-        let new_content = append_directive_after_heading(&content);
-        fs::write(path, new_content)?;
-        println!("Appended Engram directive to: {}", display_name);
     } else {
-        // File doesn't exist - create with header and directive
-        let content = format!("{}\n\n{}", default_header, ROOT_DIRECTIVE_TEMPLATE);
-        fs::write(path, content)?;
-        println!("Created: {}", display_name);
-    }
-
+        InitAction::Updated {
+            path: relative.to_string(),
+            change: "rebuilt from the worklog entries".to_string(),
+        }
+    });
     Ok(())
 }
 
-/// Append the directive after the first level-1 heading, or at the start if no heading found
-fn append_directive_after_heading(content: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-
-    // Find the first level-1 heading (starts with "# ")
-    let heading_index = lines.iter().position(|line| line.starts_with("# "));
-
-    match heading_index {
-        Some(idx) => {
-            // Insert directive after the heading line
-            let mut result = String::new();
-
-            // Add lines up to and including the heading
-            for line in &lines[..=idx] {
-                result.push_str(line);
-                result.push('\n');
-            }
-
-            // Add blank line and directive
-            result.push('\n');
-            result.push_str(ROOT_DIRECTIVE_TEMPLATE);
-
-            // Add remaining content
-            if idx + 1 < lines.len() {
-                for line in &lines[idx + 1..] {
-                    result.push_str(line);
-                    result.push('\n');
-                }
-            }
-
-            result
-        }
-        None => {
-            // No heading found, prepend directive
-            format!("{}\n{}", ROOT_DIRECTIVE_TEMPLATE, content)
+/// Write the directive to the selected targets, or in detection mode to the
+/// targets whose files already exist (AGENTS.md when none do)
+fn handle_root_level_files(
+    cwd: &Path,
+    options: &InitOptions,
+    actions: &mut Vec<InitAction>,
+) -> Result<(), EngramError> {
+    let targets: Vec<&DirectiveTarget> = if options.any_flag_set() {
+        DIRECTIVE_TARGETS
+            .iter()
+            .filter(|target| options.selects(target.name))
+            .collect()
+    } else {
+        let detected = detect_targets(cwd);
+        if detected.is_empty() {
+            directives::target(FALLBACK_TARGET).into_iter().collect()
+        } else {
+            detected
         }
+    };
+
+    let refresh = options.mode == InitMode::Force;
+    for target in targets {
+        target.install(cwd, refresh, actions)?;
     }
+    Ok(())
 }
 
-enum WriteStatus {
+pub(crate) enum WriteStatus {
     Created,
     SkippedAlreadyExists,
 }
 
-struct WrapperWriteReport {
-    sh_path: PathBuf,
-    sh_status: WriteStatus,
-    cmd_path: PathBuf,
-    cmd_status: WriteStatus,
+pub(crate) struct WrapperWriteReport {
+    pub sh_path: PathBuf,
+    pub sh_status: WriteStatus,
+    pub cmd_path: PathBuf,
+    pub cmd_status: WriteStatus,
 }
 
-fn write_wrappers(cwd: &Path) -> io::Result<WrapperWriteReport> {
-    let version = env!("CARGO_PKG_VERSION");
-
+/// Write the `./engram` and `engram.cmd` wrappers pinned to `version`,
+/// skipping any that already exist
+pub(crate) fn write_wrappers(
+    cwd: &Path,
+    spec: &WrapperSpec,
+    version: &str,
+) -> io::Result<WrapperWriteReport> {
     let sh_path = cwd.join("engram");
     let sh_status = if sh_path.exists() {
         WriteStatus::SkippedAlreadyExists
     } else {
-        let wrapper_sh = WRAPPER_SH_TEMPLATE.replace("__ENGRAM_VERSION__", version);
+        let wrapper_sh = spec.render(WrapperKind::Sh, version);
         fs::write(&sh_path, wrapper_sh)?;
         set_executable(&sh_path)?;
         WriteStatus::Created
@@ -330,7 +383,7 @@ fn write_wrappers(cwd: &Path) -> io::Result<WrapperWriteReport> {
     let cmd_status = if cmd_path.exists() {
         WriteStatus::SkippedAlreadyExists
     } else {
-        let wrapper_cmd = WRAPPER_CMD_TEMPLATE.replace("__ENGRAM_VERSION__", version);
+        let wrapper_cmd = spec.render(WrapperKind::Cmd, version);
         fs::write(&cmd_path, wrapper_cmd)?;
         WriteStatus::Created
     };
@@ -343,8 +396,13 @@ fn write_wrappers(cwd: &Path) -> io::Result<WrapperWriteReport> {
     })
 }
 
+/// Render a wrapper template pinned to the given Engram version
+pub(crate) fn render_wrapper(template: &str, version: &str) -> String {
+    template.replace("__ENGRAM_VERSION__", version)
+}
+
 #[cfg(unix)]
-fn set_executable(path: &Path) -> io::Result<()> {
+pub(crate) fn set_executable(path: &Path) -> io::Result<()> {
     use std::os::unix::fs::PermissionsExt;
 
     let mut perms = fs::metadata(path)?.permissions();
@@ -353,7 +411,7 @@ fn set_executable(path: &Path) -> io::Result<()> {
 }
 
 #[cfg(not(unix))]
-fn set_executable(_path: &Path) -> io::Result<()> {
+pub(crate) fn set_executable(_path: &Path) -> io::Result<()> {
     Ok(())
 }
 
@@ -392,6 +450,30 @@ mod tests {
         assert!(temp_dir.path().join(".engram/.gitattributes").exists());
     }
 
+    #[test]
+    fn test_init_reports_actions() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("engram"), "custom").unwrap();
+        fs::write(temp_dir.path().join("CLAUDE.md"), "# Claude\n").unwrap();
+
+        let report = run_init_in_dir(temp_dir.path(), InitOptions::default()).unwrap();
+
+        assert!(report
+            .actions
+            .contains(&InitAction::Created(".engram/draft.md".to_string())));
+        assert!(report.actions.contains(&InitAction::Skipped {
+            path: "engram".to_string(),
+            reason: "already exists",
+        }));
+        assert!(report
+            .actions
+            .contains(&InitAction::Appended("CLAUDE.md".to_string())));
+        assert_eq!(
+            InitAction::Appended("CLAUDE.md".to_string()).to_string(),
+            "Appended Engram directive to: CLAUDE.md"
+        );
+    }
+
     #[test]
     fn test_init_creates_agents_md() {
         let temp_dir = TempDir::new().unwrap();
@@ -444,7 +526,106 @@ mod tests {
 
         // Second init should fail with AlreadyInitialized
         let result = run_init_in_dir(temp_dir.path(), InitOptions::default());
-        assert!(matches!(result, Err(InitError::AlreadyInitialized)));
+        assert!(matches!(result, Err(EngramError::AlreadyInitialized)));
+    }
+
+    #[test]
+    fn test_init_repair_completes_partial_install() {
+        let temp_dir = TempDir::new().unwrap();
+        run_init_in_dir(temp_dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(temp_dir.path()).unwrap();
+        repo.write_draft("<summary>First</summary>\n\n## Changes\n- x")
+            .unwrap();
+        repo.commit().unwrap();
+        repo.write_draft("<summary>Unfinished</summary>").unwrap();
+
+        fs::remove_file(temp_dir.path().join(".engram/.gitattributes")).unwrap();
+        fs::remove_file(temp_dir.path().join(".engram/worklog/SUMMARY.md")).unwrap();
+        fs::write(temp_dir.path().join(".engram/AGENTS.md"), "custom\n").unwrap();
+
+        let options = InitOptions {
+            mode: InitMode::Repair,
+            ..Default::default()
+        };
+        let report = run_init_in_dir(temp_dir.path(), options).unwrap();
+
+        assert!(report
+            .actions
+            .contains(&InitAction::Created(".engram/.gitattributes".to_string())));
+        assert!(report.actions.contains(&InitAction::Skipped {
+            path: ".engram/draft.md".to_string(),
+            reason: "draft has content",
+        }));
+        assert!(report.actions.contains(&InitAction::Skipped {
+            path: ".engram/AGENTS.md".to_string(),
+            reason: "already exists",
+        }));
+        let summary =
+            fs::read_to_string(temp_dir.path().join(".engram/worklog/SUMMARY.md")).unwrap();
+        assert!(summary.contains("| First |"));
+        assert_eq!(repo.read_draft().unwrap(), "<summary>Unfinished</summary>");
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join(".engram/AGENTS.md")).unwrap(),
+            "custom\n"
+        );
+        assert_eq!(repo.verify().unwrap().entry_count, 1);
+    }
+
+    #[test]
+    fn test_init_repair_recreates_missing_worklog() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".engram")).unwrap();
+        fs::write(temp_dir.path().join(".engram/draft.md"), "  \n").unwrap();
+
+        let options = InitOptions {
+            mode: InitMode::Repair,
+            ..Default::default()
+        };
+        let report = run_init_in_dir(temp_dir.path(), options).unwrap();
+
+        assert_eq!(
+            report.actions[0],
+            InitAction::Created(".engram/worklog/".to_string())
+        );
+        assert!(report.actions.contains(&InitAction::Updated {
+            path: ".engram/draft.md".to_string(),
+            change: "restored the empty draft template".to_string(),
+        }));
+        assert!(Repository::open(temp_dir.path()).unwrap().verify().is_ok());
+    }
+
+    #[test]
+    fn test_init_force_resets_templates_and_keeps_history() {
+        let temp_dir = TempDir::new().unwrap();
+        run_init_in_dir(temp_dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(temp_dir.path()).unwrap();
+        repo.write_draft("<summary>First</summary>\n\n## Changes\n- x")
+            .unwrap();
+        repo.commit().unwrap();
+        fs::write(temp_dir.path().join(".engram/AGENTS.md"), "custom\n").unwrap();
+        fs::write(temp_dir.path().join("engram"), "#!/bin/sh\necho custom\n").unwrap();
+
+        let options = InitOptions {
+            mode: InitMode::Force,
+            ..Default::default()
+        };
+        let report = run_init_in_dir(temp_dir.path(), options).unwrap();
+
+        assert!(report.actions.contains(&InitAction::Updated {
+            path: ".engram/AGENTS.md".to_string(),
+            change: "reset to the current template".to_string(),
+        }));
+        assert!(report.actions.contains(&InitAction::Skipped {
+            path: ".engram/worklog/SUMMARY.md".to_string(),
+            reason: "up to date",
+        }));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join(".engram/AGENTS.md")).unwrap(),
+            AGENTS_TEMPLATE
+        );
+        let wrapper = fs::read_to_string(temp_dir.path().join("engram")).unwrap();
+        assert!(wrapper.contains(env!("CARGO_PKG_VERSION")));
+        assert_eq!(repo.verify().unwrap().entry_count, 1);
     }
 
     #[test]
@@ -456,7 +637,7 @@ mod tests {
 
         // Init should fail
         let result = run_init_in_dir(temp_dir.path(), InitOptions::default());
-        assert!(matches!(result, Err(InitError::AlreadyInitialized)));
+        assert!(matches!(result, Err(EngramError::AlreadyInitialized)));
     }
 
     // === New tests for Phase 2 Task 2: Init extensions ===
@@ -543,6 +724,7 @@ mod tests {
             claude: true,
             junie: true,
             agents: true,
+            ..Default::default()
         };
         let result = run_init_in_dir(temp_dir.path(), options);
         assert!(result.is_ok());
@@ -679,25 +861,4 @@ mod tests {
         let content = fs::read_to_string(&agents_path).unwrap();
         assert!(content.contains("Engram Protocol"));
     }
-
-    #[test]
-    fn test_append_directive_after_heading() {
-        let content = "# My Title\n\nSome content here.\n\n## Section\n\nMore content.\n";
-        let result = append_directive_after_heading(content);
-
-        // Should have heading first, then directive, then rest of content
-        assert!(result.starts_with("# My Title\n"));
-        assert!(result.contains("Engram Protocol"));
-        assert!(result.contains("Some content here."));
-    }
-
-    #[test]
-    fn test_append_directive_no_heading() {
-        let content = "Just some content without a heading.\n";
-        let result = append_directive_after_heading(content);
-
-        // Directive should be prepended
-        assert!(result.starts_with("## 🔒 Engram Protocol"));
-        assert!(result.contains("Just some content without a heading."));
-    }
 }
@@ -1,4 +1,18 @@
+pub mod changelog;
 pub mod commit;
+pub mod deinit;
+pub mod directives;
+pub mod doctor;
+pub mod draft;
+pub mod export;
+pub mod import;
+pub mod import_git;
 pub mod init;
+pub mod pin;
+pub mod self_update;
 pub mod status;
+pub mod sync_directives;
+pub mod upgrade;
+pub mod vendor;
 pub mod verify;
+pub mod wrappers;
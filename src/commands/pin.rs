@@ -0,0 +1,262 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::init::{set_executable, write_wrappers, WriteStatus};
+use crate::commands::wrappers::{
+    is_release_version, read_wrapper, WrapperKind, WrapperMode, WrapperSpec,
+};
+use crate::error::EngramError;
+
+const ENGRAM_DIR: &str = ".engram";
+
+/// Options for `engram pin`
+#[derive(Debug, Clone, Default)]
+pub struct PinOptions {
+    /// Release checksums.txt for mirror-mode wrappers
+    pub checksums: Option<PathBuf>,
+}
+
+/// What `pin` did to one wrapper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinStatus {
+    /// Regenerated; holds the version it pinned before
+    Pinned(String),
+    /// Already pinned to the requested version
+    Unchanged,
+    /// The wrapper was missing and was created
+    Created,
+    /// Left alone because it was edited outside the custom markers
+    Skipped(&'static str),
+}
+
+/// One wrapper handled by `pin`
+#[derive(Debug, Clone)]
+pub struct PinnedWrapper {
+    pub path: String,
+    pub status: PinStatus,
+}
+
+impl std::fmt::Display for PinnedWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.status {
+            PinStatus::Pinned(from) => write!(f, "Pinned: {} (was {})", self.path, from),
+            PinStatus::Unchanged => write!(f, "Up to date: {}", self.path),
+            PinStatus::Created => write!(f, "Created: {}", self.path),
+            PinStatus::Skipped(reason) => write!(f, "Skipped: {} ({})", self.path, reason),
+        }
+    }
+}
+
+/// Result of `pin`
+#[derive(Debug, Clone)]
+pub struct PinReport {
+    pub version: String,
+    pub wrappers: Vec<PinnedWrapper>,
+}
+
+impl PinReport {
+    /// True when a wrapper could not be pinned
+    pub fn has_skipped(&self) -> bool {
+        self.wrappers
+            .iter()
+            .any(|wrapper| matches!(wrapper.status, PinStatus::Skipped(_)))
+    }
+
+    pub fn to_json(&self) -> Value {
+        let wrappers: Vec<_> = self
+            .wrappers
+            .iter()
+            .map(|wrapper| {
+                let (status, detail) = match &wrapper.status {
+                    PinStatus::Pinned(from) => ("pinned", Some(from.as_str())),
+                    PinStatus::Unchanged => ("unchanged", None),
+                    PinStatus::Created => ("created", None),
+                    PinStatus::Skipped(reason) => ("skipped", Some(*reason)),
+                };
+                json!({
+                    "path": wrapper.path,
+                    "status": status,
+                    "detail": detail,
+                })
+            })
+            .collect();
+        json!({
+            "version": self.version,
+            "wrappers": wrappers,
+        })
+    }
+}
+
+/// Regenerate `./engram` and `engram.cmd` pinned to `version`, keeping their
+/// wrapper mode and any `engram:custom` blocks. Mirror-mode wrappers pin the
+/// new release's checksums, from `options.checksums` or the local mirror.
+pub fn run_pin_in_dir(
+    root: &Path,
+    version: &str,
+    options: &PinOptions,
+) -> Result<PinReport, EngramError> {
+    if !root.join(ENGRAM_DIR).exists() {
+        return Err(EngramError::NotInitialized);
+    }
+    if !is_release_version(version) {
+        return Err(EngramError::Config(format!(
+            "'{}' is not a release version (expected MAJOR.MINOR.PATCH)",
+            version
+        )));
+    }
+
+    let installed = WrapperSpec::installed(root);
+    let spec = match installed.mode {
+        WrapperMode::Mirror => WrapperSpec::new(
+            WrapperMode::Mirror,
+            installed.mirror,
+            options.checksums.clone(),
+            version,
+        )?,
+        _ if options.checksums.is_some() => {
+            return Err(EngramError::Config(
+                "--checksums only applies to mirror-mode wrappers".to_string(),
+            ))
+        }
+        _ => installed,
+    };
+
+    let mut wrappers = Vec::new();
+    let created = write_wrappers(root, &spec, version)?;
+    for kind in WrapperKind::ALL {
+        let name = kind.file_name();
+        let path = root.join(name);
+        let written = match kind {
+            WrapperKind::Sh => &created.sh_status,
+            WrapperKind::Cmd => &created.cmd_status,
+        };
+        let status = if matches!(written, WriteStatus::Created) {
+            PinStatus::Created
+        } else {
+            let content = fs::read_to_string(&path)?;
+            match read_wrapper(kind, &content) {
+                None => PinStatus::Skipped("has local changes outside engram:custom markers"),
+                Some(wrapper) => {
+                    let pinned = wrapper.render(kind, &spec, version);
+                    if pinned == content {
+                        PinStatus::Unchanged
+                    } else {
+                        fs::write(&path, pinned)?;
+                        if kind == WrapperKind::Sh {
+                            set_executable(&path)?;
+                        }
+                        PinStatus::Pinned(wrapper.version)
+                    }
+                }
+            }
+        };
+        wrappers.push(PinnedWrapper {
+            path: name.to_string(),
+            status,
+        });
+    }
+
+    Ok(PinReport {
+        version: version.to_string(),
+        wrappers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::doctor::wrapper_version;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_pin_rewrites_both_wrappers_and_keeps_custom_blocks() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let sh = dir.path().join("engram");
+        let custom = "# engram:custom:begin\nexport ENGRAM_REPO=acme/engram\n# engram:custom:end\n";
+        let content = fs::read_to_string(&sh).unwrap();
+        let content = content.replacen(
+            "cd \"$ROOT_DIR\"\n",
+            &format!("cd \"$ROOT_DIR\"\n{}", custom),
+            1,
+        );
+        fs::write(&sh, &content).unwrap();
+
+        let report = run_pin_in_dir(dir.path(), "9.8.7", &PinOptions::default()).unwrap();
+
+        let current = env!("CARGO_PKG_VERSION").to_string();
+        assert_eq!(
+            report.wrappers[0].status,
+            PinStatus::Pinned(current.clone())
+        );
+        assert_eq!(report.wrappers[1].status, PinStatus::Pinned(current));
+        let pinned = fs::read_to_string(&sh).unwrap();
+        assert_eq!(wrapper_version(&pinned).as_deref(), Some("9.8.7"));
+        assert!(pinned.contains(&format!("cd \"$ROOT_DIR\"\n{}", custom)));
+        let cmd = fs::read_to_string(dir.path().join("engram.cmd")).unwrap();
+        assert_eq!(wrapper_version(&cmd).as_deref(), Some("9.8.7"));
+
+        let report = run_pin_in_dir(dir.path(), "9.8.7", &PinOptions::default()).unwrap();
+        assert!(report
+            .wrappers
+            .iter()
+            .all(|wrapper| wrapper.status == PinStatus::Unchanged));
+    }
+
+    #[test]
+    fn test_pin_skips_hand_edited_wrapper() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let cmd = dir.path().join("engram.cmd");
+        let edited = fs::read_to_string(&cmd).unwrap() + "rem tweak\n";
+        fs::write(&cmd, &edited).unwrap();
+
+        let report = run_pin_in_dir(dir.path(), "9.8.7", &PinOptions::default()).unwrap();
+
+        assert!(report.has_skipped());
+        assert_eq!(fs::read_to_string(&cmd).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_pin_rejects_bad_versions() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        for version in ["latest", "1.2", "v1.2.3", "1.2.3-rc & calc"] {
+            assert!(matches!(
+                run_pin_in_dir(dir.path(), version, &PinOptions::default()),
+                Err(EngramError::Config(_))
+            ));
+        }
+        assert!(run_pin_in_dir(dir.path(), "1.2.3-rc.1", &PinOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_pin_mirror_wrappers_pins_new_checksums() {
+        let dir = tempdir().unwrap();
+        let mirror = tempdir().unwrap();
+        for (version, sha) in [(env!("CARGO_PKG_VERSION"), "a"), ("9.8.7", "b")] {
+            let release = mirror.path().join(format!("v{}", version));
+            fs::create_dir_all(&release).unwrap();
+            fs::write(
+                release.join("checksums.txt"),
+                format!("{}  engram-linux-x86_64\n", sha.repeat(64)),
+            )
+            .unwrap();
+        }
+        let options = InitOptions {
+            wrapper_mode: Some(WrapperMode::Mirror),
+            mirror: Some(format!("file://{}", mirror.path().display())),
+            ..InitOptions::default()
+        };
+        run_init_in_dir(dir.path(), options).unwrap();
+
+        run_pin_in_dir(dir.path(), "9.8.7", &PinOptions::default()).unwrap();
+
+        let sh = fs::read_to_string(dir.path().join("engram")).unwrap();
+        assert!(sh.contains(&"b".repeat(64)));
+        assert!(!sh.contains(&"a".repeat(64)));
+        assert!(sh.contains("# Wrapper mode: mirror"));
+    }
+}
@@ -0,0 +1,349 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use crate::commands::init::set_executable;
+use crate::commands::upgrade::parse_version;
+use crate::commands::wrappers::{is_release_version, local_dir, parse_checksums, platform_asset};
+use crate::error::EngramError;
+use crate::utils::hash::sha256_hex;
+
+/// Release directory used when neither `--from` nor `ENGRAM_MIRROR` is set
+pub const DEFAULT_RELEASES: &str = "https://github.com/lkubicek1/engram/releases/download";
+/// GitHub API answer naming the newest release of `DEFAULT_RELEASES`
+const LATEST_RELEASE_API: &str = "https://api.github.com/repos/lkubicek1/engram/releases/latest";
+/// Environment variable naming a release mirror, shared with the wrappers
+pub const MIRROR_ENV: &str = "ENGRAM_MIRROR";
+
+/// Options for `engram self-update`
+#[derive(Debug, Clone, Default)]
+pub struct SelfUpdateOptions {
+    /// Release directory: a URL, `file://` URL or path laid out as
+    /// `<dir>/v<version>/{checksums.txt,<asset>}`
+    pub from: Option<String>,
+    /// Release to install; the newest in a local directory or on GitHub when unset
+    pub version: Option<String>,
+}
+
+/// Result of `self-update`
+#[derive(Debug, Clone)]
+pub struct SelfUpdateReport {
+    pub from_version: String,
+    pub to_version: String,
+    /// Where the binary came from, None when nothing was installed
+    pub source: Option<String>,
+    pub sha256: Option<String>,
+}
+
+impl SelfUpdateReport {
+    pub fn updated(&self) -> bool {
+        self.source.is_some()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "from": self.from_version,
+            "to": self.to_version,
+            "updated": self.updated(),
+            "source": self.source,
+            "sha256": self.sha256,
+        })
+    }
+}
+
+/// Replace the binary at `exe` with a release from the configured release
+/// directory. The asset for this platform must match its line in the
+/// release's checksums.txt; nothing is replaced otherwise.
+pub fn run_self_update(
+    exe: &Path,
+    options: &SelfUpdateOptions,
+) -> Result<SelfUpdateReport, EngramError> {
+    let current = env!("CARGO_PKG_VERSION");
+    let releases = options
+        .from
+        .clone()
+        .or_else(|| {
+            std::env::var(MIRROR_ENV)
+                .ok()
+                .filter(|value| !value.is_empty())
+        })
+        .unwrap_or_else(|| DEFAULT_RELEASES.to_string());
+    let releases = releases.trim_end_matches('/');
+
+    let version = match &options.version {
+        Some(version) => version.trim_start_matches('v').to_string(),
+        None => newest_release(releases)?,
+    };
+    if !is_release_version(&version) {
+        return Err(EngramError::Config(format!(
+            "'{}' is not a release version (expected MAJOR.MINOR.PATCH)",
+            version
+        )));
+    }
+
+    // Without an explicit version only move forward
+    let wanted = match &options.version {
+        Some(_) => version != current,
+        None => parse_version(&version) > parse_version(current),
+    };
+    if !wanted {
+        return Ok(SelfUpdateReport {
+            from_version: current.to_string(),
+            to_version: version,
+            source: None,
+            sha256: None,
+        });
+    }
+
+    let asset = platform_asset().ok_or_else(|| {
+        EngramError::Config("no Engram release is built for this platform".to_string())
+    })?;
+    let release = format!("{}/v{}", releases, version);
+    let checksums = fetch(&format!("{}/checksums.txt", release))?;
+    let Some((_, expected)) = parse_checksums(&String::from_utf8_lossy(&checksums))
+        .into_iter()
+        .find(|(name, _)| *name == asset)
+    else {
+        return Err(EngramError::Config(format!(
+            "{}/checksums.txt has no entry for {}",
+            release, asset
+        )));
+    };
+
+    let source = format!("{}/{}", release, asset);
+    let binary = fetch(&source)?;
+    let actual = sha256_hex(&binary);
+    if actual != expected {
+        return Err(EngramError::Config(format!(
+            "checksum mismatch for {}: expected {}, downloaded {}",
+            source, expected, actual
+        )));
+    }
+
+    replace_binary(exe, &binary)?;
+
+    Ok(SelfUpdateReport {
+        from_version: current.to_string(),
+        to_version: version,
+        source: Some(source),
+        sha256: Some(actual),
+    })
+}
+
+/// Newest release in `releases`: the highest `v<version>` directory of a
+/// local directory, or GitHub's latest release for the default source
+fn newest_release(releases: &str) -> Result<String, EngramError> {
+    let Some(dir) = local_dir(releases) else {
+        if releases == DEFAULT_RELEASES {
+            return latest_tag(&fetch(LATEST_RELEASE_API)?);
+        }
+        return Err(EngramError::Config(format!(
+            "pass --version; {} cannot be listed",
+            releases
+        )));
+    };
+    fs::read_dir(&dir)
+        .map_err(|err| EngramError::Config(format!("cannot read {}: {}", dir.display(), err)))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix('v')
+                .filter(|version| is_release_version(version))
+                .map(str::to_string)
+        })
+        .max_by_key(|version| parse_version(version))
+        .ok_or_else(|| EngramError::Config(format!("no v<version> releases in {}", dir.display())))
+}
+
+/// The version in the `tag_name` of a GitHub latest-release response
+fn latest_tag(response: &[u8]) -> Result<String, EngramError> {
+    serde_json::from_slice::<Value>(response)
+        .ok()
+        .and_then(|release| {
+            release["tag_name"]
+                .as_str()
+                .map(|tag| tag.trim_start_matches('v').to_string())
+        })
+        .ok_or_else(|| {
+            EngramError::Config(format!(
+                "{} did not name a release; pass --version",
+                LATEST_RELEASE_API
+            ))
+        })
+}
+
+/// Read a release file from a local directory, or download it with curl or
+/// wget as the wrappers do
+fn fetch(location: &str) -> Result<Vec<u8>, EngramError> {
+    if let Some(path) = local_dir(location) {
+        return fs::read(&path).map_err(|err| {
+            EngramError::Config(format!("cannot read {}: {}", path.display(), err))
+        });
+    }
+
+    let attempts: [(&str, &[&str]); 2] = [("curl", &["-fsSL"]), ("wget", &["-qO-"])];
+    for (program, args) in attempts {
+        let output = match Command::new(program).args(args).arg(location).output() {
+            Ok(output) => output,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if !output.status.success() {
+            return Err(EngramError::Config(format!(
+                "failed to download {}: {}",
+                location,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        return Ok(output.stdout);
+    }
+    Err(EngramError::Config(format!(
+        "curl or wget is required to download {}",
+        location
+    )))
+}
+
+/// Swap `binary` in for `exe`. The running binary is moved aside first,
+/// which also works on Windows, and restored if the swap fails.
+fn replace_binary(exe: &Path, binary: &[u8]) -> io::Result<()> {
+    let name = exe.file_name().unwrap_or_default().to_string_lossy();
+    let staging = exe.with_file_name(format!(".{}.new", name));
+    let previous = exe.with_file_name(format!(".{}.old", name));
+
+    fs::write(&staging, binary)?;
+    set_executable(&staging)?;
+    // Left behind by an earlier update on Windows
+    let _ = fs::remove_file(&previous);
+    fs::rename(exe, &previous)?;
+    if let Err(err) = fs::rename(&staging, exe) {
+        fs::rename(&previous, exe)?;
+        return Err(err);
+    }
+    // Windows keeps the running binary locked until it exits
+    let _ = fs::remove_file(&previous);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// A release directory with `v<version>/` for each (version, binary)
+    fn releases(dir: &Path, asset: &str, builds: &[(&str, &[u8])]) {
+        for (version, binary) in builds {
+            let release = dir.join(format!("v{}", version));
+            fs::create_dir_all(&release).unwrap();
+            fs::write(release.join(asset), binary).unwrap();
+            fs::write(
+                release.join("checksums.txt"),
+                format!("{}  {}\n", sha256_hex(binary), asset),
+            )
+            .unwrap();
+        }
+    }
+
+    fn options(from: &Path, version: Option<&str>) -> SelfUpdateOptions {
+        SelfUpdateOptions {
+            from: Some(format!("file://{}", from.display())),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_self_update_installs_newest_verified_release() {
+        let Some(asset) = platform_asset() else {
+            return;
+        };
+        let dir = tempdir().unwrap();
+        let exe = dir.path().join("engram");
+        fs::write(&exe, b"old build").unwrap();
+        let store = dir.path().join("releases");
+        releases(
+            &store,
+            &asset,
+            &[("0.0.1", b"ancient"), ("99.0.0", b"new build")],
+        );
+
+        let report = run_self_update(&exe, &options(&store, None)).unwrap();
+
+        assert!(report.updated());
+        assert_eq!(report.to_version, "99.0.0");
+        assert_eq!(fs::read(&exe).unwrap(), b"new build");
+        assert!(!dir.path().join(".engram.old").exists());
+        assert!(!dir.path().join(".engram.new").exists());
+    }
+
+    #[test]
+    fn test_self_update_only_moves_forward_unless_asked() {
+        let Some(asset) = platform_asset() else {
+            return;
+        };
+        let dir = tempdir().unwrap();
+        let exe = dir.path().join("engram");
+        fs::write(&exe, b"current build").unwrap();
+        let store = dir.path().join("releases");
+        releases(&store, &asset, &[("0.0.1", b"ancient")]);
+
+        let report = run_self_update(&exe, &options(&store, None)).unwrap();
+        assert!(!report.updated());
+        assert_eq!(fs::read(&exe).unwrap(), b"current build");
+
+        let report = run_self_update(&exe, &options(&store, Some("v0.0.1"))).unwrap();
+        assert!(report.updated());
+        assert_eq!(fs::read(&exe).unwrap(), b"ancient");
+    }
+
+    #[test]
+    fn test_self_update_refuses_checksum_mismatch() {
+        let Some(asset) = platform_asset() else {
+            return;
+        };
+        let dir = tempdir().unwrap();
+        let exe = dir.path().join("engram");
+        fs::write(&exe, b"current build").unwrap();
+        let store = dir.path().join("releases");
+        releases(&store, &asset, &[("99.0.0", b"new build")]);
+        fs::write(store.join("v99.0.0").join(&asset), b"tampered").unwrap();
+
+        let result = run_self_update(&exe, &options(&store, None));
+
+        assert!(
+            matches!(result, Err(EngramError::Config(message)) if message.contains("mismatch"))
+        );
+        assert_eq!(fs::read(&exe).unwrap(), b"current build");
+    }
+
+    #[test]
+    fn test_remote_releases_need_a_version() {
+        let dir = tempdir().unwrap();
+        let options = SelfUpdateOptions {
+            from: Some("https://releases.example".to_string()),
+            version: None,
+        };
+        assert!(matches!(
+            run_self_update(&dir.path().join("engram"), &options),
+            Err(EngramError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_latest_tag_reads_github_response() {
+        assert_eq!(
+            latest_tag(br#"{"tag_name": "v1.4.2", "name": "Engram 1.4.2"}"#).unwrap(),
+            "1.4.2"
+        );
+        assert!(matches!(
+            latest_tag(br#"{"message": "API rate limit exceeded"}"#),
+            Err(EngramError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_other_remote_sources_need_a_version() {
+        let err = newest_release("https://mirror.example/engram").unwrap_err();
+        assert!(err.to_string().contains("--version"));
+    }
+}
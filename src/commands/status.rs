@@ -1,92 +1,73 @@
+use serde_json::{json, Value};
 use std::fs;
 use std::io;
 use std::path::Path;
 
-use crate::commands::verify::{verify_chain, VerifyError};
+use crate::commands::verify::{verify_chain_in_dir, VerifyResult};
 use crate::engram::chain::{parse_date, parse_summary};
 use crate::engram::draft::Draft;
-use crate::engram::worklog::WorklogEntry;
+use crate::engram::worklog::{WorklogEntry, DATE_FORMAT};
+use crate::error::EngramError;
+use crate::utils::clock::SystemClock;
 
 const ENGRAM_DIR: &str = ".engram";
 const DRAFT_FILE: &str = ".engram/draft.md";
 const WORKLOG_DIR: &str = ".engram/worklog";
 
-pub fn run() -> io::Result<()> {
-    run_status_in_dir(Path::new("."))
+/// Snapshot of an Engram repository's state
+#[derive(Debug)]
+pub struct StatusReport {
+    pub worklog: WorklogInfo,
+    pub draft: DraftStatus,
+    pub chain: Result<VerifyResult, EngramError>,
 }
 
-fn run_status_in_dir(base_dir: &Path) -> io::Result<()> {
+impl StatusReport {
+    /// JSON form used by `--json` and the MCP server
+    pub fn to_json(&self) -> Value {
+        let draft = match &self.draft {
+            DraftStatus::HasContent(summary) => json!({"state": "has_content", "summary": summary}),
+            DraftStatus::Empty => json!({"state": "empty"}),
+            DraftStatus::NotFound => json!({"state": "not_found"}),
+        };
+        let chain = match &self.chain {
+            Ok(_) => json!({"verified": true}),
+            Err(e) => json!({"verified": false, "error": e.to_json()["error"]}),
+        };
+        json!({
+            "entry_count": self.worklog.entry_count,
+            "latest": self.worklog.latest.as_ref().map(|latest| json!({
+                "filename": latest.filename,
+                "date": latest.date,
+                "summary": latest.summary,
+            })),
+            "draft": draft,
+            "chain": chain,
+        })
+    }
+}
+
+/// Gather worklog, draft and chain status for the repository in `base_dir`
+pub fn run_status_in_dir(base_dir: &Path) -> Result<StatusReport, EngramError> {
     let engram_dir = base_dir.join(ENGRAM_DIR);
     let draft_file = base_dir.join(DRAFT_FILE);
     let worklog_dir = base_dir.join(WORKLOG_DIR);
 
     // Check if engram is initialized
-    if !engram_dir.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "Engram not initialized. Run `engram init` first.",
-        ));
-    }
-
-    // Print header
-    println!("Engram Status");
-    println!("─────────────");
-
-    // Validate worklog directory exists
-    if !worklog_dir.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "Engram not initialized. Run `engram init` first.",
-        ));
-    }
-
-    // Get worklog info
-    let info = get_worklog_info(&worklog_dir)?;
-    println!("Worklog: {} entries", info.entry_count);
-
-    // Display latest entry info if available
-    if let Some(latest) = info.latest {
-        println!("Latest:  {} ({})", latest.filename, latest.date);
-        println!("         \"{}\"", latest.summary);
-    }
-
-    println!();
-
-    // Get draft status
-    let draft_status = get_draft_status(&draft_file);
-    match draft_status {
-        DraftStatus::HasContent(summary) => {
-            println!("Draft:   Has content (uncommitted work)");
-            println!("         Summary: \"{}\"", summary);
-        }
-        DraftStatus::Empty => {
-            println!("Draft:   Empty (ready for new work)");
-        }
-        DraftStatus::NotFound => {
-            println!("Draft:   Not found");
-        }
+    if !engram_dir.exists() || !worklog_dir.exists() {
+        return Err(EngramError::NotInitialized);
     }
 
-    println!();
-
-    // Verify chain
-    match verify_chain() {
-        Ok(_) => {
-            println!("Chain:   ✓ Verified");
-        }
-        Err(VerifyError::NotInitialized) => {
-            println!("Chain:   Not initialized");
-        }
-        Err(e) => {
-            println!("Chain:   ✗ {}", e);
-        }
-    }
-
-    Ok(())
+    Ok(StatusReport {
+        worklog: get_worklog_info(&worklog_dir)?,
+        draft: get_draft_status(&draft_file),
+        chain: verify_chain_in_dir(base_dir, &SystemClock),
+    })
 }
 
 /// Status of the draft file
-enum DraftStatus {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DraftStatus {
     HasContent(String), // Contains the summary
     Empty,
     NotFound,
@@ -109,15 +90,19 @@ fn get_draft_status(draft_path: &Path) -> DraftStatus {
     }
 }
 
-struct LatestWorklogEntry {
-    filename: String,
-    date: String,
-    summary: String,
+/// Filename, date and summary of the newest entry
+#[derive(Debug, Clone)]
+pub struct LatestWorklogEntry {
+    pub filename: String,
+    pub date: String,
+    pub summary: String,
 }
 
-struct WorklogInfo {
-    entry_count: usize,
-    latest: Option<LatestWorklogEntry>,
+/// Entry count and newest entry of the worklog
+#[derive(Debug, Clone)]
+pub struct WorklogInfo {
+    pub entry_count: usize,
+    pub latest: Option<LatestWorklogEntry>,
 }
 
 /// Get worklog information: entry count and latest entry details
@@ -156,7 +141,9 @@ fn get_worklog_info(worklog_path: &Path) -> io::Result<WorklogInfo> {
 
     // Read the latest entry to get date and summary
     let content = fs::read_to_string(&latest.path)?;
-    let date = parse_date(&content).unwrap_or_else(|| "unknown".to_string());
+    let date = parse_date(&content)
+        .map(|date| date.format(DATE_FORMAT).to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
     let summary = parse_summary(&content).unwrap_or_else(|| "No summary".to_string());
 
     Ok(WorklogInfo {
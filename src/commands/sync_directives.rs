@@ -0,0 +1,236 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::commands::directives::{refresh_directive, DirectiveState, DIRECTIVE_TARGETS};
+use crate::error::EngramError;
+use crate::templates::AGENTS_TEMPLATE;
+use crate::utils::diff::unified_diff;
+
+const ENGRAM_DIR: &str = ".engram";
+/// Full protocol instructions, owned by Engram and replaced whole
+const AGENTS_FILE: &str = ".engram/AGENTS.md";
+
+/// Options for `engram sync-directives`
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Report the changes as diffs without writing them
+    pub dry_run: bool,
+}
+
+/// What happened to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// Already matches the current template
+    Current,
+    /// The directive block (or AGENTS.md) was replaced with the current template
+    Updated,
+    /// An unmarked directive from an older release became a versioned block
+    Migrated,
+    /// The file was missing and was created
+    Created,
+}
+
+impl SyncStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncStatus::Current => "current",
+            SyncStatus::Updated => "updated",
+            SyncStatus::Migrated => "migrated",
+            SyncStatus::Created => "created",
+        }
+    }
+}
+
+/// One file checked by `sync-directives`
+#[derive(Debug, Clone)]
+pub struct FileSync {
+    pub path: String,
+    pub status: SyncStatus,
+    /// Unified diff of the change, empty when the file is current
+    pub diff: String,
+}
+
+impl std::fmt::Display for FileSync {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.status {
+            SyncStatus::Current => write!(f, "Up to date: {}", self.path),
+            SyncStatus::Updated => write!(f, "Updated: {}", self.path),
+            SyncStatus::Migrated => {
+                write!(f, "Updated: {} (added version markers)", self.path)
+            }
+            SyncStatus::Created => write!(f, "Created: {}", self.path),
+        }
+    }
+}
+
+/// Result of `sync-directives`: every file that carries Engram instructions
+#[derive(Debug, Clone)]
+pub struct SyncReport {
+    pub dry_run: bool,
+    pub files: Vec<FileSync>,
+}
+
+impl SyncReport {
+    /// Files that were (or, in a dry run, would be) rewritten
+    pub fn changed(&self) -> impl Iterator<Item = &FileSync> {
+        self.files
+            .iter()
+            .filter(|file| file.status != SyncStatus::Current)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let files: Vec<_> = self
+            .files
+            .iter()
+            .map(|file| {
+                json!({
+                    "path": file.path,
+                    "status": file.status.as_str(),
+                    "diff": file.diff,
+                })
+            })
+            .collect();
+        json!({
+            "dry_run": self.dry_run,
+            "changed": self.changed().count(),
+            "files": files,
+        })
+    }
+}
+
+/// Bring `.engram/AGENTS.md` and every directive block under `root` up to the
+/// current templates. Files without an Engram directive are left alone.
+pub fn run_sync_directives_in_dir(
+    root: &Path,
+    options: &SyncOptions,
+) -> Result<SyncReport, EngramError> {
+    if !root.join(ENGRAM_DIR).exists() {
+        return Err(EngramError::NotInitialized);
+    }
+
+    let mut files = Vec::new();
+
+    let agents = fs::read_to_string(root.join(AGENTS_FILE)).ok();
+    let status = match &agents {
+        None => SyncStatus::Created,
+        Some(content) if content == AGENTS_TEMPLATE => SyncStatus::Current,
+        Some(_) => SyncStatus::Updated,
+    };
+    files.push(sync_file(
+        root,
+        AGENTS_FILE,
+        agents.as_deref().unwrap_or_default(),
+        AGENTS_TEMPLATE,
+        status,
+        options,
+    )?);
+
+    for target in DIRECTIVE_TARGETS {
+        let relative = target.relative_path(root);
+        let Ok(content) = fs::read_to_string(root.join(relative)) else {
+            continue;
+        };
+        let (state, updated) = refresh_directive(&content);
+        let status = match state {
+            DirectiveState::Missing => continue,
+            DirectiveState::Current => SyncStatus::Current,
+            DirectiveState::Stale => SyncStatus::Updated,
+            DirectiveState::Legacy => SyncStatus::Migrated,
+        };
+        files.push(sync_file(
+            root, relative, &content, &updated, status, options,
+        )?);
+    }
+
+    Ok(SyncReport {
+        dry_run: options.dry_run,
+        files,
+    })
+}
+
+fn sync_file(
+    root: &Path,
+    relative: &str,
+    old: &str,
+    new: &str,
+    status: SyncStatus,
+    options: &SyncOptions,
+) -> Result<FileSync, EngramError> {
+    if status != SyncStatus::Current && !options.dry_run {
+        fs::write(root.join(relative), new)?;
+    }
+    Ok(FileSync {
+        path: relative.to_string(),
+        status,
+        diff: unified_diff(relative, old, new),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::directives::directive_block;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use crate::templates::ROOT_DIRECTIVE_TEMPLATE;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sync_requires_init() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            run_sync_directives_in_dir(dir.path(), &SyncOptions::default()),
+            Err(EngramError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_fresh_install_is_current() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+
+        let report = run_sync_directives_in_dir(dir.path(), &SyncOptions::default()).unwrap();
+
+        let paths: Vec<_> = report.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec![".engram/AGENTS.md", "AGENTS.md"]);
+        assert_eq!(report.changed().count(), 0);
+    }
+
+    #[test]
+    fn test_sync_updates_stale_files_and_dry_run_writes_nothing() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let legacy = format!("# Claude\n\n{}## Style\n\nTabs.\n", ROOT_DIRECTIVE_TEMPLATE);
+        fs::write(dir.path().join("CLAUDE.md"), &legacy).unwrap();
+        fs::write(dir.path().join(".engram/AGENTS.md"), "old instructions\n").unwrap();
+
+        let dry_run = SyncOptions { dry_run: true };
+        let report = run_sync_directives_in_dir(dir.path(), &dry_run).unwrap();
+        assert_eq!(report.changed().count(), 2);
+        assert!(report.files[0].diff.contains("-old instructions\n"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap(),
+            legacy
+        );
+
+        let report = run_sync_directives_in_dir(dir.path(), &SyncOptions::default()).unwrap();
+        let claude = report
+            .files
+            .iter()
+            .find(|file| file.path == "CLAUDE.md")
+            .unwrap();
+        assert_eq!(claude.status, SyncStatus::Migrated);
+        assert!(claude.diff.contains("+<!-- engram:begin v"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap(),
+            format!("# Claude\n\n{}## Style\n\nTabs.\n", directive_block())
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".engram/AGENTS.md")).unwrap(),
+            AGENTS_TEMPLATE
+        );
+
+        let report = run_sync_directives_in_dir(dir.path(), &SyncOptions::default()).unwrap();
+        assert_eq!(report.changed().count(), 0);
+    }
+}
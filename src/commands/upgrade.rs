@@ -0,0 +1,548 @@
+use chrono::Utc;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::commands::doctor::wrapper_version;
+use crate::commands::import::{chain_entries, write_entries};
+use crate::commands::init::{
+    set_executable, write_wrappers, WriteStatus, GITATTRIBUTES_CONTENT, GITIGNORE_CONTENT,
+    VERSION_FILE,
+};
+use crate::commands::sync_directives::{run_sync_directives_in_dir, SyncOptions, SyncStatus};
+use crate::commands::vendor::vendored_binary;
+use crate::commands::verify::{scan_worklog, verify_entries, VerifyError};
+use crate::commands::wrappers::{read_wrapper, WrapperKind, WrapperMode, WrapperSpec};
+use crate::engram::worklog::{EntryContent, WorklogEntry};
+use crate::error::EngramError;
+use crate::repository::Repository;
+use crate::utils::clock::Clock;
+
+const ENGRAM_DIR: &str = ".engram";
+const WORKLOG_DIR: &str = ".engram/worklog";
+/// Extra header on the upgrade entry: `<from> -> <to>`
+pub const UPGRADE_HEADER: &str = "Engram-Upgrade";
+
+/// One migration: a name for reports and a step that returns what it changed
+type Migration = (
+    &'static str,
+    fn(&Path, &mut UpgradeReport) -> Result<Vec<String>, EngramError>,
+);
+
+/// Migrations run in this order; each one checks the installation's state, so
+/// running them again is a no-op
+const MIGRATIONS: [Migration; 4] = [
+    ("Legacy entry filenames", migrate_entry_filenames),
+    ("Git hygiene files", migrate_hygiene_files),
+    ("Wrapper scripts", migrate_wrappers),
+    ("Templates", migrate_templates),
+];
+
+/// What one migration changed
+#[derive(Debug, Clone)]
+pub struct MigrationStep {
+    pub name: &'static str,
+    pub changes: Vec<String>,
+}
+
+/// Result of `engram upgrade`
+#[derive(Debug, Clone)]
+pub struct UpgradeReport {
+    /// Version the installation was at, when it could be determined
+    pub from: Option<String>,
+    /// Version of this binary, now recorded in `.engram/VERSION`
+    pub to: String,
+    pub steps: Vec<MigrationStep>,
+    /// Things the upgrade left alone and the user may want to look at
+    pub warnings: Vec<String>,
+    /// Filename of the entry recording the upgrade, if anything changed
+    pub entry: Option<String>,
+}
+
+impl UpgradeReport {
+    /// True when any migration changed a file
+    pub fn changed(&self) -> bool {
+        self.steps.iter().any(|step| !step.changes.is_empty())
+    }
+
+    pub fn to_json(&self) -> Value {
+        let steps: Vec<_> = self
+            .steps
+            .iter()
+            .map(|step| json!({"name": step.name, "changes": step.changes}))
+            .collect();
+        json!({
+            "from": self.from,
+            "to": self.to,
+            "steps": steps,
+            "warnings": self.warnings,
+            "entry": self.entry,
+        })
+    }
+}
+
+/// Bring the installation in `root` up to this release: run every migration,
+/// record what changed in a worklog entry and write `.engram/VERSION`
+pub fn run_upgrade_in_dir(root: &Path, clock: &dyn Clock) -> Result<UpgradeReport, EngramError> {
+    if !root.join(ENGRAM_DIR).exists() || !root.join(WORKLOG_DIR).exists() {
+        return Err(EngramError::NotInitialized);
+    }
+
+    let current = env!("CARGO_PKG_VERSION");
+    let from = installed_version(root);
+    if let Some(from) = &from {
+        if parse_version(from) > parse_version(current) {
+            return Err(EngramError::Config(format!(
+                "this installation was upgraded to Engram {} but this binary is {}; install a newer engram",
+                from, current
+            )));
+        }
+    }
+
+    // A failure after a migration would leave the tree changed but unrecorded
+    preflight(root, clock)?;
+
+    let mut report = UpgradeReport {
+        from,
+        to: current.to_string(),
+        steps: Vec::new(),
+        warnings: Vec::new(),
+        entry: None,
+    };
+    for (name, migrate) in MIGRATIONS {
+        let changes = migrate(root, &mut report)?;
+        report.steps.push(MigrationStep { name, changes });
+    }
+
+    if report.changed() {
+        report.entry = Some(record_upgrade(root, &report, clock)?);
+    }
+    fs::write(root.join(VERSION_FILE), format!("{}\n", current))?;
+
+    Ok(report)
+}
+
+/// Verify the chain, reading legacy entry files under the names the migrations
+/// will give them, and check the clock, before anything is changed
+fn preflight(root: &Path, clock: &dyn Clock) -> Result<(), EngramError> {
+    let worklog_dir = root.join(WORKLOG_DIR);
+    let scan = scan_worklog(&worklog_dir)?;
+    let mut entries = scan.entries;
+    for filename in scan.unexpected {
+        let entry = legacy_entry_name(&filename)
+            .and_then(|renamed| WorklogEntry::from_filename(&renamed, &worklog_dir))
+            .ok_or_else(|| VerifyError::UnexpectedFile(filename.clone()))?;
+        entries.push(WorklogEntry {
+            path: worklog_dir.join(&filename),
+            ..entry
+        });
+    }
+
+    let latest = entries
+        .iter()
+        .max_by_key(|entry| entry.sequence)
+        .map(|entry| entry.path.clone());
+    verify_entries(entries, Utc::now())?;
+
+    if let Some(path) = latest {
+        let latest = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| EntryContent::parse(&text))
+            .ok_or_else(|| {
+                EngramError::InvalidEntry(
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            })?
+            .date;
+        let now = clock.now();
+        if now < latest {
+            return Err(EngramError::ClockBehind { now, latest });
+        }
+    }
+    Ok(())
+}
+
+/// The version in `.engram/VERSION`, or else the one pinned by `./engram`
+pub fn installed_version(root: &Path) -> Option<String> {
+    if let Ok(content) = fs::read_to_string(root.join(VERSION_FILE)) {
+        let version = content.trim();
+        if !version.is_empty() {
+            return Some(version.to_string());
+        }
+    }
+    fs::read_to_string(root.join("engram"))
+        .ok()
+        .and_then(|content| wrapper_version(&content))
+}
+
+/// `1.2.3` (with any `-pre`/`+build` suffix ignored) as comparable numbers
+pub(crate) fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// The current name for an entry file written with a short sequence number
+/// (`002_e5f6a7b8.md` becomes `000002_e5f6a7b8.md`)
+pub(crate) fn legacy_entry_name(filename: &str) -> Option<String> {
+    let re = Regex::new(r"^(\d{1,5})_([a-f0-9]{8})\.md$").unwrap();
+    let caps = re.captures(filename)?;
+    let sequence: u32 = caps[1].parse().ok()?;
+    Some(format!("{:06}_{}.md", sequence, &caps[2]))
+}
+
+/// Rename short-sequence entry files. Names are not hashed, so the chain is
+/// unchanged; the upgrade entry written afterwards re-anchors it.
+fn migrate_entry_filenames(
+    root: &Path,
+    _report: &mut UpgradeReport,
+) -> Result<Vec<String>, EngramError> {
+    let worklog_dir = root.join(WORKLOG_DIR);
+    let mut renames = Vec::new();
+    for dir_entry in fs::read_dir(&worklog_dir)? {
+        let filename = dir_entry?.file_name().to_string_lossy().to_string();
+        if let Some(renamed) = legacy_entry_name(&filename) {
+            renames.push((filename, renamed));
+        }
+    }
+    renames.sort_by(|a, b| a.1.cmp(&b.1));
+
+    if let Some((filename, renamed)) = renames
+        .iter()
+        .find(|(_, renamed)| worklog_dir.join(renamed).exists())
+    {
+        return Err(EngramError::Config(format!(
+            "cannot rename {} to {}: the target already exists",
+            filename, renamed
+        )));
+    }
+
+    let mut changes = Vec::with_capacity(renames.len());
+    for (filename, renamed) in &renames {
+        fs::rename(worklog_dir.join(filename), worklog_dir.join(renamed))?;
+        changes.push(format!("Renamed {} to {}", filename, renamed));
+    }
+
+    if !changes.is_empty() {
+        // SUMMARY.md rows link to the old names
+        Repository::open(root)?.rebuild_summary()?;
+        changes.push("Rebuilt .engram/worklog/SUMMARY.md".to_string());
+    }
+    Ok(changes)
+}
+
+/// Create `.engram/.gitignore` and `.engram/.gitattributes` if missing
+fn migrate_hygiene_files(
+    root: &Path,
+    _report: &mut UpgradeReport,
+) -> Result<Vec<String>, EngramError> {
+    let mut changes = Vec::new();
+    for (relative, content) in [
+        (".engram/.gitignore", GITIGNORE_CONTENT),
+        (".engram/.gitattributes", GITATTRIBUTES_CONTENT),
+    ] {
+        let path = root.join(relative);
+        if !path.exists() {
+            fs::write(&path, content)?;
+            changes.push(format!("Created {}", relative));
+        }
+    }
+    Ok(changes)
+}
+
+/// Re-pin unmodified wrappers to this release and restore missing ones
+fn migrate_wrappers(root: &Path, report: &mut UpgradeReport) -> Result<Vec<String>, EngramError> {
+    let current = env!("CARGO_PKG_VERSION");
+    let spec = WrapperSpec::installed(root);
+    let mut changes = Vec::new();
+
+    let written = write_wrappers(root, &spec, current)?;
+    for (name, status) in [
+        ("engram", &written.sh_status),
+        ("engram.cmd", &written.cmd_status),
+    ] {
+        if matches!(status, WriteStatus::Created) {
+            changes.push(format!("Created {}", name));
+        }
+    }
+
+    for kind in WrapperKind::ALL {
+        let name = kind.file_name();
+        let path = root.join(name);
+        let content = fs::read_to_string(&path)?;
+        if wrapper_version(&content).as_deref() == Some(current) {
+            continue;
+        }
+        match read_wrapper(kind, &content) {
+            Some(wrapper) if wrapper.spec.can_repin() => {
+                fs::write(&path, wrapper.render(kind, &wrapper.spec, current))?;
+                if kind == WrapperKind::Sh {
+                    set_executable(&path)?;
+                }
+                changes.push(format!(
+                    "Re-pinned {} from {} to {}",
+                    name, wrapper.version, current
+                ));
+            }
+            Some(_) => report.warnings.push(format!(
+                "{} pins the checksums of an older release; run `engram pin {} --checksums <FILE>`",
+                name, current
+            )),
+            None => report.warnings.push(format!(
+                "{} has local changes and was left as is; set ENGRAM_VERSION to {} by hand",
+                name, current
+            )),
+        }
+    }
+
+    if spec.mode == WrapperMode::Local && !root.join(vendored_binary(current)).exists() {
+        report.warnings.push(format!(
+            "the wrappers run in local mode; run `engram vendor` to put Engram {} in .engram/bin",
+            current
+        ));
+    }
+    Ok(changes)
+}
+
+/// Refresh `.engram/AGENTS.md` and the directive blocks in agent files
+fn migrate_templates(root: &Path, _report: &mut UpgradeReport) -> Result<Vec<String>, EngramError> {
+    let synced = run_sync_directives_in_dir(root, &SyncOptions::default())?;
+    Ok(synced
+        .changed()
+        .map(|file| match file.status {
+            SyncStatus::Migrated => {
+                format!("Added version markers to the directive in {}", file.path)
+            }
+            SyncStatus::Created => format!("Created {}", file.path),
+            _ => format!("Refreshed {}", file.path),
+        })
+        .collect())
+}
+
+/// Append an entry listing every change, chained onto the (renamed) history
+fn record_upgrade(
+    root: &Path,
+    report: &UpgradeReport,
+    clock: &dyn Clock,
+) -> Result<String, EngramError> {
+    let repo = Repository::open(root)?;
+    // Renamed history must verify before anything is anchored to it
+    repo.verify()?;
+    let existing = repo.entries()?;
+
+    let date = clock.now();
+    if let Some(latest) = existing.last().map(|entry| entry.content.date) {
+        if date < latest {
+            return Err(EngramError::ClockBehind { now: date, latest });
+        }
+    }
+
+    let from = report.from.as_deref().unwrap_or("unknown");
+    let mut body = format!(
+        "## Intent\nUpgrade the Engram installation from {} to {}.\n\n## Changes\n",
+        from, report.to
+    );
+    for step in report.steps.iter().filter(|step| !step.changes.is_empty()) {
+        body.push_str(&format!("### {}\n", step.name));
+        for change in &step.changes {
+            body.push_str(&format!("- {}\n", change));
+        }
+    }
+    body.push_str("\n## Verification\nengram verify passed before this entry was written.");
+
+    let content = EntryContent {
+        summary: format!("Upgrade Engram from {} to {}", from, report.to),
+        previous: String::new(),
+        date,
+        headers: vec![(
+            UPGRADE_HEADER.to_string(),
+            format!("{} -> {}", from, report.to),
+        )],
+        body,
+    };
+    let written = write_entries(&repo, chain_entries(&existing, vec![content])?)?;
+    Ok(written.into_iter().next().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{render_wrapper, run_init_in_dir, InitOptions};
+    use crate::templates::WRAPPER_SH_TEMPLATE;
+    use crate::utils::clock::FixedClock;
+    use chrono::{TimeZone, Utc};
+    use tempfile::tempdir;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap())
+    }
+
+    fn commit(root: &Path, summary: &str) {
+        let repo = Repository::open(root).unwrap();
+        repo.write_draft(&format!(
+            "<summary>{}</summary>\n\n## Changes\n- x",
+            summary
+        ))
+        .unwrap();
+        repo.commit_with_clock(&clock()).unwrap();
+    }
+
+    #[test]
+    fn test_upgrade_requires_init() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            run_upgrade_in_dir(dir.path(), &clock()),
+            Err(EngramError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_current_install_needs_nothing() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+
+        let report = run_upgrade_in_dir(dir.path(), &clock()).unwrap();
+
+        assert_eq!(report.from.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+        assert!(!report.changed());
+        assert!(report.entry.is_none());
+        assert!(Repository::open(dir.path())
+            .unwrap()
+            .entries()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_upgrade_from_old_release() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        commit(dir.path(), "First");
+        commit(dir.path(), "Second");
+
+        // An installation from before VERSION and 6-digit sequences
+        fs::remove_file(dir.path().join(VERSION_FILE)).unwrap();
+        fs::write(
+            dir.path().join("engram"),
+            render_wrapper(WRAPPER_SH_TEMPLATE, "0.0.9"),
+        )
+        .unwrap();
+        fs::write(dir.path().join("engram.cmd"), "@echo custom\n").unwrap();
+        let worklog = dir.path().join(WORKLOG_DIR);
+        let mut names: Vec<String> = fs::read_dir(&worklog)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name != "SUMMARY.md")
+            .collect();
+        names.sort();
+        for name in &names {
+            fs::rename(worklog.join(name), worklog.join(&name[3..])).unwrap();
+        }
+
+        let report = run_upgrade_in_dir(dir.path(), &clock()).unwrap();
+
+        assert_eq!(report.from.as_deref(), Some("0.0.9"));
+        assert_eq!(
+            report.steps[0].changes[0],
+            format!("Renamed {} to {}", &names[0][3..], names[0])
+        );
+        assert_eq!(
+            report.steps[2].changes,
+            vec![format!(
+                "Re-pinned engram from 0.0.9 to {}",
+                env!("CARGO_PKG_VERSION")
+            )]
+        );
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.entry.as_deref().unwrap().starts_with("000003_"));
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.verify().unwrap();
+        let entry = repo.find_entry("3").unwrap();
+        assert_eq!(
+            entry.content.header(UPGRADE_HEADER),
+            Some(format!("0.0.9 -> {}", env!("CARGO_PKG_VERSION")).as_str())
+        );
+        assert!(entry.content.body.contains("- Renamed 001_"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join(VERSION_FILE)).unwrap(),
+            format!("{}\n", env!("CARGO_PKG_VERSION"))
+        );
+
+        // Running again finds nothing left to do
+        let report = run_upgrade_in_dir(dir.path(), &clock()).unwrap();
+        assert!(!report.changed());
+    }
+
+    #[test]
+    fn test_failed_preflight_leaves_tree_untouched() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        commit(dir.path(), "First");
+        fs::remove_file(dir.path().join(VERSION_FILE)).unwrap();
+        let worklog = dir.path().join(WORKLOG_DIR);
+        let name = fs::read_dir(&worklog)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .find(|name| name != "SUMMARY.md")
+            .unwrap();
+        fs::rename(worklog.join(&name), worklog.join(&name[3..])).unwrap();
+
+        let behind = FixedClock(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert!(matches!(
+            run_upgrade_in_dir(dir.path(), &behind),
+            Err(EngramError::ClockBehind { .. })
+        ));
+        assert!(worklog.join(&name[3..]).exists());
+        assert!(!dir.path().join(VERSION_FILE).exists());
+
+        fs::write(worklog.join("notes.txt"), "x").unwrap();
+        assert!(matches!(
+            run_upgrade_in_dir(dir.path(), &clock()),
+            Err(EngramError::Chain(VerifyError::UnexpectedFile(f))) if f == "notes.txt"
+        ));
+        assert!(worklog.join(&name[3..]).exists());
+        assert!(!dir.path().join(VERSION_FILE).exists());
+
+        // Once the problem is gone the retry still records the upgrade
+        fs::remove_file(worklog.join("notes.txt")).unwrap();
+        let report = run_upgrade_in_dir(dir.path(), &clock()).unwrap();
+        assert!(report.entry.is_some());
+    }
+
+    #[test]
+    fn test_refuses_newer_installation() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        fs::write(dir.path().join(VERSION_FILE), "99.0.0\n").unwrap();
+
+        assert!(matches!(
+            run_upgrade_in_dir(dir.path(), &clock()),
+            Err(EngramError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_legacy_entry_name() {
+        assert_eq!(
+            legacy_entry_name("002_e5f6a7b8.md").as_deref(),
+            Some("000002_e5f6a7b8.md")
+        );
+        assert_eq!(legacy_entry_name("000002_e5f6a7b8.md"), None);
+        assert_eq!(legacy_entry_name("SUMMARY.md"), None);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert!(parse_version("0.10.0") > parse_version("0.9.3"));
+        assert_eq!(parse_version("1.2.3-rc.1"), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,184 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::init::set_executable;
+use crate::commands::wrappers::{parse_checksums, platform_asset};
+use crate::error::EngramError;
+use crate::utils::hash::sha256_hex;
+
+const ENGRAM_DIR: &str = ".engram";
+
+/// Options for `engram vendor`
+#[derive(Debug, Clone, Default)]
+pub struct VendorOptions {
+    /// Release checksums.txt the binary must match
+    pub checksums: Option<PathBuf>,
+}
+
+/// Result of `vendor`
+#[derive(Debug, Clone)]
+pub struct VendorReport {
+    pub version: String,
+    /// The vendored binary, relative to the project root
+    pub path: String,
+    pub sha256: String,
+    /// Release asset the binary was checked against in `checksums`
+    pub verified_asset: Option<String>,
+}
+
+impl VendorReport {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "version": self.version,
+            "path": self.path,
+            "sha256": self.sha256,
+            "verified_asset": self.verified_asset,
+        })
+    }
+}
+
+/// Where the wrappers look for the binary of `version`, relative to the
+/// project root
+pub fn vendored_binary(version: &str) -> String {
+    format!(
+        "{}/bin/engram-v{}{}",
+        ENGRAM_DIR,
+        version,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+/// Copy `binary` (the running engram) to `.engram/bin/engram-v<version>`,
+/// where every wrapper mode finds it without downloading. The copy must hash
+/// the same as the source and, with `options.checksums`, as the release asset
+/// for this platform. The SHA-256 is recorded next to it in
+/// `engram-v<version>.sha256`, which local-mode wrappers check before running it.
+pub fn run_vendor_in_dir(
+    root: &Path,
+    binary: &Path,
+    options: &VendorOptions,
+) -> Result<VendorReport, EngramError> {
+    if !root.join(ENGRAM_DIR).exists() {
+        return Err(EngramError::NotInitialized);
+    }
+    let version = env!("CARGO_PKG_VERSION");
+    let sha256 = sha256_hex(fs::read(binary)?);
+
+    let verified_asset = match &options.checksums {
+        Some(checksums) => {
+            let asset = platform_asset().ok_or_else(|| {
+                EngramError::Config("no Engram release is built for this platform".to_string())
+            })?;
+            let content = fs::read_to_string(checksums)?;
+            let Some((_, expected)) = parse_checksums(&content)
+                .into_iter()
+                .find(|(name, _)| *name == asset)
+            else {
+                return Err(EngramError::Config(format!(
+                    "{} has no entry for {}",
+                    checksums.display(),
+                    asset
+                )));
+            };
+            if expected != sha256 {
+                return Err(EngramError::Config(format!(
+                    "checksum mismatch for {}: expected {}, this binary is {}",
+                    asset, expected, sha256
+                )));
+            }
+            Some(asset)
+        }
+        None => None,
+    };
+
+    let relative = vendored_binary(version);
+    let target = root.join(&relative);
+    let staging = target.with_extension("partial");
+    fs::create_dir_all(target.parent().unwrap_or(root))?;
+    fs::copy(binary, &staging)?;
+
+    // Only move the copy into place once it is known to be intact
+    let copied = sha256_hex(fs::read(&staging)?);
+    if copied != sha256 {
+        fs::remove_file(&staging)?;
+        return Err(EngramError::Config(format!(
+            "copy of {} is corrupt (expected {}, got {})",
+            binary.display(),
+            sha256,
+            copied
+        )));
+    }
+    set_executable(&staging)?;
+    fs::rename(&staging, &target)?;
+
+    let file_name = relative.rsplit('/').next().unwrap_or(&relative);
+    fs::write(
+        root.join(format!("{}.sha256", relative)),
+        format!("{}  {}\n", sha256, file_name),
+    )?;
+
+    Ok(VendorReport {
+        version: version.to_string(),
+        path: relative,
+        sha256,
+        verified_asset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_vendor_copies_binary_and_records_checksum() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let binary = dir.path().join("engram-build");
+        fs::write(&binary, b"\x7fELF fake engram").unwrap();
+
+        let report = run_vendor_in_dir(dir.path(), &binary, &VendorOptions::default()).unwrap();
+
+        let vendored = dir.path().join(&report.path);
+        assert_eq!(fs::read(&vendored).unwrap(), b"\x7fELF fake engram");
+        assert_eq!(report.sha256, sha256_hex(b"\x7fELF fake engram"));
+        let recorded = fs::read_to_string(format!("{}.sha256", vendored.display())).unwrap();
+        assert!(recorded.starts_with(&report.sha256));
+        assert!(!vendored.with_extension("partial").exists());
+    }
+
+    #[test]
+    fn test_vendor_checks_release_checksums() {
+        let Some(asset) = platform_asset() else {
+            return;
+        };
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let binary = dir.path().join("engram-build");
+        fs::write(&binary, b"release build").unwrap();
+        let checksums = dir.path().join("checksums.txt");
+
+        fs::write(&checksums, format!("{}  {}\n", "0".repeat(64), asset)).unwrap();
+        let options = VendorOptions {
+            checksums: Some(checksums.clone()),
+        };
+        assert!(matches!(
+            run_vendor_in_dir(dir.path(), &binary, &options),
+            Err(EngramError::Config(_))
+        ));
+        assert!(!dir
+            .path()
+            .join(vendored_binary(env!("CARGO_PKG_VERSION")))
+            .exists());
+
+        fs::write(
+            &checksums,
+            format!("{}  {}\n", sha256_hex(b"release build"), asset),
+        )
+        .unwrap();
+        let report = run_vendor_in_dir(dir.path(), &binary, &options).unwrap();
+        assert_eq!(report.verified_asset, Some(asset));
+    }
+}
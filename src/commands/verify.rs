@@ -1,19 +1,23 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::path::Path;
-use std::process;
 
-use crate::engram::chain::{parse_date, parse_previous_hash};
-use crate::engram::worklog::WorklogEntry;
+use crate::engram::canonical::{describe, diagnose_drift, Drift};
+use crate::engram::chain::{parse_date, parse_previous_hash, parse_refs, DateError};
+use crate::engram::refs::Reference;
+use crate::engram::worklog::{WorklogEntry, DATE_FORMAT};
+use crate::error::EngramError;
+use crate::utils::clock::{Clock, SystemClock};
 use crate::utils::hash::{sha256_hex, sha256_short};
 
 const ENGRAM_DIR: &str = ".engram";
 const WORKLOG_DIR: &str = ".engram/worklog";
 
-/// Exit codes per spec
-const EXIT_SUCCESS: i32 = 0;
-const EXIT_CHAIN_BROKEN: i32 = 1;
-const EXIT_NOT_INITIALIZED: i32 = 2;
+/// How far ahead of the local clock an entry date may be (allows for clock skew)
+const FUTURE_TOLERANCE_MINUTES: i64 = 5;
 
 /// Result of a successful verification
 #[derive(Debug)]
@@ -23,11 +27,26 @@ pub struct VerifyResult {
     pub latest_entry: Option<(String, String)>, // (filename, date)
 }
 
+impl VerifyResult {
+    /// JSON form used by `--json` and the MCP server
+    pub fn to_json(&self) -> Value {
+        let endpoint = |entry: &Option<(String, String)>| {
+            entry
+                .as_ref()
+                .map(|(filename, date)| json!({"filename": filename, "date": date}))
+        };
+        json!({
+            "verified": true,
+            "entry_count": self.entry_count,
+            "first": endpoint(&self.first_entry),
+            "latest": endpoint(&self.latest_entry),
+        })
+    }
+}
+
 /// Error types for verification failures
 #[derive(Debug)]
 pub enum VerifyError {
-    /// Engram not initialized
-    NotInitialized,
     /// Chain linkage broken - Previous hash mismatch
     ChainBroken {
         filename: String,
@@ -40,18 +59,47 @@ pub enum VerifyError {
         content_hash: String,
         filename_hash: String,
     },
+    /// Content differs from its recorded hash only by encoding noise
+    /// (CRLF line endings, BOM, trailing whitespace)
+    NonCanonical { filename: String, drift: Vec<Drift> },
+    /// Entry is not valid UTF-8
+    InvalidUtf8(String),
     /// Missing Previous: line in entry
     MissingPreviousLine(String),
-    /// I/O error
-    IoError(io::Error),
+    /// Sequence numbers skip a value (e.g. a deleted middle entry)
+    SequenceGap {
+        filename: String,
+        expected: u32,
+        found: u32,
+    },
+    /// More than one entry claims the same sequence number
+    DuplicateSequence {
+        sequence: u32,
+        filenames: Vec<String>,
+    },
+    /// A file in worklog/ that is neither SUMMARY.md nor a well-formed entry
+    UnexpectedFile(String),
+    /// Date: line missing or not strict RFC 3339
+    InvalidDate { filename: String, error: DateError },
+    /// Entry is dated before the entry it follows
+    DateNotMonotonic {
+        filename: String,
+        date: DateTime<Utc>,
+        previous: DateTime<Utc>,
+    },
+    /// Entry is dated further in the future than clock skew explains
+    DateInFuture {
+        filename: String,
+        date: DateTime<Utc>,
+        now: DateTime<Utc>,
+    },
+    /// A `Refs:` entry hash does not match any earlier entry
+    UnresolvedRef { filename: String, reference: String },
 }
 
 impl std::fmt::Display for VerifyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            VerifyError::NotInitialized => {
-                write!(f, "Engram not initialized. Run `engram init` first.")
-            }
             VerifyError::ChainBroken {
                 filename,
                 expected,
@@ -74,93 +122,164 @@ impl std::fmt::Display for VerifyError {
                     filename, content_hash, filename_hash
                 )
             }
+            VerifyError::NonCanonical { filename, drift } => {
+                write!(
+                    f,
+                    "Entry {} differs from its recorded hash only by {}",
+                    filename,
+                    describe(drift)
+                )
+            }
+            VerifyError::InvalidUtf8(filename) => {
+                write!(f, "Entry {} is not valid UTF-8", filename)
+            }
             VerifyError::MissingPreviousLine(filename) => {
                 write!(f, "Missing 'Previous:' line in {}", filename)
             }
-            VerifyError::IoError(e) => write!(f, "I/O error: {}", e),
+            VerifyError::SequenceGap {
+                filename,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Sequence gap at {}: expected {:06}, found {:06}",
+                    filename, expected, found
+                )
+            }
+            VerifyError::DuplicateSequence {
+                sequence,
+                filenames,
+            } => {
+                write!(
+                    f,
+                    "Duplicate sequence {:06}: {}",
+                    sequence,
+                    filenames.join(", ")
+                )
+            }
+            VerifyError::UnexpectedFile(filename) => {
+                write!(f, "Unexpected file in worklog/: {}", filename)
+            }
+            VerifyError::InvalidDate { filename, error } => {
+                write!(f, "Invalid date in {}: {}", filename, error)
+            }
+            VerifyError::DateNotMonotonic {
+                filename,
+                date,
+                previous,
+            } => {
+                write!(
+                    f,
+                    "Date goes backwards at {}: {} is before previous entry's {}",
+                    filename,
+                    date.format(DATE_FORMAT),
+                    previous.format(DATE_FORMAT)
+                )
+            }
+            VerifyError::DateInFuture {
+                filename,
+                date,
+                now,
+            } => {
+                write!(
+                    f,
+                    "Date in the future at {}: {} (now {})",
+                    filename,
+                    date.format(DATE_FORMAT),
+                    now.format(DATE_FORMAT)
+                )
+            }
+            VerifyError::UnresolvedRef {
+                filename,
+                reference,
+            } => {
+                write!(
+                    f,
+                    "Unresolved reference in {}: no earlier entry has hash {}",
+                    filename, reference
+                )
+            }
         }
     }
 }
 
-impl std::error::Error for VerifyError {}
-
-impl From<io::Error> for VerifyError {
-    fn from(error: io::Error) -> Self {
-        VerifyError::IoError(error)
+impl VerifyError {
+    /// Stable identifier for scripts and JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerifyError::ChainBroken { .. } => "E_CHAIN_BROKEN",
+            VerifyError::HashMismatch { .. } => "E_HASH_MISMATCH",
+            VerifyError::NonCanonical { .. } => "E_NON_CANONICAL",
+            VerifyError::InvalidUtf8(_) => "E_ENTRY_INVALID_UTF8",
+            VerifyError::MissingPreviousLine(_) => "E_ENTRY_MISSING_PREVIOUS",
+            VerifyError::SequenceGap { .. } => "E_SEQUENCE_GAP",
+            VerifyError::DuplicateSequence { .. } => "E_DUPLICATE_SEQUENCE",
+            VerifyError::UnexpectedFile(_) => "E_UNEXPECTED_FILE",
+            VerifyError::InvalidDate { .. } => "E_INVALID_DATE",
+            VerifyError::DateNotMonotonic { .. } => "E_DATE_NOT_MONOTONIC",
+            VerifyError::DateInFuture { .. } => "E_DATE_IN_FUTURE",
+            VerifyError::UnresolvedRef { .. } => "E_REF_UNRESOLVED",
+        }
     }
-}
 
-pub fn run() -> io::Result<()> {
-    match verify_chain() {
-        Ok(result) => {
-            println!("✓ Chain verified: {} entries", result.entry_count);
-            if let Some((first_file, first_date)) = result.first_entry {
-                println!("  First: {} ({})", first_file, first_date);
-            }
-            if let Some((latest_file, latest_date)) = result.latest_entry {
-                println!("  Latest: {} ({})", latest_file, latest_date);
+    /// The worklog file the failure was found at
+    pub fn filename(&self) -> &str {
+        match self {
+            VerifyError::ChainBroken { filename, .. }
+            | VerifyError::HashMismatch { filename, .. }
+            | VerifyError::NonCanonical { filename, .. }
+            | VerifyError::InvalidUtf8(filename)
+            | VerifyError::MissingPreviousLine(filename)
+            | VerifyError::SequenceGap { filename, .. }
+            | VerifyError::UnexpectedFile(filename)
+            | VerifyError::InvalidDate { filename, .. }
+            | VerifyError::DateNotMonotonic { filename, .. }
+            | VerifyError::DateInFuture { filename, .. }
+            | VerifyError::UnresolvedRef { filename, .. } => filename,
+            VerifyError::DuplicateSequence { filenames, .. } => {
+                filenames.first().map_or("", String::as_str)
             }
-            process::exit(EXIT_SUCCESS);
-        }
-        Err(VerifyError::NotInitialized) => {
-            eprintln!("Engram not initialized. Run `engram init` first.");
-            process::exit(EXIT_NOT_INITIALIZED);
-        }
-        Err(VerifyError::ChainBroken {
-            filename,
-            expected,
-            found,
-        }) => {
-            eprintln!("✗ Chain broken at entry {}", filename);
-            eprintln!();
-            eprintln!("Expected Previous: {}", expected);
-            eprintln!("Found Previous:    {}", found);
-            eprintln!();
-            eprintln!("The worklog has been tampered with or corrupted.");
-            process::exit(EXIT_CHAIN_BROKEN);
-        }
-        Err(VerifyError::HashMismatch {
-            filename,
-            content_hash,
-            filename_hash,
-        }) => {
-            eprintln!("✗ Hash mismatch at {}", filename);
-            eprintln!();
-            eprintln!("Content hashes to: {}", content_hash);
-            eprintln!("Filename claims:   {}", filename_hash);
-            eprintln!();
-            eprintln!("The worklog has been tampered with or corrupted.");
-            process::exit(EXIT_CHAIN_BROKEN);
-        }
-        Err(VerifyError::MissingPreviousLine(filename)) => {
-            eprintln!("✗ Invalid entry: Missing 'Previous:' line in {}", filename);
-            process::exit(EXIT_CHAIN_BROKEN);
-        }
-        Err(VerifyError::IoError(e)) => {
-            eprintln!("Error: {}", e);
-            process::exit(EXIT_CHAIN_BROKEN);
         }
     }
 }
 
+impl std::error::Error for VerifyError {}
+
 /// Internal verification logic that can be tested
-pub fn verify_chain() -> Result<VerifyResult, VerifyError> {
-    verify_chain_in_dir(Path::new("."))
+pub fn verify_chain() -> Result<VerifyResult, EngramError> {
+    verify_chain_in_dir(Path::new("."), &SystemClock)
 }
 
-/// Verification logic with configurable base directory for testing
-pub fn verify_chain_in_dir(base_dir: &Path) -> Result<VerifyResult, VerifyError> {
+/// Verification logic with configurable base directory for testing; `clock`
+/// bounds how far in the future an entry may be dated
+pub fn verify_chain_in_dir(
+    base_dir: &Path,
+    clock: &dyn Clock,
+) -> Result<VerifyResult, EngramError> {
     let engram_dir = base_dir.join(ENGRAM_DIR);
     let worklog_dir = base_dir.join(WORKLOG_DIR);
 
     // 1. Validate environment
     if !engram_dir.exists() || !worklog_dir.exists() {
-        return Err(VerifyError::NotInitialized);
+        return Err(EngramError::NotInitialized);
     }
 
-    // 2. List and sort entries by sequence number
-    let mut entries = collect_entries(&worklog_dir)?;
+    // 2. Reject anything that is not SUMMARY.md or a well-formed entry
+    let scan = scan_worklog(&worklog_dir)?;
+    if let Some(filename) = scan.unexpected.into_iter().next() {
+        return Err(VerifyError::UnexpectedFile(filename).into());
+    }
+
+    verify_entries(scan.entries, clock.now())
+}
 
+/// Verify sequence numbering, hash linkage and dates of a set of entries.
+/// `now` bounds how far in the future an entry may be dated.
+pub(crate) fn verify_entries(
+    mut entries: Vec<WorklogEntry>,
+    now: DateTime<Utc>,
+) -> Result<VerifyResult, EngramError> {
     if entries.is_empty() {
         return Ok(VerifyResult {
             entry_count: 0,
@@ -169,19 +288,27 @@ pub fn verify_chain_in_dir(base_dir: &Path) -> Result<VerifyResult, VerifyError>
         });
     }
 
-    // Sort by sequence number ascending
-    entries.sort_by_key(|e| e.sequence);
+    // Sort by sequence number ascending (filename breaks ties deterministically)
+    entries.sort_by(|a, b| (a.sequence, &a.filename).cmp(&(b.sequence, &b.filename)));
+
+    // Sequences must be unique and contiguous from 1
+    check_sequences(&entries)?;
 
     // 3. Verify chain
     let mut expected_prev = "none".to_string();
+    let mut previous_date: Option<DateTime<Utc>> = None;
     let mut first_entry: Option<(String, String)> = None;
     let mut latest_entry: Option<(String, String)> = None;
+    let mut earlier_hashes: HashSet<String> = HashSet::new();
 
     for entry in &entries {
-        let content = fs::read_to_string(&entry.path)?;
+        // Hash the exact bytes on disk; decode only for header parsing
+        let bytes = fs::read(&entry.path)?;
+        let content = std::str::from_utf8(&bytes)
+            .map_err(|_| VerifyError::InvalidUtf8(entry.filename.clone()))?;
 
         // Extract embedded previous hash
-        let embedded_prev = parse_previous_hash(&content)
+        let embedded_prev = parse_previous_hash(content)
             .ok_or_else(|| VerifyError::MissingPreviousLine(entry.filename.clone()))?;
 
         // Check chain linkage
@@ -190,34 +317,79 @@ pub fn verify_chain_in_dir(base_dir: &Path) -> Result<VerifyResult, VerifyError>
                 filename: entry.filename.clone(),
                 expected: expected_prev,
                 found: embedded_prev,
-            });
+            }
+            .into());
         }
 
         // Check filename hash matches content hash
-        let content_hash = sha256_hex(&content);
-        let content_short_hash = sha256_short(&content);
+        let content_hash = sha256_hex(&bytes);
+        let content_short_hash = sha256_short(&bytes);
 
         if content_short_hash != entry.short_hash {
+            if let Some(drift) = diagnose_drift(&bytes, &entry.short_hash) {
+                return Err(VerifyError::NonCanonical {
+                    filename: entry.filename.clone(),
+                    drift,
+                }
+                .into());
+            }
             return Err(VerifyError::HashMismatch {
                 filename: entry.filename.clone(),
                 content_hash: content_short_hash,
                 filename_hash: entry.short_hash.clone(),
-            });
+            }
+            .into());
         }
 
-        // Track first entry info
-        if first_entry.is_none() {
-            let date = parse_date(&content).unwrap_or_else(|| "unknown".to_string());
-            let date_short = date.split('T').next().unwrap_or(&date).to_string();
-            first_entry = Some((entry.filename.clone(), date_short));
+        // Check the date is well-formed, not before its predecessor, and not in the future
+        let date = parse_date(content).map_err(|error| VerifyError::InvalidDate {
+            filename: entry.filename.clone(),
+            error,
+        })?;
+
+        if let Some(previous) = previous_date {
+            if date < previous {
+                return Err(VerifyError::DateNotMonotonic {
+                    filename: entry.filename.clone(),
+                    date,
+                    previous,
+                }
+                .into());
+            }
         }
 
-        // Track latest entry info
-        let date = parse_date(&content).unwrap_or_else(|| "unknown".to_string());
-        let date_short = date.split('T').next().unwrap_or(&date).to_string();
+        if date > now + Duration::minutes(FUTURE_TOLERANCE_MINUTES) {
+            return Err(VerifyError::DateInFuture {
+                filename: entry.filename.clone(),
+                date,
+                now,
+            }
+            .into());
+        }
+        previous_date = Some(date);
+
+        // Entry references must point back along the chain
+        for reference in parse_refs(content) {
+            if let Reference::Entry(hash) = reference {
+                if !earlier_hashes.contains(&hash) {
+                    return Err(VerifyError::UnresolvedRef {
+                        filename: entry.filename.clone(),
+                        reference: hash,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        // Track first and latest entry info
+        let date_short = date.format("%Y-%m-%d").to_string();
+        if first_entry.is_none() {
+            first_entry = Some((entry.filename.clone(), date_short.clone()));
+        }
         latest_entry = Some((entry.filename.clone(), date_short));
 
         // Update expected_prev for next iteration (full 64-char hash)
+        earlier_hashes.insert(content_hash.clone());
         expected_prev = content_hash;
     }
 
@@ -228,8 +400,69 @@ pub fn verify_chain_in_dir(base_dir: &Path) -> Result<VerifyResult, VerifyError>
     })
 }
 
+/// Check that sequence numbers are unique and run 1, 2, 3, ... without gaps.
+/// Expects entries sorted by sequence.
+fn check_sequences(entries: &[WorklogEntry]) -> Result<(), VerifyError> {
+    for (index, pair) in entries.windows(2).enumerate() {
+        if pair[0].sequence == pair[1].sequence {
+            let sequence = pair[0].sequence;
+            let filenames = entries[index..]
+                .iter()
+                .take_while(|e| e.sequence == sequence)
+                .map(|e| e.filename.clone())
+                .collect();
+            return Err(VerifyError::DuplicateSequence {
+                sequence,
+                filenames,
+            });
+        }
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let expected = index as u32 + 1;
+        if entry.sequence != expected {
+            return Err(VerifyError::SequenceGap {
+                filename: entry.filename.clone(),
+                expected,
+                found: entry.sequence,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Contents of the worklog directory, split into entries and everything else
+pub(crate) struct WorklogScan {
+    pub entries: Vec<WorklogEntry>,
+    /// Names of files that are neither SUMMARY.md nor entries, sorted
+    pub unexpected: Vec<String>,
+}
+
+/// List the worklog directory, separating entries from unexpected files
+pub(crate) fn scan_worklog(history_path: &Path) -> io::Result<WorklogScan> {
+    let mut entries = Vec::new();
+    let mut unexpected = Vec::new();
+
+    for dir_entry in fs::read_dir(history_path)? {
+        let filename = dir_entry?.file_name().to_string_lossy().to_string();
+
+        if let Some(entry) = WorklogEntry::from_filename(&filename, history_path) {
+            entries.push(entry);
+        } else if filename != "SUMMARY.md" {
+            unexpected.push(filename);
+        }
+    }
+
+    unexpected.sort();
+    Ok(WorklogScan {
+        entries,
+        unexpected,
+    })
+}
+
 /// Collect all valid worklog entries from the worklog directory
-fn collect_entries(history_path: &Path) -> io::Result<Vec<WorklogEntry>> {
+pub(crate) fn collect_entries(history_path: &Path) -> io::Result<Vec<WorklogEntry>> {
     let mut entries = Vec::new();
 
     for dir_entry in fs::read_dir(history_path)? {
@@ -249,6 +482,7 @@ fn collect_entries(history_path: &Path) -> io::Result<Vec<WorklogEntry>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::clock::FixedClock;
     use std::fs;
     use tempfile::tempdir;
 
@@ -303,8 +537,8 @@ mod tests {
         let dir = tempdir().unwrap();
         // Don't create .engram directory
 
-        let result = verify_chain_in_dir(dir.path());
-        assert!(matches!(result, Err(VerifyError::NotInitialized)));
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(result, Err(EngramError::NotInitialized)));
     }
 
     #[test]
@@ -313,8 +547,8 @@ mod tests {
         // Create .engram but not history
         fs::create_dir(dir.path().join(".engram")).unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
-        assert!(matches!(result, Err(VerifyError::NotInitialized)));
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(result, Err(EngramError::NotInitialized)));
     }
 
     #[test]
@@ -322,7 +556,7 @@ mod tests {
         let dir = tempdir().unwrap();
         setup_engram_dir(dir.path());
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_ok());
 
         let verify_result = result.unwrap();
@@ -342,7 +576,7 @@ mod tests {
         let filename = format!("000001_{}.md", short_hash);
         fs::write(dir.path().join(".engram/worklog").join(&filename), content).unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_ok());
 
         let verify_result = result.unwrap();
@@ -383,7 +617,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_ok());
 
         let verify_result = result.unwrap();
@@ -422,15 +656,15 @@ mod tests {
         )
         .unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_err());
 
         match result {
-            Err(VerifyError::ChainBroken {
+            Err(EngramError::Chain(VerifyError::ChainBroken {
                 filename,
                 expected,
                 found,
-            }) => {
+            })) => {
                 assert_eq!(filename, filename2);
                 assert_eq!(expected, sha256_hex(content1));
                 assert_eq!(found, wrong_prev);
@@ -450,13 +684,13 @@ mod tests {
         let filename = format!("000001_{}.md", short_hash);
         fs::write(dir.path().join(".engram/worklog").join(&filename), content).unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_err());
 
         match result {
-            Err(VerifyError::ChainBroken {
+            Err(EngramError::Chain(VerifyError::ChainBroken {
                 expected, found, ..
-            }) => {
+            })) => {
                 assert_eq!(expected, "none");
                 assert_eq!(
                     found,
@@ -480,15 +714,15 @@ mod tests {
         let filename = format!("000001_{}.md", wrong_hash);
         fs::write(dir.path().join(".engram/worklog").join(&filename), content).unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_err());
 
         match result {
-            Err(VerifyError::HashMismatch {
+            Err(EngramError::Chain(VerifyError::HashMismatch {
                 filename: f,
                 content_hash,
                 filename_hash,
-            }) => {
+            })) => {
                 assert_eq!(f, filename);
                 assert_eq!(content_hash, sha256_short(content));
                 assert_eq!(filename_hash, wrong_hash);
@@ -508,17 +742,295 @@ mod tests {
         let filename = format!("000001_{}.md", short_hash);
         fs::write(dir.path().join(".engram/worklog").join(&filename), content).unwrap();
 
-        let result = verify_chain_in_dir(dir.path());
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
         assert!(result.is_err());
 
         match result {
-            Err(VerifyError::MissingPreviousLine(f)) => {
+            Err(EngramError::Chain(VerifyError::MissingPreviousLine(f))) => {
                 assert_eq!(f, filename);
             }
             _ => panic!("Expected MissingPreviousLine error"),
         }
     }
 
+    #[test]
+    fn test_verify_reports_crlf_drift() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        // Filename records the LF hash, but the file on disk was converted to CRLF
+        let content =
+            "Summary: First entry\nPrevious: none\nDate: 2025-06-12T14:32:07Z\n\n---\n\nBody";
+        let filename = format!("000001_{}.md", sha256_short(content));
+        fs::write(
+            dir.path().join(".engram/worklog").join(&filename),
+            content.replace('\n', "\r\n"),
+        )
+        .unwrap();
+
+        match verify_chain_in_dir(dir.path(), &SystemClock) {
+            Err(EngramError::Chain(VerifyError::NonCanonical { filename: f, drift })) => {
+                assert_eq!(f, filename);
+                assert_eq!(drift, vec![Drift::Crlf]);
+            }
+            other => panic!("Expected NonCanonical error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_invalid_utf8() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        let content: &[u8] = b"Summary: \xff\nPrevious: none\n";
+        let filename = format!("000001_{}.md", sha256_short(content));
+        fs::write(dir.path().join(".engram/worklog").join(&filename), content).unwrap();
+
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
+        assert!(
+            matches!(result, Err(EngramError::Chain(VerifyError::InvalidUtf8(f))) if f == filename)
+        );
+    }
+
+    /// Write an entry with a correct filename hash and return its content
+    fn write_linked_entry(base: &Path, sequence: u32, previous: &str) -> String {
+        let content = format!(
+            "Summary: Entry {}\nPrevious: {}\nDate: 2025-06-12T14:32:07Z\n\n---\n\nBody",
+            sequence, previous
+        );
+        let filename = format!("{:06}_{}.md", sequence, sha256_short(&content));
+        fs::write(base.join(".engram/worklog").join(filename), &content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_verify_detects_sequence_gap() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        // Entry 2 was deleted; entry 3 still links to it
+        let first = write_linked_entry(dir.path(), 1, "none");
+        let second = format!(
+            "Summary: Entry 2\nPrevious: {}\nDate: 2025-06-12T14:32:07Z\n\n---\n\nBody",
+            sha256_hex(&first)
+        );
+        write_linked_entry(dir.path(), 3, &sha256_hex(&second));
+
+        match verify_chain_in_dir(dir.path(), &SystemClock) {
+            Err(EngramError::Chain(VerifyError::SequenceGap {
+                filename,
+                expected,
+                found,
+            })) => {
+                assert!(filename.starts_with("000003_"));
+                assert_eq!(expected, 2);
+                assert_eq!(found, 3);
+            }
+            other => panic!("Expected SequenceGap error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_sequence_not_starting_at_one() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        write_linked_entry(dir.path(), 2, "none");
+
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(
+            result,
+            Err(EngramError::Chain(VerifyError::SequenceGap {
+                expected: 1,
+                found: 2,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_verify_detects_duplicate_sequence() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        write_linked_entry(dir.path(), 1, "none");
+        fs::write(
+            dir.path().join(".engram/worklog/000001_deadbeef.md"),
+            "Summary: Other\nPrevious: none\n",
+        )
+        .unwrap();
+
+        match verify_chain_in_dir(dir.path(), &SystemClock) {
+            Err(EngramError::Chain(VerifyError::DuplicateSequence {
+                sequence,
+                filenames,
+            })) => {
+                assert_eq!(sequence, 1);
+                assert_eq!(filenames.len(), 2);
+                assert!(filenames.contains(&"000001_deadbeef.md".to_string()));
+            }
+            other => panic!("Expected DuplicateSequence error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_unexpected_file() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        write_linked_entry(dir.path(), 1, "none");
+        fs::write(
+            dir.path().join(".engram/worklog/000003_deadbeef.md.bak"),
+            "content",
+        )
+        .unwrap();
+
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
+        assert!(
+            matches!(result, Err(EngramError::Chain(VerifyError::UnexpectedFile(f))) if f == "000003_deadbeef.md.bak")
+        );
+    }
+
+    #[test]
+    fn test_scan_worklog_allows_summary() {
+        let dir = tempdir().unwrap();
+        let history_path = dir.path().join("history");
+        fs::create_dir(&history_path).unwrap();
+        fs::write(history_path.join("SUMMARY.md"), "summary").unwrap();
+        fs::write(history_path.join("000001_a1b2c3d4.md"), "content").unwrap();
+        fs::write(history_path.join("notes.txt"), "notes").unwrap();
+
+        let scan = scan_worklog(&history_path).unwrap();
+        assert_eq!(scan.entries.len(), 1);
+        assert_eq!(scan.unexpected, vec!["notes.txt".to_string()]);
+    }
+
+    /// Write an entry with the given date and a correct filename hash
+    fn write_dated_entry(base: &Path, sequence: u32, previous: &str, date: &str) -> String {
+        let content = format!(
+            "Summary: Entry {}\nPrevious: {}\nDate: {}\n\n---\n\nBody",
+            sequence, previous, date
+        );
+        let filename = format!("{:06}_{}.md", sequence, sha256_short(&content));
+        fs::write(base.join(".engram/worklog").join(filename), &content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_verify_detects_backwards_date() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        let first = write_dated_entry(dir.path(), 1, "none", "2025-06-13T10:00:00Z");
+        write_dated_entry(dir.path(), 2, &sha256_hex(&first), "2025-06-12T10:00:00Z");
+
+        match verify_chain_in_dir(dir.path(), &SystemClock) {
+            Err(EngramError::Chain(VerifyError::DateNotMonotonic {
+                filename,
+                date,
+                previous,
+            })) => {
+                assert!(filename.starts_with("000002_"));
+                assert!(date < previous);
+            }
+            other => panic!("Expected DateNotMonotonic error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_allows_equal_dates() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        let first = write_dated_entry(dir.path(), 1, "none", "2025-06-12T10:00:00Z");
+        write_dated_entry(dir.path(), 2, &sha256_hex(&first), "2025-06-12T10:00:00Z");
+
+        assert!(verify_chain_in_dir(dir.path(), &SystemClock).is_ok());
+    }
+
+    #[test]
+    fn test_verify_detects_future_date() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        write_dated_entry(dir.path(), 1, "none", "2025-06-12T10:10:00Z");
+
+        let now = DateTime::parse_from_rfc3339("2025-06-12T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let result = verify_chain_in_dir(dir.path(), &FixedClock(now));
+        assert!(matches!(
+            result,
+            Err(EngramError::Chain(VerifyError::DateInFuture { .. }))
+        ));
+
+        // Within tolerance of clock skew
+        let result = verify_chain_in_dir(dir.path(), &FixedClock(now + Duration::minutes(6)));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_verify_rejects_non_rfc3339_date() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+        write_dated_entry(dir.path(), 1, "none", "June 12, 2025");
+
+        let result = verify_chain_in_dir(dir.path(), &SystemClock);
+        assert!(matches!(
+            result,
+            Err(EngramError::Chain(VerifyError::InvalidDate {
+                error: DateError::Invalid(_),
+                ..
+            }))
+        ));
+    }
+
+    /// Write an entry with a Refs: header and a correct filename hash
+    fn write_referencing_entry(base: &Path, sequence: u32, previous: &str, refs: &str) -> String {
+        let content = format!(
+            "Summary: Entry {}\nPrevious: {}\nDate: 2025-06-12T14:32:07Z\nRefs: {}\n\n---\n\nBody",
+            sequence, previous, refs
+        );
+        let filename = format!("{:06}_{}.md", sequence, sha256_short(&content));
+        fs::write(base.join(".engram/worklog").join(filename), &content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_verify_accepts_refs_to_earlier_entries() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        let first = write_linked_entry(dir.path(), 1, "none");
+        let refs = format!("{}, #123, JIRA-45", sha256_hex(&first));
+        write_referencing_entry(dir.path(), 2, &sha256_hex(&first), &refs);
+
+        assert_eq!(
+            verify_chain_in_dir(dir.path(), &SystemClock)
+                .unwrap()
+                .entry_count,
+            2
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_unresolved_ref() {
+        let dir = tempdir().unwrap();
+        setup_engram_dir(dir.path());
+
+        let first = write_linked_entry(dir.path(), 1, "none");
+        let dangling = "d".repeat(64);
+        write_referencing_entry(dir.path(), 2, &sha256_hex(&first), &dangling);
+
+        match verify_chain_in_dir(dir.path(), &SystemClock) {
+            Err(EngramError::Chain(VerifyError::UnresolvedRef {
+                filename,
+                reference,
+            })) => {
+                assert!(filename.starts_with("000002_"));
+                assert_eq!(reference, dangling);
+            }
+            other => panic!("Expected UnresolvedRef error, got {:?}", other),
+        }
+    }
+
     /// Helper to set up a valid .engram directory structure for testing
     fn setup_engram_dir(base: &std::path::Path) {
         fs::create_dir(base.join(".engram")).unwrap();
@@ -0,0 +1,591 @@
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::doctor::wrapper_version;
+use crate::commands::init::render_wrapper;
+use crate::error::EngramError;
+use crate::templates::{
+    WRAPPER_CMD_LOCAL_TEMPLATE, WRAPPER_CMD_MIRROR_TEMPLATE, WRAPPER_CMD_PATH_TEMPLATE,
+    WRAPPER_CMD_TEMPLATE, WRAPPER_SH_LOCAL_TEMPLATE, WRAPPER_SH_MIRROR_TEMPLATE,
+    WRAPPER_SH_PATH_TEMPLATE, WRAPPER_SH_TEMPLATE,
+};
+
+/// Release asset of the Windows wrapper; every other asset belongs to `./engram`
+const WINDOWS_ASSET: &str = "engram-windows-x86_64.exe";
+/// Lines between these markers (after `#` or `rem`) are local additions that
+/// survive regenerating the wrapper
+const CUSTOM_BEGIN: &str = "engram:custom:begin";
+const CUSTOM_END: &str = "engram:custom:end";
+/// Characters that would break out of the quoting the mirror gets in
+/// `./engram` (`"${ENGRAM_MIRROR:-…}"`) or `engram.cmd` (`set ENGRAM_MIRROR=…`)
+const MIRROR_FORBIDDEN: &[char] = &['"', '$', '`', '}', '%', '&', '^', '<', '>', '|'];
+
+/// Where the generated wrappers get the Engram binary from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WrapperMode {
+    /// Download from GitHub releases, checked against the release's checksums.txt
+    #[default]
+    Github,
+    /// Run the binary `engram vendor` put in `.engram/bin`; never download
+    Local,
+    /// Download from `ENGRAM_MIRROR`, checked against SHA-256s pinned in the wrapper
+    Mirror,
+    /// Run `engram` from PATH; never download
+    Path,
+}
+
+impl WrapperMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WrapperMode::Github => "github",
+            WrapperMode::Local => "local",
+            WrapperMode::Mirror => "mirror",
+            WrapperMode::Path => "path",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        [
+            WrapperMode::Github,
+            WrapperMode::Local,
+            WrapperMode::Mirror,
+            WrapperMode::Path,
+        ]
+        .into_iter()
+        .find(|mode| mode.as_str() == name)
+    }
+}
+
+/// The two wrapper scripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapperKind {
+    /// `./engram` for Linux and macOS
+    Sh,
+    /// `engram.cmd` for Windows
+    Cmd,
+}
+
+impl WrapperKind {
+    pub const ALL: [WrapperKind; 2] = [WrapperKind::Sh, WrapperKind::Cmd];
+
+    /// File name relative to the project root
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            WrapperKind::Sh => "engram",
+            WrapperKind::Cmd => "engram.cmd",
+        }
+    }
+
+    fn template(&self, mode: WrapperMode) -> &'static str {
+        match (self, mode) {
+            (WrapperKind::Sh, WrapperMode::Github) => WRAPPER_SH_TEMPLATE,
+            (WrapperKind::Sh, WrapperMode::Local) => WRAPPER_SH_LOCAL_TEMPLATE,
+            (WrapperKind::Sh, WrapperMode::Mirror) => WRAPPER_SH_MIRROR_TEMPLATE,
+            (WrapperKind::Sh, WrapperMode::Path) => WRAPPER_SH_PATH_TEMPLATE,
+            (WrapperKind::Cmd, WrapperMode::Github) => WRAPPER_CMD_TEMPLATE,
+            (WrapperKind::Cmd, WrapperMode::Local) => WRAPPER_CMD_LOCAL_TEMPLATE,
+            (WrapperKind::Cmd, WrapperMode::Mirror) => WRAPPER_CMD_MIRROR_TEMPLATE,
+            (WrapperKind::Cmd, WrapperMode::Path) => WRAPPER_CMD_PATH_TEMPLATE,
+        }
+    }
+
+    /// The line after which custom blocks go when their anchor is gone
+    fn default_anchor(&self) -> &'static str {
+        match self {
+            WrapperKind::Sh => "cd \"$ROOT_DIR\"",
+            WrapperKind::Cmd => "cd /d \"%ROOT_DIR%\"",
+        }
+    }
+
+    /// True for the release assets this wrapper can run
+    fn runs(&self, asset: &str) -> bool {
+        (asset == WINDOWS_ASSET) == (*self == WrapperKind::Cmd)
+    }
+}
+
+/// Everything besides the version that goes into a generated wrapper
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WrapperSpec {
+    pub mode: WrapperMode,
+    /// Default for `ENGRAM_MIRROR` (mirror mode)
+    pub mirror: Option<String>,
+    /// (asset, SHA-256) pairs pinned in the wrapper (mirror mode)
+    pub checksums: Vec<(String, String)>,
+}
+
+impl WrapperSpec {
+    /// Build the spec for `init --wrapper-mode`. Mirror mode pins the
+    /// checksums of `version` from `checksums` or, when the mirror is a
+    /// local directory, from its `v<version>/checksums.txt`.
+    pub fn new(
+        mode: WrapperMode,
+        mirror: Option<String>,
+        checksums: Option<PathBuf>,
+        version: &str,
+    ) -> Result<Self, EngramError> {
+        let mirror = mirror.map(|mirror| mirror.trim_end_matches('/').to_string());
+        if let Some(mirror) = &mirror {
+            if mirror
+                .chars()
+                .any(|c| MIRROR_FORBIDDEN.contains(&c) || c.is_control())
+            {
+                return Err(EngramError::Config(format!(
+                    "--mirror cannot contain control characters or any of {}; they would be interpreted by the wrapper scripts",
+                    MIRROR_FORBIDDEN.iter().collect::<String>()
+                )));
+            }
+        }
+        if mode != WrapperMode::Mirror {
+            if mirror.is_some() || checksums.is_some() {
+                return Err(EngramError::Config(
+                    "--mirror and --checksums only apply to --wrapper-mode mirror".to_string(),
+                ));
+            }
+            return Ok(WrapperSpec {
+                mode,
+                ..WrapperSpec::default()
+            });
+        }
+
+        let checksums_path = match (checksums, mirror.as_deref().and_then(local_dir)) {
+            (Some(path), _) => path,
+            (None, Some(dir)) => dir.join(format!("v{}", version)).join("checksums.txt"),
+            (None, None) => {
+                return Err(EngramError::Config(
+                    "mirror mode pins the release checksums in the wrappers; pass --checksums <FILE> or a file:// --mirror".to_string(),
+                ))
+            }
+        };
+        let content = fs::read_to_string(&checksums_path).map_err(|err| {
+            EngramError::Config(format!(
+                "cannot read checksums from {}: {}",
+                checksums_path.display(),
+                err
+            ))
+        })?;
+        let checksums: Vec<_> = parse_checksums(&content)
+            .into_iter()
+            .filter(|(asset, _)| is_engram_asset(asset))
+            .collect();
+        if checksums.is_empty() {
+            return Err(EngramError::Config(format!(
+                "{} lists no engram-* release assets",
+                checksums_path.display()
+            )));
+        }
+
+        Ok(WrapperSpec {
+            mode,
+            mirror,
+            checksums,
+        })
+    }
+
+    /// The spec the wrappers under `root` were generated with, read from
+    /// `./engram` (or `engram.cmd`); the default when neither parses
+    pub(crate) fn installed(root: &Path) -> Self {
+        WrapperKind::ALL
+            .iter()
+            .find_map(|kind| {
+                let content = fs::read_to_string(root.join(kind.file_name())).ok()?;
+                read_wrapper(*kind, &content).map(|wrapper| wrapper.spec)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Render `kind` pinned to `version`
+    pub(crate) fn render(&self, kind: WrapperKind, version: &str) -> String {
+        let mut content = render_wrapper(kind.template(self.mode), version);
+        if self.mode == WrapperMode::Mirror {
+            let pins = self.checksums.iter().filter(|(asset, _)| kind.runs(asset));
+            let pins = match kind {
+                WrapperKind::Sh => pins
+                    .map(|(asset, sha)| format!("    {}) EXPECTED=\"{}\" ;;\n", asset, sha))
+                    .collect(),
+                WrapperKind::Cmd => pins.map(|(_, sha)| sha.clone()).collect::<String>(),
+            };
+            content = content
+                .replace(
+                    "__ENGRAM_MIRROR__",
+                    self.mirror.as_deref().unwrap_or_default(),
+                )
+                .replace("__ENGRAM_CHECKSUMS__", &pins)
+                .replace("__ENGRAM_CHECKSUM__", &pins);
+        }
+        content
+    }
+
+    /// Read the spec back out of a generated wrapper
+    fn parse(kind: WrapperKind, content: &str) -> Option<Self> {
+        let mode_re = Regex::new(r"(?m)^(?:#|rem) Wrapper mode: ([a-z]+)$").unwrap();
+        let mode = match mode_re.captures(content) {
+            Some(caps) => WrapperMode::parse(&caps[1])?,
+            None => WrapperMode::Github,
+        };
+        if mode != WrapperMode::Mirror {
+            return Some(WrapperSpec {
+                mode,
+                ..WrapperSpec::default()
+            });
+        }
+
+        let (mirror_re, pin_re) = match kind {
+            WrapperKind::Sh => (
+                r#"(?m)^ENGRAM_MIRROR="\$\{ENGRAM_MIRROR:-(.*)\}"$"#,
+                r#"(?m)^    (engram-[A-Za-z0-9_.-]+)\) EXPECTED="([0-9a-f]{64})" ;;$"#,
+            ),
+            WrapperKind::Cmd => (
+                r#"(?m)^if "%ENGRAM_MIRROR%"=="" set ENGRAM_MIRROR=(.*)$"#,
+                r"(?m)^set EXPECTED=()([0-9a-f]{64})$",
+            ),
+        };
+        let mirror = Regex::new(mirror_re)
+            .unwrap()
+            .captures(content)
+            .map(|caps| caps[1].to_string())
+            .filter(|mirror| !mirror.is_empty());
+        let checksums = Regex::new(pin_re)
+            .unwrap()
+            .captures_iter(content)
+            .map(|caps| {
+                let asset = match kind {
+                    WrapperKind::Sh => caps[1].to_string(),
+                    WrapperKind::Cmd => WINDOWS_ASSET.to_string(),
+                };
+                (asset, caps[2].to_string())
+            })
+            .collect();
+        Some(WrapperSpec {
+            mode,
+            mirror,
+            checksums,
+        })
+    }
+
+    /// True when the wrapper can be re-pinned to another release without
+    /// new information; mirror wrappers need that release's checksums
+    pub(crate) fn can_repin(&self) -> bool {
+        self.mode != WrapperMode::Mirror
+    }
+}
+
+/// The spec and pinned version of `content` when it is exactly what
+/// `init` generates, or None when it was edited by hand
+pub(crate) fn generated_wrapper(kind: WrapperKind, content: &str) -> Option<(WrapperSpec, String)> {
+    let version = wrapper_version(content)?;
+    let spec = WrapperSpec::parse(kind, content)?;
+    (spec.render(kind, &version) == content).then_some((spec, version))
+}
+
+/// A block of lines between custom markers and the line it followed
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CustomBlock {
+    anchor: Option<String>,
+    lines: Vec<String>,
+}
+
+/// A generated wrapper, possibly with marked local additions
+#[derive(Debug, Clone)]
+pub(crate) struct Wrapper {
+    pub spec: WrapperSpec,
+    /// Version the wrapper pins
+    pub version: String,
+    custom: Vec<CustomBlock>,
+}
+
+impl Wrapper {
+    /// Regenerate the wrapper with `spec` and `version`, putting each custom
+    /// block back after the line it followed (or after the `cd` into the
+    /// project when that line is gone)
+    pub(crate) fn render(&self, kind: WrapperKind, spec: &WrapperSpec, version: &str) -> String {
+        let rendered = spec.render(kind, version);
+        let lines: Vec<&str> = rendered.lines().collect();
+        let position = |anchor: &str| lines.iter().position(|line| *line == anchor);
+        let default = position(kind.default_anchor()).unwrap_or(0);
+
+        let mut placed: Vec<(usize, &CustomBlock)> = self
+            .custom
+            .iter()
+            .map(|block| {
+                let index = block.anchor.as_deref().and_then(position);
+                (index.unwrap_or(default), block)
+            })
+            .collect();
+        placed.sort_by_key(|(index, _)| *index);
+
+        let mut content = String::new();
+        for (index, line) in lines.iter().enumerate() {
+            content.push_str(line);
+            content.push('\n');
+            for (_, block) in placed.iter().filter(|(at, _)| *at == index) {
+                for line in &block.lines {
+                    content.push_str(line);
+                    content.push('\n');
+                }
+            }
+        }
+        content
+    }
+}
+
+/// Read a wrapper that is exactly what `init` generates apart from blocks
+/// between `engram:custom:begin` and `engram:custom:end` comment lines.
+/// None when it was edited anywhere else.
+pub(crate) fn read_wrapper(kind: WrapperKind, content: &str) -> Option<Wrapper> {
+    let mut stripped = String::new();
+    let mut custom = Vec::new();
+    let mut open: Option<CustomBlock> = None;
+    let mut previous: Option<&str> = None;
+
+    for line in content.lines() {
+        let marker = line
+            .trim()
+            .trim_start_matches('#')
+            .trim_start_matches("rem")
+            .trim();
+        if let Some(block) = &mut open {
+            block.lines.push(line.to_string());
+            if marker == CUSTOM_END {
+                custom.extend(open.take());
+            }
+            continue;
+        }
+        if marker == CUSTOM_BEGIN {
+            open = Some(CustomBlock {
+                anchor: previous.map(str::to_string),
+                lines: vec![line.to_string()],
+            });
+            continue;
+        }
+        stripped.push_str(line);
+        stripped.push('\n');
+        previous = Some(line);
+    }
+    if open.is_some() {
+        return None;
+    }
+
+    let (spec, version) = generated_wrapper(kind, &stripped)?;
+    Some(Wrapper {
+        spec,
+        version,
+        custom,
+    })
+}
+
+/// (asset, SHA-256) pairs from a `sha256sum`-style checksums.txt
+pub fn parse_checksums(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (sha, asset) = line.trim().split_once(char::is_whitespace)?;
+            let asset = asset.trim_start().trim_start_matches('*');
+            let valid = sha.len() == 64 && sha.bytes().all(|byte| byte.is_ascii_hexdigit());
+            (valid && !asset.is_empty()).then(|| (asset.to_string(), sha.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// An `engram-*` release asset name made only of `[A-Za-z0-9_.-]`, so it can be
+/// pasted into the wrappers' `case` patterns as is
+fn is_engram_asset(asset: &str) -> bool {
+    asset.starts_with("engram-")
+        && asset
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.' | b'-'))
+}
+
+/// Release asset name of the running platform, as the wrappers compute it
+pub fn platform_asset() -> Option<String> {
+    let os = match std::env::consts::OS {
+        "linux" => "linux",
+        "macos" => "darwin",
+        "windows" => "windows",
+        _ => return None,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        _ => return None,
+    };
+    Some(format!(
+        "engram-{}-{}{}",
+        os,
+        arch,
+        std::env::consts::EXE_SUFFIX
+    ))
+}
+
+/// True for `MAJOR.MINOR.PATCH` with an optional `-pre`/`+build` suffix.
+/// Versions are written into shell and batch scripts, so nothing else passes.
+pub(crate) fn is_release_version(version: &str) -> bool {
+    Regex::new(r"^\d+\.\d+\.\d+(?:[-+][0-9A-Za-z.+-]+)?$")
+        .unwrap()
+        .is_match(version)
+}
+
+/// Directory of a `file://` URL or plain path mirror
+pub(crate) fn local_dir(mirror: &str) -> Option<PathBuf> {
+    if let Some(path) = mirror.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+    (!mirror.contains("://")).then(|| PathBuf::from(mirror))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SHA_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const SHA_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+    fn mirror_spec() -> WrapperSpec {
+        WrapperSpec {
+            mode: WrapperMode::Mirror,
+            mirror: Some("https://mirror.example/engram".to_string()),
+            checksums: vec![
+                ("engram-linux-x86_64".to_string(), SHA_A.to_string()),
+                (WINDOWS_ASSET.to_string(), SHA_B.to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse_checksums() {
+        let content = format!(
+            "{}  engram-linux-x86_64\n{} *engram-windows-x86_64.exe\nnot a line\n",
+            SHA_A, SHA_B
+        );
+        assert_eq!(
+            parse_checksums(&content),
+            vec![
+                ("engram-linux-x86_64".to_string(), SHA_A.to_string()),
+                (WINDOWS_ASSET.to_string(), SHA_B.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mirror_wrappers_pin_their_own_assets() {
+        let spec = mirror_spec();
+        let sh = spec.render(WrapperKind::Sh, "1.2.3");
+        assert!(sh.contains(&format!(
+            "    engram-linux-x86_64) EXPECTED=\"{}\" ;;\n",
+            SHA_A
+        )));
+        assert!(!sh.contains(SHA_B));
+        assert!(sh.contains("ENGRAM_MIRROR=\"${ENGRAM_MIRROR:-https://mirror.example/engram}\""));
+        assert!(!sh.contains("__ENGRAM"));
+
+        let cmd = spec.render(WrapperKind::Cmd, "1.2.3");
+        assert!(cmd.contains(&format!("set EXPECTED={}\n", SHA_B)));
+        assert!(!cmd.contains("__ENGRAM"));
+    }
+
+    #[test]
+    fn test_generated_wrappers_round_trip() {
+        for spec in [
+            WrapperSpec::default(),
+            WrapperSpec {
+                mode: WrapperMode::Local,
+                ..WrapperSpec::default()
+            },
+            WrapperSpec {
+                mode: WrapperMode::Path,
+                ..WrapperSpec::default()
+            },
+            mirror_spec(),
+        ] {
+            for kind in WrapperKind::ALL {
+                let content = spec.render(kind, "1.2.3");
+                let (parsed, version) = generated_wrapper(kind, &content).unwrap();
+                assert_eq!(version, "1.2.3");
+                assert_eq!(parsed.render(kind, "1.2.3"), content);
+                assert_eq!(parsed.mode, spec.mode);
+                assert!(generated_wrapper(kind, &format!("{}rem edited\n", content)).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_blocks_survive_regeneration() {
+        let spec = WrapperSpec::default();
+        let generated = spec.render(WrapperKind::Sh, "1.0.0");
+        let custom =
+            "# engram:custom:begin\nexport HTTPS_PROXY=http://proxy:3128\n# engram:custom:end\n";
+        let anchor = "ENGRAM_VERSION=\"1.0.0\"\n";
+        let customized = generated.replacen(anchor, &format!("{}{}", anchor, custom), 1);
+
+        let wrapper = read_wrapper(WrapperKind::Sh, &customized).unwrap();
+        assert_eq!(wrapper.version, "1.0.0");
+        assert_eq!(wrapper.render(WrapperKind::Sh, &spec, "1.0.0"), customized);
+
+        // The anchor line changes with the version, so the block moves after the `cd`
+        let repinned = wrapper.render(WrapperKind::Sh, &spec, "2.0.0");
+        assert!(repinned.contains(&format!("cd \"$ROOT_DIR\"\n{}", custom)));
+        assert!(repinned.contains("ENGRAM_VERSION=\"2.0.0\""));
+        assert!(read_wrapper(WrapperKind::Sh, &repinned).is_some());
+
+        // Edits outside the markers, or an unterminated block, are not regenerated
+        assert!(read_wrapper(WrapperKind::Sh, &format!("{}echo hi\n", generated)).is_none());
+        assert!(read_wrapper(
+            WrapperKind::Sh,
+            &format!("{}# engram:custom:begin\n", generated)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_mirror_spec_reads_checksums_from_local_mirror() {
+        let dir = tempdir().unwrap();
+        let release = dir.path().join("v1.2.3");
+        fs::create_dir_all(&release).unwrap();
+        fs::write(
+            release.join("checksums.txt"),
+            format!(
+                "{}  engram-linux-x86_64\n{}  SHA256SUMS.sig\n{}  engram-$(id)*\n",
+                SHA_A, SHA_B, SHA_B
+            ),
+        )
+        .unwrap();
+
+        let mirror = format!("file://{}/", dir.path().display());
+        let spec =
+            WrapperSpec::new(WrapperMode::Mirror, Some(mirror.clone()), None, "1.2.3").unwrap();
+        assert_eq!(spec.mirror.as_deref(), Some(mirror.trim_end_matches('/')));
+        assert_eq!(
+            spec.checksums,
+            vec![("engram-linux-x86_64".to_string(), SHA_A.to_string())]
+        );
+
+        // A remote mirror needs the checksums passed in
+        assert!(matches!(
+            WrapperSpec::new(
+                WrapperMode::Mirror,
+                Some("https://mirror.example".to_string()),
+                None,
+                "1.2.3"
+            ),
+            Err(EngramError::Config(_))
+        ));
+        assert!(matches!(
+            WrapperSpec::new(WrapperMode::Local, Some(mirror.clone()), None, "1.2.3"),
+            Err(EngramError::Config(_))
+        ));
+
+        // Anything the shell or cmd.exe would interpret is refused
+        for bad in ["?sig=a&b", "%TEMP%", "\"$(id)\"", "`id`", "a\nb", "x}"] {
+            assert!(matches!(
+                WrapperSpec::new(
+                    WrapperMode::Mirror,
+                    Some(format!("{}{}", mirror, bad)),
+                    None,
+                    "1.2.3"
+                ),
+                Err(EngramError::Config(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use crate::utils::hash::sha256_short;
+
+/// UTF-8 byte order mark
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// A way an entry's bytes can drift from the canonical form written by `commit`
+/// (LF line endings, no BOM, no trailing whitespace) without changing its text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drift {
+    Bom,
+    Crlf,
+    TrailingWhitespace,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::Bom => write!(f, "a UTF-8 BOM"),
+            Drift::Crlf => write!(f, "CRLF line endings"),
+            Drift::TrailingWhitespace => write!(f, "trailing whitespace"),
+        }
+    }
+}
+
+/// Candidate drift combinations, smallest first
+const CANDIDATES: &[&[Drift]] = &[
+    &[Drift::Crlf],
+    &[Drift::Bom],
+    &[Drift::TrailingWhitespace],
+    &[Drift::Bom, Drift::Crlf],
+    &[Drift::Crlf, Drift::TrailingWhitespace],
+    &[Drift::Bom, Drift::TrailingWhitespace],
+    &[Drift::Bom, Drift::Crlf, Drift::TrailingWhitespace],
+];
+
+/// Strip a leading UTF-8 BOM and convert CRLF to LF.
+/// Used on draft input so committed entries are always canonical.
+pub fn normalize(bytes: &[u8]) -> Vec<u8> {
+    undo(bytes, &[Drift::Bom, Drift::Crlf])
+}
+
+/// Find the smallest set of drifts whose removal makes `bytes` hash to `short_hash`.
+/// Returns `None` if the content differs by more than encoding noise.
+pub fn diagnose_drift(bytes: &[u8], short_hash: &str) -> Option<Vec<Drift>> {
+    CANDIDATES
+        .iter()
+        .find(|drifts| sha256_short(undo(bytes, drifts)) == short_hash)
+        .map(|drifts| drifts.to_vec())
+}
+
+/// Remove the given drifts from `bytes`
+pub fn undo(bytes: &[u8], drifts: &[Drift]) -> Vec<u8> {
+    let mut out = bytes;
+    if drifts.contains(&Drift::Bom) {
+        out = out.strip_prefix(BOM).unwrap_or(out);
+    }
+
+    let mut out = if drifts.contains(&Drift::Crlf) {
+        crlf_to_lf(out)
+    } else {
+        out.to_vec()
+    };
+
+    if drifts.contains(&Drift::TrailingWhitespace) {
+        let len = out
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map_or(0, |i| i + 1);
+        out.truncate(len);
+    }
+
+    out
+}
+
+/// Human-readable list of drifts, e.g. "CRLF line endings and a UTF-8 BOM"
+pub fn describe(drifts: &[Drift]) -> String {
+    let parts: Vec<String> = drifts.iter().map(|d| d.to_string()).collect();
+    match parts.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => String::new(),
+    }
+}
+
+fn crlf_to_lf(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter().peekable();
+    while let Some(&b) = iter.next() {
+        if b == b'\r' && iter.peek() == Some(&&b'\n') {
+            continue;
+        }
+        out.push(b);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY: &str = "Summary: Test\nPrevious: none\nDate: 2025-06-12T14:32:07Z\n\n---\n\nBody";
+
+    #[test]
+    fn test_normalize_strips_bom_and_crlf() {
+        let input = b"\xef\xbb\xbf<summary>x</summary>\r\n\r\nBody\r\n";
+        assert_eq!(normalize(input), b"<summary>x</summary>\n\nBody\n");
+    }
+
+    #[test]
+    fn test_normalize_keeps_lone_cr() {
+        assert_eq!(normalize(b"a\rb\r\n"), b"a\rb\n");
+    }
+
+    #[test]
+    fn test_diagnose_crlf() {
+        let short = sha256_short(ENTRY);
+        let crlf = ENTRY.replace('\n', "\r\n");
+        assert_eq!(
+            diagnose_drift(crlf.as_bytes(), &short),
+            Some(vec![Drift::Crlf])
+        );
+    }
+
+    #[test]
+    fn test_diagnose_bom_and_trailing_newline() {
+        let short = sha256_short(ENTRY);
+        let drifted = format!("\u{feff}{}\n", ENTRY);
+        assert_eq!(
+            diagnose_drift(drifted.as_bytes(), &short),
+            Some(vec![Drift::Bom, Drift::TrailingWhitespace])
+        );
+    }
+
+    #[test]
+    fn test_diagnose_real_edit_is_none() {
+        let short = sha256_short(ENTRY);
+        let edited = ENTRY.replace("Body", "Edited body");
+        assert_eq!(diagnose_drift(edited.as_bytes(), &short), None);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(&[Drift::Crlf]), "CRLF line endings");
+        assert_eq!(
+            describe(&[Drift::Bom, Drift::Crlf, Drift::TrailingWhitespace]),
+            "a UTF-8 BOM, CRLF line endings and trailing whitespace"
+        );
+    }
+}
@@ -1,4 +1,27 @@
+use chrono::{DateTime, Utc};
 use regex::Regex;
+use std::fmt;
+
+use crate::engram::refs::{split_list, Reference};
+use crate::engram::worklog::BODY_SEPARATOR;
+
+/// Why an entry's Date: header could not be used
+#[derive(Debug, PartialEq, Eq)]
+pub enum DateError {
+    /// No Date: line present
+    Missing,
+    /// Date: line present but not a strict RFC 3339 timestamp (raw value attached)
+    Invalid(String),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::Missing => write!(f, "missing 'Date:' line"),
+            DateError::Invalid(value) => write!(f, "invalid RFC 3339 date '{}'", value),
+        }
+    }
+}
 
 /// Parse the Previous hash from entry content
 /// Returns the hash string ("none" or 64-char hex)
@@ -23,15 +46,37 @@ pub fn parse_summary(content: &str) -> Option<String> {
     None
 }
 
-/// Parse the Date from entry content
-pub fn parse_date(content: &str) -> Option<String> {
+/// References on the `Refs:` header; tokens that are not references are
+/// ignored, and so is a `Refs:` line in the body
+pub fn parse_refs(content: &str) -> Vec<Reference> {
+    let re = Regex::new(r"^Refs: (.*)$").unwrap();
+    let header = content.split(BODY_SEPARATOR).next().unwrap_or(content);
+    header
+        .lines()
+        .find_map(|line| re.captures(line).map(|caps| caps[1].to_string()))
+        .map(|value| split_list(&value).filter_map(Reference::parse).collect())
+        .unwrap_or_default()
+}
+
+/// Parse the Date from entry content as a strict RFC 3339 timestamp
+/// (e.g. "2025-06-12T14:32:07Z"), normalized to UTC
+pub fn parse_date(content: &str) -> Result<DateTime<Utc>, DateError> {
     let re = Regex::new(r"^Date: (.+)$").unwrap();
-    for line in content.lines() {
-        if let Some(caps) = re.captures(line) {
-            return Some(caps[1].to_string());
-        }
+    let value = content
+        .lines()
+        .find_map(|line| re.captures(line).map(|caps| caps[1].to_string()))
+        .ok_or(DateError::Missing)?;
+
+    // chrono accepts a space or lowercase 't' separator; RFC 3339 entries must not
+    let strict =
+        Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$").unwrap();
+    if !strict.is_match(&value) {
+        return Err(DateError::Invalid(value));
     }
-    None
+
+    DateTime::parse_from_rfc3339(&value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|_| DateError::Invalid(value))
 }
 
 #[cfg(test)]
@@ -69,12 +114,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_refs_reads_headers_only() {
+        let content = "Summary: Test\nPrevious: none\nDate: 2025-06-12T14:32:07Z\nRefs: #1, JIRA-2\n\n---\n\nRefs: #9";
+        assert_eq!(
+            parse_refs(content),
+            vec![
+                Reference::External("#1".to_string()),
+                Reference::External("JIRA-2".to_string()),
+            ]
+        );
+        let content =
+            "Summary: Test\nPrevious: none\nDate: 2025-06-12T14:32:07Z\n\n---\n\nRefs: #9";
+        assert!(parse_refs(content).is_empty());
+    }
+
     #[test]
     fn test_parse_date() {
         let content = "Summary: Test\nPrevious: none\nDate: 2025-06-12T14:32:07Z";
         assert_eq!(
             parse_date(content),
-            Some("2025-06-12T14:32:07Z".to_string())
+            Ok(DateTime::parse_from_rfc3339("2025-06-12T14:32:07Z")
+                .unwrap()
+                .with_timezone(&Utc))
+        );
+    }
+
+    #[test]
+    fn test_parse_date_normalizes_offset() {
+        let content = "Date: 2025-06-12T16:32:07+02:00";
+        assert_eq!(
+            parse_date(content).unwrap().to_rfc3339(),
+            "2025-06-12T14:32:07+00:00"
         );
     }
+
+    #[test]
+    fn test_parse_date_missing() {
+        assert_eq!(
+            parse_date("Summary: Test\nPrevious: none"),
+            Err(DateError::Missing)
+        );
+    }
+
+    #[test]
+    fn test_parse_date_rejects_non_rfc3339() {
+        for value in [
+            "2025-06-12 14:32:07Z",
+            "2025-06-12t14:32:07Z",
+            "2025-06-12T14:32:07",
+            "2025-13-12T14:32:07Z",
+            "yesterday",
+        ] {
+            let content = format!("Date: {}", value);
+            assert_eq!(
+                parse_date(&content),
+                Err(DateError::Invalid(value.to_string())),
+                "{}",
+                value
+            );
+        }
+    }
 }
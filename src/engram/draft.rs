@@ -1,9 +1,18 @@
 use regex::Regex;
+use serde_json::{json, Value};
 use std::fmt;
 
+use crate::engram::refs::{split_list, Reference};
+
 #[derive(Debug)]
 pub struct Draft {
     pub summary: String,
+    /// Lowercased, deduplicated tags from front matter or a `Tags:` line
+    pub tags: Vec<String>,
+    /// Lowercased change type from front matter or a `Type:` line (`fix`, `feat`, ...)
+    pub change_type: Option<String>,
+    /// References from front matter or a `Refs:` line, validated but not resolved
+    pub refs: Vec<Reference>,
     pub body: String,
 }
 
@@ -12,6 +21,15 @@ pub enum DraftError {
     MissingSummaryTag,
     EmptySummary,
     EmptyBody,
+    InvalidUtf8,
+    /// A tag is empty or contains characters other than a-z, 0-9, `-`, `_`, `.`, `/`
+    InvalidTag(String),
+    /// The type is not a single word of tag characters
+    InvalidType(String),
+    /// A reference is neither a full entry hash nor an external id (`#123`, `JIRA-45`)
+    InvalidRef(String),
+    /// A full hash in `Refs:` does not match any committed entry
+    UnknownRef(String),
 }
 
 impl fmt::Display for DraftError {
@@ -26,6 +44,43 @@ impl fmt::Display for DraftError {
             DraftError::EmptyBody => {
                 write!(f, "Draft body is empty. Document your changes.")
             }
+            DraftError::InvalidUtf8 => {
+                write!(f, "draft.md is not valid UTF-8")
+            }
+            DraftError::InvalidTag(tag) => write!(
+                f,
+                "Invalid tag '{}'. Tags use letters, digits, '-', '_', '.' and '/'.",
+                tag
+            ),
+            DraftError::InvalidType(value) => write!(
+                f,
+                "Invalid type '{}'. Use a single word such as fix, feat or changed.",
+                value
+            ),
+            DraftError::InvalidRef(reference) => write!(
+                f,
+                "Invalid reference '{}'. Use a full entry hash or an id like #123 or JIRA-45.",
+                reference
+            ),
+            DraftError::UnknownRef(hash) => {
+                write!(f, "Refs: no committed entry has hash {}", hash)
+            }
+        }
+    }
+}
+
+impl DraftError {
+    /// Stable identifier for scripts and JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            DraftError::MissingSummaryTag => "E_DRAFT_NO_SUMMARY_TAG",
+            DraftError::EmptySummary => "E_DRAFT_SUMMARY_EMPTY",
+            DraftError::EmptyBody => "E_DRAFT_EMPTY",
+            DraftError::InvalidUtf8 => "E_DRAFT_INVALID_UTF8",
+            DraftError::InvalidTag(_) => "E_DRAFT_INVALID_TAG",
+            DraftError::InvalidType(_) => "E_DRAFT_INVALID_TYPE",
+            DraftError::InvalidRef(_) => "E_DRAFT_INVALID_REF",
+            DraftError::UnknownRef(_) => "E_DRAFT_UNKNOWN_REF",
         }
     }
 }
@@ -33,7 +88,13 @@ impl fmt::Display for DraftError {
 impl std::error::Error for DraftError {}
 
 impl Draft {
+    /// Parse a draft. Tags, type and references may be given in YAML-style
+    /// front matter (`---` / `tags: auth, bugfix` / `type: fix` / `refs: "#12"`
+    /// / `---`) or on `Tags:` / `Type:` / `Refs:` lines directly below the
+    /// summary; either form is removed from the body.
     pub fn parse(content: &str) -> Result<Self, DraftError> {
+        let (front_matter, content) = split_front_matter(content);
+
         // Extract <summary>...</summary>
         let re = Regex::new(r"<summary>(.*?)</summary>").unwrap();
         let caps = re.captures(content).ok_or(DraftError::MissingSummaryTag)?;
@@ -43,13 +104,53 @@ impl Draft {
             return Err(DraftError::EmptySummary);
         }
 
-        // Extract body after </summary>
-        let body_start = content
-            .find("</summary>")
-            .map(|i| i + "</summary>".len())
-            .unwrap_or(0);
+        // Header lines directly below </summary>, then the body
+        let (headers, body) = split_header_lines(
+            &content[caps.get(0).unwrap().end()..],
+            &["Tags", "Type", "Refs"],
+        );
+        let line_tags = header_value(&headers, "Tags");
+        let line_type = header_value(&headers, "Type");
+        let line_refs = header_value(&headers, "Refs");
+
+        let mut tags = Vec::new();
+        for list in front_matter_value(&front_matter, "tags")
+            .iter()
+            .chain(line_tags.iter())
+        {
+            for tag in parse_tag_list(list)? {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+
+        // A `Type:` line overrides front matter
+        let change_type = match line_type.or_else(|| front_matter_value(&front_matter, "type")) {
+            Some(value) => match parse_tag_list(&value) {
+                Ok(words) if words.len() == 1 => words.into_iter().next(),
+                Ok(words) if words.is_empty() => None,
+                _ => return Err(DraftError::InvalidType(value.trim().to_string())),
+            },
+            None => None,
+        };
+
+        let mut refs = Vec::new();
+        for list in front_matter_value(&front_matter, "refs")
+            .iter()
+            .chain(line_refs.iter())
+        {
+            for token in split_list(list) {
+                let token = token.trim_matches(|c| c == '"' || c == '\'');
+                let reference = Reference::parse(token)
+                    .ok_or_else(|| DraftError::InvalidRef(token.to_string()))?;
+                if !refs.contains(&reference) {
+                    refs.push(reference);
+                }
+            }
+        }
 
-        let body = content[body_start..].trim().to_string();
+        let body = body.trim().to_string();
 
         // Check if body has content beyond template comments
         let body_without_comments = remove_html_comments(&body);
@@ -57,10 +158,185 @@ impl Draft {
             return Err(DraftError::EmptyBody);
         }
 
-        Ok(Draft { summary, body })
+        Ok(Draft {
+            summary,
+            tags,
+            change_type,
+            refs,
+            body,
+        })
+    }
+
+    /// JSON form used by `engram draft validate --json`
+    pub fn to_json(&self) -> Value {
+        let refs: Vec<&str> = self.refs.iter().map(Reference::as_str).collect();
+        json!({
+            "summary": self.summary,
+            "tags": self.tags,
+            "type": self.change_type,
+            "refs": refs,
+            "body": self.body,
+        })
+    }
+}
+
+/// Set the text of the `<summary>` tag, leaving the rest of the draft as is.
+/// The summary is collapsed to one line; a draft whose tag was deleted gets
+/// a new one at the top, below any front matter.
+pub fn set_summary(content: &str, summary: &str) -> Result<String, DraftError> {
+    let summary = summary
+        .replace("<summary>", "")
+        .replace("</summary>", "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if summary.is_empty() {
+        return Err(DraftError::EmptySummary);
+    }
+
+    let re = Regex::new(r"<summary>(.*?)</summary>").unwrap();
+    if let Some(inner) = re.captures(content).and_then(|caps| caps.get(1)) {
+        return Ok(format!(
+            "{}{}{}",
+            &content[..inner.start()],
+            summary,
+            &content[inner.end()..]
+        ));
+    }
+    let body_start = content.len() - split_front_matter(content).1.len();
+    Ok(format!(
+        "{}<summary>{}</summary>\n\n{}",
+        &content[..body_start],
+        summary,
+        &content[body_start..]
+    ))
+}
+
+/// Append `text` to the end of the `## {section}` section, after its
+/// template comment. The heading is matched ignoring case; a missing
+/// section is added at the end of the draft.
+pub fn append_to_section(content: &str, section: &str, text: &str) -> String {
+    let text = text.trim();
+    let mut lines: Vec<&str> = content.lines().collect();
+    let heading = lines.iter().position(|line| {
+        line.strip_prefix("## ")
+            .is_some_and(|title| title.trim().eq_ignore_ascii_case(section.trim()))
+    });
+
+    let Some(heading) = heading else {
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let mut draft = lines.join("\n");
+        if !draft.is_empty() {
+            draft.push_str("\n\n");
+        }
+        draft.push_str(&format!("## {}\n{}\n", section.trim(), text));
+        return draft;
+    };
+
+    let end = lines[heading + 1..]
+        .iter()
+        .position(|line| line.starts_with("# ") || line.starts_with("## "))
+        .map_or(lines.len(), |offset| heading + 1 + offset);
+    let last = lines[heading + 1..end]
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(heading, |offset| heading + 1 + offset);
+
+    let mut draft: Vec<&str> = lines[..=last].to_vec();
+    draft.extend(text.lines());
+    if end < lines.len() {
+        draft.push("");
+        draft.extend(&lines[end..]);
+    }
+    let mut draft = draft.join("\n");
+    draft.push('\n');
+    draft
+}
+
+/// Split off leading `---` front matter, returning its `key: value` pairs
+/// and the rest of the draft
+fn split_front_matter(content: &str) -> (Vec<(String, String)>, &str) {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return (Vec::new(), content);
+    };
+    let Some((front_matter, rest)) = rest.split_once("\n---\n") else {
+        return (Vec::new(), content);
+    };
+
+    let pairs = front_matter
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    (pairs, rest)
+}
+
+/// Split the `{name}: value` lines that directly follow `</summary>` from
+/// the body below them. Each name is taken once; the same text further down
+/// is body prose.
+fn split_header_lines<'a>(rest: &'a str, names: &[&str]) -> (Vec<(String, String)>, &'a str) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let Some(mut lines) = rest
+        .trim_start_matches([' ', '\t', '\r'])
+        .strip_prefix('\n')
+    else {
+        return (headers, rest);
+    };
+    loop {
+        let (line, next) = lines.split_once('\n').unwrap_or((lines, ""));
+        let header = line.split_once(':').filter(|(name, _)| {
+            names.contains(name) && !headers.iter().any(|(seen, _)| seen == name)
+        });
+        let Some((name, value)) = header else {
+            break;
+        };
+        headers.push((name.to_string(), value.trim().to_string()));
+        lines = next;
+    }
+    if headers.is_empty() {
+        (headers, rest)
+    } else {
+        (headers, lines)
     }
 }
 
+fn header_value(headers: &[(String, String)], name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|(header, _)| header == name)
+        .map(|(_, value)| value.clone())
+}
+
+fn front_matter_value(pairs: &[(String, String)], key: &str) -> Option<String> {
+    pairs
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.clone())
+}
+
+/// Parse "auth, bugfix" or "[auth, bugfix]" into lowercased tags
+pub fn parse_tag_list(list: &str) -> Result<Vec<String>, DraftError> {
+    let valid = Regex::new(r"^[a-z0-9][a-z0-9_./-]*$").unwrap();
+    let list = list.trim().trim_start_matches('[').trim_end_matches(']');
+    list.split(',')
+        .map(|tag| {
+            tag.trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .to_lowercase()
+        })
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| {
+            if valid.is_match(&tag) {
+                Ok(tag)
+            } else {
+                Err(DraftError::InvalidTag(tag))
+            }
+        })
+        .collect()
+}
+
 fn remove_html_comments(text: &str) -> String {
     let re = Regex::new(r"<!--.*?-->").unwrap();
     re.replace_all(text, "").to_string()
@@ -69,6 +345,7 @@ fn remove_html_comments(text: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::templates::DRAFT_TEMPLATE;
 
     #[test]
     fn test_parse_valid_draft() {
@@ -102,6 +379,167 @@ Ran tests."#;
         assert!(matches!(result, Err(DraftError::EmptySummary)));
     }
 
+    #[test]
+    fn test_parse_tag_line() {
+        let content =
+            "<summary>Fix login</summary>\nTags: Auth, bugfix, auth\n\n## Changes\n- Fixed";
+        let draft = Draft::parse(content).unwrap();
+        assert_eq!(draft.tags, vec!["auth", "bugfix"]);
+        assert_eq!(draft.body, "## Changes\n- Fixed");
+    }
+
+    #[test]
+    fn test_tags_in_body_are_prose() {
+        let content =
+            "<summary>Fix login</summary>\n\n## Intent\nTags: we should add CI tags later";
+        let draft = Draft::parse(content).unwrap();
+        assert!(draft.tags.is_empty());
+        assert!(draft.body.ends_with("Tags: we should add CI tags later"));
+
+        let content = "<summary>Fix login</summary>\nTags: auth\n\n## Intent\nTags: not, tags here";
+        let draft = Draft::parse(content).unwrap();
+        assert_eq!(draft.tags, vec!["auth"]);
+        assert_eq!(draft.body, "## Intent\nTags: not, tags here");
+    }
+
+    #[test]
+    fn test_parse_front_matter_tags() {
+        let content = "---\ntags: [api, \"docs\"]\n---\n<summary>Document API</summary>\n\n## Changes\n- Docs";
+        let draft = Draft::parse(content).unwrap();
+        assert_eq!(draft.summary, "Document API");
+        assert_eq!(draft.tags, vec!["api", "docs"]);
+        assert!(!draft.body.contains("tags"));
+    }
+
+    #[test]
+    fn test_parse_invalid_tag() {
+        let content = "<summary>S</summary>\nTags: good, not ok\n\n## Changes\n- x";
+        assert!(matches!(
+            Draft::parse(content),
+            Err(DraftError::InvalidTag(tag)) if tag == "not ok"
+        ));
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let content = "<summary>S</summary>\nTags: auth\nType: Fix\n\n## Changes\n- x";
+        let draft = Draft::parse(content).unwrap();
+        assert_eq!(draft.change_type.as_deref(), Some("fix"));
+        assert_eq!(draft.body, "## Changes\n- x");
+
+        let content = "---\ntype: feat\n---\n<summary>S</summary>\n\n## Changes\n- x";
+        assert_eq!(
+            Draft::parse(content).unwrap().change_type.as_deref(),
+            Some("feat")
+        );
+
+        let content = "<summary>S</summary>\nType: fix, feat\n\n## Changes\n- x";
+        assert!(matches!(
+            Draft::parse(content),
+            Err(DraftError::InvalidType(value)) if value == "fix, feat"
+        ));
+    }
+
+    #[test]
+    fn test_parse_refs() {
+        let hash = "b".repeat(64);
+        let content = format!(
+            "<summary>Revert login</summary>\nRefs: {}, #123, JIRA-45\n\n## Changes\n- Reverted",
+            hash
+        );
+        let draft = Draft::parse(&content).unwrap();
+        assert_eq!(
+            draft.refs,
+            vec![
+                Reference::Entry(hash),
+                Reference::External("#123".to_string()),
+                Reference::External("JIRA-45".to_string()),
+            ]
+        );
+        assert!(!draft.body.contains("Refs:"));
+
+        let content = "---\nrefs: [\"#9\"]\n---\n<summary>S</summary>\n\n## Changes\n- x";
+        assert_eq!(
+            Draft::parse(content).unwrap().refs,
+            vec![Reference::External("#9".to_string())]
+        );
+
+        let content = "<summary>S</summary>\nRefs: abcd1234\n\n## Changes\n- x";
+        assert!(matches!(
+            Draft::parse(content),
+            Err(DraftError::InvalidRef(reference)) if reference == "abcd1234"
+        ));
+    }
+
+    #[test]
+    fn test_refs_in_body_are_prose() {
+        let content = "<summary>S</summary>\n\n## Intent\nRefs: see the design doc";
+        let draft = Draft::parse(content).unwrap();
+        assert!(draft.refs.is_empty());
+        assert!(draft.body.ends_with("Refs: see the design doc"));
+
+        let content = "<summary>S</summary>\nRefs: #4\nTags: auth\n\n## Intent\nRefs: the RFC";
+        let draft = Draft::parse(content).unwrap();
+        assert_eq!(draft.refs, vec![Reference::External("#4".to_string())]);
+        assert_eq!(draft.tags, vec!["auth"]);
+        assert_eq!(draft.body, "## Intent\nRefs: the RFC");
+    }
+
+    #[test]
+    fn test_parse_without_tags() {
+        let draft = Draft::parse("<summary>S</summary>\n\n## Changes\n- x").unwrap();
+        assert!(draft.tags.is_empty());
+    }
+
+    #[test]
+    fn test_set_summary_keeps_template() {
+        let draft = set_summary(DRAFT_TEMPLATE, "  Fix\n login  ").unwrap();
+        assert_eq!(
+            draft,
+            DRAFT_TEMPLATE.replace("<summary></summary>", "<summary>Fix login</summary>")
+        );
+        let draft = set_summary(&draft, "Fix $0 </summary>redirect").unwrap();
+        assert!(draft.starts_with("<summary>Fix $0 redirect</summary>\n\n## Intent\n<!--"));
+
+        assert!(matches!(
+            set_summary(DRAFT_TEMPLATE, " <summary> "),
+            Err(DraftError::EmptySummary)
+        ));
+    }
+
+    #[test]
+    fn test_set_summary_restores_missing_tag() {
+        let draft = set_summary("---\ntags: api\n---\n## Changes\n- x\n", "Add API").unwrap();
+        assert_eq!(
+            draft,
+            "---\ntags: api\n---\n<summary>Add API</summary>\n\n## Changes\n- x\n"
+        );
+        assert_eq!(Draft::parse(&draft).unwrap().summary, "Add API");
+    }
+
+    #[test]
+    fn test_append_to_section() {
+        let draft = append_to_section(DRAFT_TEMPLATE, "changes", "- src/login.rs: validate()");
+        let draft = append_to_section(&draft, "Changes", "- src/token.rs\n- README.md");
+        assert_eq!(
+            draft,
+            DRAFT_TEMPLATE.replace(
+                "modified -->\n",
+                "modified -->\n- src/login.rs: validate()\n- src/token.rs\n- README.md\n"
+            )
+        );
+
+        let draft = append_to_section(&draft, "Verification", "cargo test");
+        assert!(draft.ends_with("validate this change? -->\ncargo test\n"));
+    }
+
+    #[test]
+    fn test_append_adds_missing_section() {
+        let draft = append_to_section("<summary>S</summary>\n\n", "Notes", "Follow up");
+        assert_eq!(draft, "<summary>S</summary>\n\n## Notes\nFollow up\n");
+        assert_eq!(Draft::parse(&draft).unwrap().body, "## Notes\nFollow up");
+    }
+
     #[test]
     fn test_parse_empty_body() {
         let content = "<summary>Summary here</summary>\n\n<!-- just comments -->";
@@ -1,4 +1,6 @@
+pub mod canonical;
 pub mod chain;
 pub mod draft;
+pub mod refs;
 pub mod summary;
 pub mod worklog;
@@ -0,0 +1,82 @@
+use regex::Regex;
+
+/// Header holding an entry's comma-separated references
+pub const REFS_HEADER: &str = "Refs";
+
+/// A `Refs:` value: another entry (by full hash) or an external identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// Full SHA256 of an earlier entry
+    Entry(String),
+    /// Issue or ticket, e.g. `#123`, `JIRA-45`, `org/repo#7`
+    External(String),
+}
+
+impl Reference {
+    /// Classify a single reference; None if it is neither form
+    pub fn parse(token: &str) -> Option<Self> {
+        let token = token.trim();
+        let hash = Regex::new(r"^[0-9a-fA-F]{64}$").unwrap();
+        if hash.is_match(token) {
+            return Some(Reference::Entry(token.to_lowercase()));
+        }
+
+        let external =
+            Regex::new(r"^(#\d+|[A-Za-z][A-Za-z0-9_]*-\d+|[\w.-]+/[\w.-]+#\d+)$").unwrap();
+        external
+            .is_match(token)
+            .then(|| Reference::External(token.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Reference::Entry(hash) => hash,
+            Reference::External(id) => id,
+        }
+    }
+}
+
+/// Split a `Refs:` value into its comma-separated references, skipping empties
+pub fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference() {
+        let hash = "a".repeat(64);
+        assert_eq!(
+            Reference::parse(&hash),
+            Some(Reference::Entry(hash.clone()))
+        );
+        assert_eq!(
+            Reference::parse(&hash.to_uppercase()),
+            Some(Reference::Entry(hash))
+        );
+        for external in ["#123", "JIRA-45", "org/repo#7"] {
+            assert_eq!(
+                Reference::parse(external),
+                Some(Reference::External(external.to_string()))
+            );
+        }
+        for invalid in ["abcd1234", "see above", "#", "JIRA-"] {
+            assert_eq!(Reference::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list(" #1, JIRA-2 ,, ").collect::<Vec<_>>(),
+            vec!["#1", "JIRA-2"]
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::fs;
 use std::io;
 use std::path::Path;
 
+use crate::templates::SUMMARY_TEMPLATE;
+
 /// Append an entry to the SUMMARY.md file
 /// Format: | {filename} | {summary} |
 pub fn append_entry(summary_path: &Path, filename: &str, summary: &str) -> io::Result<()> {
@@ -11,12 +13,52 @@ pub fn append_entry(summary_path: &Path, filename: &str, summary: &str) -> io::R
     fs::write(summary_path, content)
 }
 
+/// Summary column text for an entry: the summary followed by its tags as
+/// inline code, e.g. "Fix login `auth` `bugfix`"
+pub fn row_text(summary: &str, tags: &[String]) -> String {
+    let mut text = summary.to_string();
+    for tag in tags {
+        text.push_str(&format!(" `{}`", tag));
+    }
+    text
+}
+
+/// Parse the entry rows of SUMMARY.md as (filename, summary) pairs.
+/// Header and separator rows are skipped.
+pub fn parse_rows(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("| ")?.strip_suffix(" |"))
+        .filter_map(|row| row.split_once(" | "))
+        .filter(|(filename, _)| filename.ends_with(".md"))
+        .map(|(filename, summary)| (filename.to_string(), summary.to_string()))
+        .collect()
+}
+
+/// Rewrite SUMMARY.md from scratch with the given (filename, summary) rows
+pub fn rebuild(summary_path: &Path, rows: &[(String, String)]) -> io::Result<()> {
+    let mut content = SUMMARY_TEMPLATE.to_string();
+    for (filename, summary) in rows {
+        content.push_str(&format!("| {} | {} |\n", filename, summary));
+    }
+    fs::write(summary_path, content)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_row_text() {
+        assert_eq!(row_text("Fix login", &[]), "Fix login");
+        assert_eq!(
+            row_text("Fix login", &["auth".to_string(), "bugfix".to_string()]),
+            "Fix login `auth` `bugfix`"
+        );
+    }
+
     #[test]
     fn test_append_entry() {
         let dir = tempdir().unwrap();
@@ -35,4 +77,33 @@ mod tests {
         let content = fs::read_to_string(&summary_path).unwrap();
         assert!(content.contains("| 000001_a1b2c3d4.md | First commit |"));
     }
+
+    #[test]
+    fn test_parse_rows_skips_header() {
+        let content = "# Engram Worklog\n\n| Entry | Summary |\n|-------|---------|\n| 000001_a1b2c3d4.md | First | with pipe |\n";
+        let rows = parse_rows(content);
+        assert_eq!(
+            rows,
+            vec![(
+                "000001_a1b2c3d4.md".to_string(),
+                "First | with pipe".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_rebuild_round_trips() {
+        let dir = tempdir().unwrap();
+        let summary_path = dir.path().join("SUMMARY.md");
+        let rows = vec![
+            ("000001_a1b2c3d4.md".to_string(), "First".to_string()),
+            ("000002_e5f6a7b8.md".to_string(), "Second".to_string()),
+        ];
+
+        rebuild(&summary_path, &rows).unwrap();
+
+        let content = fs::read_to_string(&summary_path).unwrap();
+        assert!(content.starts_with(SUMMARY_TEMPLATE));
+        assert_eq!(parse_rows(&content), rows);
+    }
 }
@@ -1,6 +1,12 @@
 use chrono::{DateTime, Utc};
 use std::path::{Path, PathBuf};
 
+use crate::engram::chain::{parse_date, parse_previous_hash, parse_summary};
+use crate::engram::refs::{split_list, Reference, REFS_HEADER};
+
+/// Format of the Date: header written by commit
+pub const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
 #[derive(Debug, Clone)]
 pub struct WorklogEntry {
     pub sequence: u32,
@@ -14,19 +20,113 @@ pub struct EntryContent {
     pub summary: String,
     pub previous: String, // "none" or 64-char hash
     pub date: DateTime<Utc>,
+    /// Headers other than Summary/Previous/Date, in file order ("Tags", "a, b")
+    pub headers: Vec<(String, String)>,
     pub body: String,
 }
 
+/// Headers every entry carries; anything else is an extra header
+const CORE_HEADERS: [&str; 3] = ["Summary", "Previous", "Date"];
+
+/// Header holding an entry's comma-separated tags
+pub const TAGS_HEADER: &str = "Tags";
+
+/// Header holding an entry's change type (`fix`, `feat`, ...)
+pub const TYPE_HEADER: &str = "Type";
+
 impl std::fmt::Display for EntryContent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Summary: {}\nPrevious: {}\nDate: {}\n\n---\n\n{}",
+            "Summary: {}\nPrevious: {}\nDate: {}\n",
             self.summary,
             self.previous,
-            self.date.format("%Y-%m-%dT%H:%M:%SZ"),
-            self.body
-        )
+            self.date.format(DATE_FORMAT)
+        )?;
+        for (name, value) in &self.headers {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+        write!(f, "\n---\n\n{}", self.body)
+    }
+}
+
+/// Separator between the header block and the body
+pub(crate) const BODY_SEPARATOR: &str = "\n\n---\n\n";
+
+impl EntryContent {
+    /// Parse entry text back into its parts (the inverse of `Display`).
+    /// Returns None if a required header is missing or malformed.
+    pub fn parse(content: &str) -> Option<Self> {
+        let (header, body) = content.split_once(BODY_SEPARATOR)?;
+
+        let headers = header
+            .lines()
+            .filter_map(|line| line.split_once(": "))
+            .filter(|(name, _)| !CORE_HEADERS.contains(name))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        Some(EntryContent {
+            summary: parse_summary(header)?,
+            previous: parse_previous_hash(header)?,
+            date: parse_date(header).ok()?,
+            headers,
+            body: body.to_string(),
+        })
+    }
+
+    /// Value of an extra header, if present
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Tags from the `Tags:` header, in the order written
+    pub fn tags(&self) -> Vec<String> {
+        self.header(TAGS_HEADER)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// References from the `Refs:` header (entry hashes and external ids)
+    pub fn refs(&self) -> Vec<Reference> {
+        self.header(REFS_HEADER)
+            .map(|value| split_list(value).filter_map(Reference::parse).collect())
+            .unwrap_or_default()
+    }
+
+    /// The body split at its `## ` headings as (heading, content) pairs.
+    /// Text before the first heading is returned under an empty heading.
+    pub fn sections(&self) -> Vec<(String, String)> {
+        let mut sections: Vec<(String, String)> = Vec::new();
+        for line in self.body.lines() {
+            if let Some(heading) = line.strip_prefix("## ") {
+                sections.push((heading.trim().to_string(), String::new()));
+                continue;
+            }
+            if sections.is_empty() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                sections.push((String::new(), String::new()));
+            }
+            let content = &mut sections.last_mut().expect("pushed above").1;
+            content.push_str(line);
+            content.push('\n');
+        }
+
+        for (_, content) in &mut sections {
+            *content = content.trim().to_string();
+        }
+        sections
     }
 }
 
@@ -61,6 +161,7 @@ mod tests {
             date: DateTime::parse_from_rfc3339("2025-06-12T14:32:07Z")
                 .unwrap()
                 .with_timezone(&Utc),
+            headers: Vec::new(),
             body: "## Intent\nTest body".to_string(),
         };
 
@@ -71,6 +172,58 @@ mod tests {
         assert!(output.contains("## Intent"));
     }
 
+    #[test]
+    fn test_entry_content_parse_round_trips() {
+        let entry = EntryContent {
+            summary: "Test summary".to_string(),
+            previous: "none".to_string(),
+            date: DateTime::parse_from_rfc3339("2025-06-12T14:32:07Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            headers: Vec::new(),
+            body: "## Intent\nTest body\n\n---\n\nA horizontal rule".to_string(),
+        };
+
+        let parsed = EntryContent::parse(&entry.to_string()).unwrap();
+        assert_eq!(parsed.to_string(), entry.to_string());
+        assert_eq!(parsed.body, entry.body);
+    }
+
+    #[test]
+    fn test_entry_content_extra_headers_round_trip() {
+        let text = "Summary: Tagged\nPrevious: none\nDate: 2025-06-12T14:32:07Z\nTags: auth, api\n\n---\n\n## Intent\nBody";
+        let parsed = EntryContent::parse(text).unwrap();
+        assert_eq!(
+            parsed.headers,
+            vec![("Tags".to_string(), "auth, api".to_string())]
+        );
+        assert_eq!(parsed.header("Tags"), Some("auth, api"));
+        assert_eq!(parsed.tags(), vec!["auth", "api"]);
+        assert_eq!(parsed.to_string(), text);
+    }
+
+    #[test]
+    fn test_entry_content_sections() {
+        let text = "Summary: S\nPrevious: none\nDate: 2025-06-12T14:32:07Z\n\n---\n\nPreamble\n\n## Intent\nWhy\n\n## Changes\n- One\n- Two\n";
+        let sections = EntryContent::parse(text).unwrap().sections();
+        assert_eq!(
+            sections,
+            vec![
+                (String::new(), "Preamble".to_string()),
+                ("Intent".to_string(), "Why".to_string()),
+                ("Changes".to_string(), "- One\n- Two".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entry_content_parse_rejects_missing_header() {
+        assert!(
+            EntryContent::parse("Summary: x\nDate: 2025-06-12T14:32:07Z\n\n---\n\nBody").is_none()
+        );
+        assert!(EntryContent::parse("no separator").is_none());
+    }
+
     #[test]
     fn test_worklog_entry_from_filename() {
         let base_path = PathBuf::from(".engram/worklog");
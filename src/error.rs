@@ -0,0 +1,254 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::fmt;
+use std::io;
+
+use crate::commands::verify::VerifyError;
+use crate::engram::draft::DraftError;
+use crate::engram::worklog::DATE_FORMAT;
+
+/// Process exit codes. These are part of the CLI contract and never change meaning.
+pub const EXIT_SUCCESS: i32 = 0;
+/// The worklog failed verification (tampering, corruption, bad dates)
+pub const EXIT_CHAIN_BROKEN: i32 = 1;
+/// No .engram/ in the current directory
+pub const EXIT_NOT_INITIALIZED: i32 = 2;
+/// `init` found an existing .engram/
+pub const EXIT_ALREADY_INITIALIZED: i32 = 3;
+/// The draft is missing or cannot be committed
+pub const EXIT_DRAFT_INVALID: i32 = 4;
+/// The clock is earlier than the latest entry
+pub const EXIT_CLOCK_BEHIND: i32 = 5;
+/// SUMMARY.md is missing
+pub const EXIT_SUMMARY: i32 = 6;
+/// Invalid configuration (e.g. SOURCE_DATE_EPOCH)
+pub const EXIT_CONFIG: i32 = 7;
+/// Filesystem or other I/O failure
+pub const EXIT_IO: i32 = 8;
+/// A referenced entry does not exist
+pub const EXIT_NOT_FOUND: i32 = 9;
+/// Records given to `import` do not form a valid continuation of the worklog
+pub const EXIT_IMPORT_REJECTED: i32 = 10;
+/// A hash prefix matches more than one entry
+pub const EXIT_AMBIGUOUS: i32 = 11;
+/// `doctor` finished but left problems unresolved
+pub const EXIT_PROBLEMS_FOUND: i32 = 12;
+
+/// Every error Engram can report.
+///
+/// Each variant has a stable identifier (`code`, e.g. `E_DRAFT_EMPTY`) and
+/// exit code (`exit_code`) that scripts may rely on.
+#[derive(Debug)]
+pub enum EngramError {
+    /// No .engram/ (or no .engram/worklog/) in the repository root
+    NotInitialized,
+    /// `init` found an existing .engram/
+    AlreadyInitialized,
+    /// .engram/draft.md does not exist
+    DraftMissing,
+    /// .engram/draft.md could not be committed
+    Draft(DraftError),
+    /// The clock is earlier than the latest entry's date
+    ClockBehind {
+        now: DateTime<Utc>,
+        latest: DateTime<Utc>,
+    },
+    /// The worklog failed verification
+    Chain(VerifyError),
+    /// An entry file could not be parsed
+    InvalidEntry(String),
+    /// No entry matches the given sequence, filename or hash
+    EntryNotFound(String),
+    /// A hash prefix matches several entries (their filenames)
+    AmbiguousEntry {
+        reference: String,
+        matches: Vec<String>,
+    },
+    /// .engram/worklog/SUMMARY.md does not exist
+    SummaryMissing,
+    /// Invalid configuration value
+    Config(String),
+    /// `import` input is malformed or does not chain onto the worklog
+    Import(String),
+    /// Underlying I/O failure
+    Io(io::Error),
+}
+
+impl EngramError {
+    /// Stable identifier, e.g. `E_DRAFT_EMPTY`
+    pub fn code(&self) -> &'static str {
+        match self {
+            EngramError::NotInitialized => "E_NOT_INITIALIZED",
+            EngramError::AlreadyInitialized => "E_ALREADY_INITIALIZED",
+            EngramError::DraftMissing => "E_DRAFT_MISSING",
+            EngramError::Draft(e) => e.code(),
+            EngramError::ClockBehind { .. } => "E_CLOCK_BEHIND",
+            EngramError::Chain(e) => e.code(),
+            EngramError::InvalidEntry(_) => "E_ENTRY_INVALID",
+            EngramError::EntryNotFound(_) => "E_ENTRY_NOT_FOUND",
+            EngramError::AmbiguousEntry { .. } => "E_ENTRY_AMBIGUOUS",
+            EngramError::SummaryMissing => "E_SUMMARY_MISSING",
+            EngramError::Config(_) => "E_CONFIG",
+            EngramError::Import(_) => "E_IMPORT_REJECTED",
+            EngramError::Io(_) => "E_IO",
+        }
+    }
+
+    /// JSON form used by `--json` and the MCP server
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "code": self.code(),
+                "exit_code": self.exit_code(),
+                "message": self.to_string(),
+            }
+        })
+    }
+
+    /// Process exit code for this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            EngramError::Chain(_) | EngramError::InvalidEntry(_) => EXIT_CHAIN_BROKEN,
+            EngramError::NotInitialized => EXIT_NOT_INITIALIZED,
+            EngramError::AlreadyInitialized => EXIT_ALREADY_INITIALIZED,
+            EngramError::DraftMissing | EngramError::Draft(_) => EXIT_DRAFT_INVALID,
+            EngramError::ClockBehind { .. } => EXIT_CLOCK_BEHIND,
+            EngramError::EntryNotFound(_) => EXIT_NOT_FOUND,
+            EngramError::AmbiguousEntry { .. } => EXIT_AMBIGUOUS,
+            EngramError::SummaryMissing => EXIT_SUMMARY,
+            EngramError::Config(_) => EXIT_CONFIG,
+            EngramError::Import(_) => EXIT_IMPORT_REJECTED,
+            EngramError::Io(_) => EXIT_IO,
+        }
+    }
+}
+
+impl fmt::Display for EngramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngramError::NotInitialized => {
+                write!(f, "Engram not initialized. Run `engram init` first.")
+            }
+            EngramError::AlreadyInitialized => {
+                write!(
+                    f,
+                    "Engram already initialized (found .engram/). Use `engram init --repair` to fill in missing files."
+                )
+            }
+            EngramError::DraftMissing => write!(f, "draft.md not found"),
+            EngramError::Draft(e) => write!(f, "{}", e),
+            EngramError::ClockBehind { now, latest } => write!(
+                f,
+                "Clock is behind the latest entry ({} < {}). Fix the system clock or set SOURCE_DATE_EPOCH.",
+                now.format(DATE_FORMAT),
+                latest.format(DATE_FORMAT)
+            ),
+            EngramError::Chain(e) => write!(f, "{}", e),
+            EngramError::InvalidEntry(filename) => write!(f, "Invalid entry: {}", filename),
+            EngramError::EntryNotFound(reference) => {
+                write!(f, "No entry matches '{}'", reference)
+            }
+            EngramError::AmbiguousEntry { reference, matches } => write!(
+                f,
+                "'{}' matches several entries ({}). Use a longer hash prefix.",
+                reference,
+                matches.join(", ")
+            ),
+            EngramError::SummaryMissing => write!(
+                f,
+                "SUMMARY.md not found. Run `engram doctor --fix` to rebuild it."
+            ),
+            EngramError::Config(message) => write!(f, "{}", message),
+            EngramError::Import(message) => write!(f, "Import rejected: {}", message),
+            EngramError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EngramError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngramError::Draft(e) => Some(e),
+            EngramError::Chain(e) => Some(e),
+            EngramError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EngramError {
+    fn from(error: io::Error) -> Self {
+        EngramError::Io(error)
+    }
+}
+
+impl From<DraftError> for EngramError {
+    fn from(error: DraftError) -> Self {
+        EngramError::Draft(error)
+    }
+}
+
+impl From<VerifyError> for EngramError {
+    fn from(error: VerifyError) -> Self {
+        EngramError::Chain(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_and_exit_codes() {
+        let cases = [
+            (EngramError::NotInitialized, "E_NOT_INITIALIZED", 2),
+            (EngramError::AlreadyInitialized, "E_ALREADY_INITIALIZED", 3),
+            (
+                EngramError::Draft(DraftError::EmptyBody),
+                "E_DRAFT_EMPTY",
+                4,
+            ),
+            (
+                EngramError::Chain(VerifyError::UnexpectedFile("x".to_string())),
+                "E_UNEXPECTED_FILE",
+                1,
+            ),
+            (EngramError::Config("bad".to_string()), "E_CONFIG", 7),
+            (
+                EngramError::Import("bad".to_string()),
+                "E_IMPORT_REJECTED",
+                10,
+            ),
+            (
+                EngramError::AmbiguousEntry {
+                    reference: "ab12".to_string(),
+                    matches: Vec::new(),
+                },
+                "E_ENTRY_AMBIGUOUS",
+                11,
+            ),
+            (EngramError::Io(io::Error::other("disk")), "E_IO", 8),
+        ];
+
+        for (error, code, exit_code) in cases {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.exit_code(), exit_code);
+        }
+    }
+
+    #[test]
+    fn test_codes_are_unique_per_draft_error() {
+        let codes = [
+            DraftError::MissingSummaryTag.code(),
+            DraftError::EmptySummary.code(),
+            DraftError::EmptyBody.code(),
+            DraftError::InvalidUtf8.code(),
+            DraftError::InvalidTag(String::new()).code(),
+            DraftError::InvalidType(String::new()).code(),
+        ];
+        for (i, code) in codes.iter().enumerate() {
+            assert!(code.starts_with("E_DRAFT_"));
+            assert!(!codes[i + 1..].contains(code));
+        }
+    }
+}
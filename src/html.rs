@@ -0,0 +1,302 @@
+//! HTML rendering shared by `engram serve` and `engram export html`.
+//!
+//! Pages are self-contained (inline CSS, no external assets). Entry bodies are
+//! rendered from markdown with raw HTML escaped and link and image targets
+//! limited to http, https, mailto and relative URLs, so an entry cannot
+//! inject markup or scripts into the page.
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+use crate::commands::verify::VerifyResult;
+use crate::engram::worklog::DATE_FORMAT;
+use crate::error::EngramError;
+use crate::repository::Entry;
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem;color:#222}
+a{color:#0b5cad}
+header{display:flex;justify-content:space-between;align-items:center;border-bottom:1px solid #ddd}
+.badge{padding:.2rem .6rem;border-radius:1rem;font-size:.85rem;color:#fff}
+.ok{background:#2e7d32}.broken{background:#c62828}
+.entry{border-bottom:1px solid #eee;padding:.6rem 0}
+.meta,.hash{color:#666;font-size:.85rem}
+.hash{font-family:ui-monospace,monospace;word-break:break-all}
+nav.pager{display:flex;justify-content:space-between;margin-top:2rem}
+pre{background:#f6f8fa;padding:.6rem;overflow-x:auto}
+table{border-collapse:collapse}td,th{border:1px solid #ddd;padding:.3rem .5rem;text-align:left}
+td.hash{max-width:18rem}
+";
+
+/// Escape text for use in HTML content and attribute values
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render markdown to HTML, showing any raw HTML as text and dropping
+/// link and image targets that are not [`safe_url`]s
+pub fn render_markdown(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) => Event::Text(raw),
+        Event::Start(tag) => Event::Start(safe_tag(tag)),
+        Event::End(tag) => Event::End(safe_tag(tag)),
+        event => event,
+    });
+
+    let mut output = String::new();
+    html::push_html(&mut output, parser);
+    output
+}
+
+fn safe_tag(tag: Tag<'_>) -> Tag<'_> {
+    match tag {
+        Tag::Link(kind, url, title) => Tag::Link(kind, checked_url(url), title),
+        Tag::Image(kind, url, title) => Tag::Image(kind, checked_url(url), title),
+        tag => tag,
+    }
+}
+
+fn checked_url(url: CowStr<'_>) -> CowStr<'_> {
+    if safe_url(&url) {
+        url
+    } else {
+        CowStr::Borrowed("")
+    }
+}
+
+/// Whether a link target is http, https, mailto or relative. Browsers skip
+/// whitespace and control characters when reading a scheme, so those are
+/// ignored here too.
+pub fn safe_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect();
+    let scheme_end = url.find([':', '/', '?', '#']);
+    match scheme_end {
+        Some(end) if url[end..].starts_with(':') => {
+            let scheme = url[..end].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+/// Wrap `body` in a complete HTML document
+pub fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+/// Verification status badge
+pub fn verify_badge(verify: &Result<VerifyResult, EngramError>) -> String {
+    match verify {
+        Ok(result) => format!(
+            "<span class=\"badge ok\" id=\"verify-badge\" title=\"Hash chain verified\">✓ Chain verified ({} entries)</span>",
+            result.entry_count
+        ),
+        Err(e) => format!(
+            "<span class=\"badge broken\" id=\"verify-badge\" title=\"{}\">✗ {}</span>",
+            escape(&e.to_string()),
+            e.code()
+        ),
+    }
+}
+
+/// Timeline of entries, newest first. `entry_href` maps an entry to its page;
+/// `footer` is appended verbatim after the list (links, scripts).
+pub fn timeline(
+    entries: &[Entry],
+    badge: &str,
+    entry_href: impl Fn(&Entry) -> String,
+    footer: &str,
+) -> String {
+    let mut body = format!(
+        "<header><h1>Engram worklog</h1>{}</header>\n<main>\n",
+        badge
+    );
+
+    if entries.is_empty() {
+        body.push_str("<p>No entries yet.</p>\n");
+    }
+
+    for entry in entries.iter().rev() {
+        body.push_str(&format!(
+            "<div class=\"entry\" data-sequence=\"{}\"><a href=\"{}\">{}</a>\n<div class=\"meta\">{} · {}</div></div>\n",
+            entry.sequence,
+            escape(&entry_href(entry)),
+            escape(&entry.content.summary),
+            escape(&entry.filename),
+            entry.content.date.format(DATE_FORMAT)
+        ));
+    }
+
+    body.push_str("</main>\n");
+    body.push_str(footer);
+    page("Engram worklog", &body)
+}
+
+/// Detail page for one entry with links to its neighbours.
+/// `previous` and `next` are the adjacent entries in sequence order.
+pub fn entry_page(
+    entry: &Entry,
+    previous: Option<&Entry>,
+    next: Option<&Entry>,
+    index_href: &str,
+    entry_href: impl Fn(&Entry) -> String,
+) -> String {
+    let previous_hash = match previous {
+        Some(prev) if prev.hash == entry.content.previous => format!(
+            "<a class=\"hash\" href=\"{}\">{}</a>",
+            escape(&entry_href(prev)),
+            escape(&entry.content.previous)
+        ),
+        _ => format!(
+            "<span class=\"hash\">{}</span>",
+            escape(&entry.content.previous)
+        ),
+    };
+
+    let mut body = format!(
+        "<header><h1>{}</h1><a href=\"{}\">All entries</a></header>\n\
+         <p class=\"meta\">{} · {}</p>\n\
+         <p class=\"meta\">Hash: <span class=\"hash\">{}</span><br>Previous: {}</p>\n\
+         <article>\n{}</article>\n<nav class=\"pager\">",
+        escape(&entry.content.summary),
+        escape(index_href),
+        escape(&entry.filename),
+        entry.content.date.format(DATE_FORMAT),
+        escape(&entry.hash),
+        previous_hash,
+        render_markdown(&entry.content.body)
+    );
+
+    match previous {
+        Some(prev) => body.push_str(&format!(
+            "<a href=\"{}\">← {}</a>",
+            escape(&entry_href(prev)),
+            escape(&prev.filename)
+        )),
+        None => body.push_str("<span></span>"),
+    }
+    if let Some(next) = next {
+        body.push_str(&format!(
+            "<a href=\"{}\">{} →</a>",
+            escape(&entry_href(next)),
+            escape(&next.filename)
+        ));
+    }
+    body.push_str("</nav>\n");
+
+    page(&entry.content.summary, &body)
+}
+
+/// Chain-integrity table: every entry with its hash and the `Previous:` hash
+/// it records, marked as linked when that matches the preceding entry.
+pub fn chain_page(
+    entries: &[Entry],
+    badge: &str,
+    index_href: &str,
+    entry_href: impl Fn(&Entry) -> String,
+) -> String {
+    let mut body = format!(
+        "<header><h1>Chain integrity</h1>{}</header>\n<p><a href=\"{}\">All entries</a></p>\n\
+         <table>\n<tr><th>Entry</th><th>Hash</th><th>Previous</th><th>Link</th></tr>\n",
+        badge,
+        escape(index_href)
+    );
+
+    let mut expected_previous = "none";
+    for entry in entries {
+        let link = if entry.content.previous == expected_previous {
+            "✓"
+        } else {
+            "✗ does not match the preceding entry"
+        };
+        body.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}</a></td><td class=\"hash\">{}</td><td class=\"hash\">{}</td><td>{}</td></tr>\n",
+            escape(&entry_href(entry)),
+            escape(&entry.filename),
+            escape(&entry.hash),
+            escape(&entry.content.previous),
+            link
+        ));
+        expected_previous = &entry.hash;
+    }
+
+    body.push_str("</table>\n");
+    page("Chain integrity", &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let html = render_markdown("## Changes\n- Added `jwt.rs`\n");
+        assert!(html.contains("<h2>Changes</h2>"));
+        assert!(html.contains("<li>Added <code>jwt.rs</code></li>"));
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_raw_html() {
+        let html = render_markdown("<script>alert(1)</script>\n");
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_render_markdown_drops_unsafe_urls() {
+        let html = render_markdown(
+            "[x](javascript:alert(document.cookie)) ![i](data:text/html,hi) <JavaScript:alert(1)>\n",
+        );
+        assert!(!html.contains("href=\"javascript"), "{}", html);
+        assert!(!html.contains("href=\"JavaScript"));
+        assert!(html.contains("<img src=\"\" alt=\"i\" />"));
+        assert!(html.contains("<a href=\"\">x</a>"));
+
+        let html =
+            render_markdown("[a](https://example.com) [b](../b.html#top) [c](mailto:a@b.c)\n");
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("href=\"../b.html#top\""));
+        assert!(html.contains("href=\"mailto:a@b.c\""));
+    }
+
+    #[test]
+    fn test_safe_url() {
+        assert!(safe_url("entries/000001_abcd1234.html"));
+        assert!(safe_url("#section"));
+        assert!(safe_url("/path?q=a:b"));
+        assert!(safe_url("HTTPS://example.com"));
+        assert!(!safe_url("java\tscript:alert(1)"));
+        assert!(!safe_url(" vbscript:x"));
+        assert!(!safe_url("file:///etc/passwd"));
+    }
+}
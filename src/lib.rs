@@ -0,0 +1,34 @@
+//! Persistent, tamper-evident memory for AI coding agents.
+//!
+//! [`Repository`] is the entry point: open or initialize a repository, commit
+//! the draft, verify the hash chain, and read entries back. Entry and draft
+//! formats live in [`engram`], errors in [`error`]. The `engram` binary is a
+//! thin command-line layer over this crate.
+
+mod cli;
+pub(crate) mod commands;
+pub mod engram;
+pub mod error;
+pub(crate) mod html;
+pub(crate) mod mcp;
+pub mod repository;
+pub(crate) mod server;
+pub(crate) mod templates;
+pub(crate) mod tui;
+pub(crate) mod utils;
+
+pub use commands::commit::CommitResult;
+pub use commands::init::{InitAction, InitMode, InitOptions, InitReport};
+pub use commands::status::{DraftStatus, StatusReport, WorklogInfo};
+pub use commands::verify::{VerifyError, VerifyResult};
+pub use commands::wrappers::WrapperMode;
+pub use error::EngramError;
+pub use repository::{Entry, Repository};
+pub use utils::clock::{Clock, FixedClock, SystemClock};
+
+/// Run the `engram` command line with the process arguments and return its
+/// exit code. Used by the binary; not part of the library API.
+#[doc(hidden)]
+pub fn run_cli() -> i32 {
+    cli::app::run()
+}
@@ -0,0 +1,502 @@
+//! Model Context Protocol server over stdio.
+//!
+//! Speaks newline-delimited JSON-RPC 2.0. Tools and resources are thin
+//! wrappers over [`Repository`]; the repository is reopened per request so the
+//! server keeps working across `engram init` or external edits.
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::engram::worklog::DATE_FORMAT;
+use crate::error::EngramError;
+use crate::repository::Repository;
+
+/// Protocol revision answered when the client asks for none, or for one not
+/// in `SUPPORTED_PROTOCOL_VERSIONS`
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Revisions whose tools and resources methods this server implements
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = ["2024-11-05", "2025-03-26", PROTOCOL_VERSION];
+
+/// URI of the SUMMARY.md resource
+const SUMMARY_URI: &str = "engram://summary";
+/// Prefix of per-entry resource URIs (followed by the entry filename)
+const ENTRY_URI_PREFIX: &str = "engram://entries/";
+
+/// Tools in the order `tools/list` reports them
+const TOOL_NAMES: [&str; 8] = [
+    "read_draft",
+    "write_draft",
+    "commit",
+    "verify",
+    "status",
+    "log",
+    "show",
+    "search",
+];
+
+/// Default number of entries returned by `log` and `search`
+const DEFAULT_LIMIT: u64 = 20;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// An MCP server for the Engram repository at `root`
+pub struct McpServer {
+    root: PathBuf,
+}
+
+impl McpServer {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        McpServer {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Serve requests from `input` until it closes, writing one response per line to `output`
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one JSON-RPC message. Notifications produce no response.
+    pub fn handle_line(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "missing method",
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = self.dispatch(method, &params);
+
+        // Notifications (no id) never get a response
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": params
+                    .get("protocolVersion")
+                    .and_then(Value::as_str)
+                    .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+                    .unwrap_or(PROTOCOL_VERSION),
+                "capabilities": {"tools": {}, "resources": {}},
+                "serverInfo": {"name": "engram", "version": env!("CARGO_PKG_VERSION")},
+            })),
+            "ping" => Ok(json!({})),
+            method if method.starts_with("notifications/") => Ok(Value::Null),
+            "tools/list" => Ok(json!({"tools": tool_definitions()})),
+            "tools/call" => {
+                let name = params
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                self.call_tool(name, &arguments)
+            }
+            "resources/list" => Ok(json!({"resources": self.list_resources()})),
+            "resources/read" => {
+                let uri = params
+                    .get("uri")
+                    .and_then(Value::as_str)
+                    .ok_or((INVALID_PARAMS, "missing uri".to_string()))?;
+                self.read_resource(uri)
+                    .map_err(|e| (INVALID_PARAMS, e.to_string()))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        }
+    }
+
+    /// Run a tool. Engram failures are reported in the result (`isError`) as MCP
+    /// requires; an unknown tool or missing argument is a protocol error.
+    fn call_tool(&self, name: &str, arguments: &Value) -> Result<Value, (i64, String)> {
+        match self.run_tool(name, arguments) {
+            Ok(Value::String(text)) => Ok(tool_result(text, false)),
+            Ok(value) => Ok(tool_result(to_pretty(&value), false)),
+            Err(ToolError::Engram(e)) => Ok(tool_result(to_pretty(&e.to_json()), true)),
+            Err(ToolError::InvalidParams(message)) => Err((INVALID_PARAMS, message)),
+        }
+    }
+
+    fn run_tool(&self, name: &str, arguments: &Value) -> Result<Value, ToolError> {
+        if !TOOL_NAMES.contains(&name) {
+            return Err(ToolError::InvalidParams(format!("unknown tool '{}'", name)));
+        }
+
+        let repo = Repository::open(&self.root)?;
+        let limit = arguments
+            .get("limit")
+            .and_then(Value::as_u64)
+            .unwrap_or(DEFAULT_LIMIT) as usize;
+
+        match name {
+            "read_draft" => Ok(Value::String(repo.read_draft()?)),
+            "write_draft" => {
+                let content = string_argument(arguments, "content")?;
+                repo.write_draft(content)?;
+                Ok(Value::String("Draft written.".to_string()))
+            }
+            "commit" => Ok(repo.commit()?.to_json()),
+            "verify" => Ok(repo.verify()?.to_json()),
+            "status" => Ok(repo.status()?.to_json()),
+            "log" => {
+                let entries: Vec<Value> = repo
+                    .entries()?
+                    .iter()
+                    .rev()
+                    .take(limit)
+                    .map(|entry| {
+                        json!({
+                            "sequence": entry.sequence,
+                            "filename": entry.filename,
+                            "date": entry.content.date.format(DATE_FORMAT).to_string(),
+                            "summary": entry.content.summary,
+                        })
+                    })
+                    .collect();
+                Ok(Value::Array(entries))
+            }
+            "show" => Ok(repo
+                .find_entry(string_argument(arguments, "entry")?)?
+                .to_json()),
+            "search" => {
+                let query = string_argument(arguments, "query")?;
+                let matches: Vec<Value> = repo
+                    .search(query)?
+                    .iter()
+                    .take(limit)
+                    .map(|entry| json!({"filename": entry.filename, "summary": entry.content.summary}))
+                    .collect();
+                Ok(Value::Array(matches))
+            }
+            _ => unreachable!("tool names are checked above"),
+        }
+    }
+
+    fn list_resources(&self) -> Vec<Value> {
+        let Ok(repo) = Repository::open(&self.root) else {
+            return Vec::new();
+        };
+
+        let mut resources = vec![json!({
+            "uri": SUMMARY_URI,
+            "name": "SUMMARY.md",
+            "description": "Index of all worklog entries",
+            "mimeType": "text/markdown",
+        })];
+        for entry in repo.entries().unwrap_or_default() {
+            resources.push(json!({
+                "uri": format!("{}{}", ENTRY_URI_PREFIX, entry.filename),
+                "name": entry.filename,
+                "description": entry.content.summary,
+                "mimeType": "text/markdown",
+            }));
+        }
+        resources
+    }
+
+    fn read_resource(&self, uri: &str) -> Result<Value, EngramError> {
+        let repo = Repository::open(&self.root)?;
+        let text = if uri == SUMMARY_URI {
+            std::fs::read_to_string(repo.summary_path())?
+        } else if let Some(filename) = uri.strip_prefix(ENTRY_URI_PREFIX) {
+            let entry = repo.find_entry(filename)?;
+            if entry.filename != filename {
+                return Err(EngramError::EntryNotFound(filename.to_string()));
+            }
+            std::fs::read_to_string(entry.path)?
+        } else {
+            return Err(EngramError::EntryNotFound(uri.to_string()));
+        };
+
+        Ok(json!({
+            "contents": [{"uri": uri, "mimeType": "text/markdown", "text": text}]
+        }))
+    }
+}
+
+fn tool_definitions() -> Value {
+    let no_arguments = json!({"type": "object", "properties": {}});
+    json!([
+        {
+            "name": "read_draft",
+            "description": "Read .engram/draft.md, the working report for uncommitted work",
+            "inputSchema": no_arguments,
+        },
+        {
+            "name": "write_draft",
+            "description": "Replace .engram/draft.md. Keep the <summary></summary> tag and the section headings.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"content": {"type": "string", "description": "Full draft text"}},
+                "required": ["content"],
+            },
+        },
+        {
+            "name": "commit",
+            "description": "Commit the draft as a new hash-linked worklog entry and reset the draft",
+            "inputSchema": no_arguments,
+        },
+        {
+            "name": "verify",
+            "description": "Verify the hash chain, sequence numbering and entry dates",
+            "inputSchema": no_arguments,
+        },
+        {
+            "name": "status",
+            "description": "Entry count, latest entry, draft state and chain status",
+            "inputSchema": no_arguments,
+        },
+        {
+            "name": "log",
+            "description": "List recent entries, newest first",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"limit": {"type": "integer", "description": "Maximum entries (default 20)"}},
+            },
+        },
+        {
+            "name": "show",
+            "description": "Show one entry by sequence number, filename or hash prefix",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"entry": {"type": "string", "description": "e.g. 3, 000003_a1b2c3d4.md or a1b2c3d4"}},
+                "required": ["entry"],
+            },
+        },
+        {
+            "name": "search",
+            "description": "Find entries whose summary or body contains the query (case-insensitive)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "limit": {"type": "integer", "description": "Maximum matches (default 20)"},
+                },
+                "required": ["query"],
+            },
+        },
+    ])
+}
+
+/// Why a tool call failed
+enum ToolError {
+    /// The operation itself failed; reported to the client as a tool result
+    Engram(EngramError),
+    /// The call was malformed; reported as a JSON-RPC error
+    InvalidParams(String),
+}
+
+impl From<EngramError> for ToolError {
+    fn from(error: EngramError) -> Self {
+        ToolError::Engram(error)
+    }
+}
+
+fn string_argument<'a>(arguments: &'a Value, name: &str) -> Result<&'a str, ToolError> {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| ToolError::InvalidParams(format!("missing string argument '{}'", name)))
+}
+
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({"content": [{"type": "text", "text": text}], "isError": is_error})
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+fn to_pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).expect("JSON values always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use tempfile::tempdir;
+
+    fn request(server: &McpServer, id: u64, method: &str, params: Value) -> Value {
+        let line = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        server.handle_line(&line.to_string()).unwrap()
+    }
+
+    fn call(server: &McpServer, name: &str, arguments: Value) -> (String, bool) {
+        let response = request(
+            server,
+            1,
+            "tools/call",
+            json!({"name": name, "arguments": arguments}),
+        );
+        let result = &response["result"];
+        (
+            result["content"][0]["text"].as_str().unwrap().to_string(),
+            result["isError"].as_bool().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let dir = tempdir().unwrap();
+        let server = McpServer::new(dir.path());
+
+        let response = request(&server, 1, "initialize", json!({}));
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+        assert_eq!(response["result"]["serverInfo"]["name"], "engram");
+
+        let response = request(&server, 2, "tools/list", json!({}));
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, TOOL_NAMES);
+    }
+
+    #[test]
+    fn test_initialize_negotiates_protocol_version() {
+        let dir = tempdir().unwrap();
+        let server = McpServer::new(dir.path());
+
+        let response = request(
+            &server,
+            1,
+            "initialize",
+            json!({"protocolVersion": "2024-11-05"}),
+        );
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+
+        let response = request(
+            &server,
+            2,
+            "initialize",
+            json!({"protocolVersion": "1999-01-01"}),
+        );
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let dir = tempdir().unwrap();
+        let server = McpServer::new(dir.path());
+        let line = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        assert!(server.handle_line(&line.to_string()).is_none());
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let dir = tempdir().unwrap();
+        let server = McpServer::new(dir.path());
+
+        let response = server.handle_line("{not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = request(&server, 3, "bogus", json!({}));
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 3);
+
+        let response = request(&server, 4, "tools/call", json!({"name": "rm"}));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_draft_commit_and_query_tools() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let server = McpServer::new(dir.path());
+
+        let (text, is_error) = call(&server, "commit", json!({}));
+        assert!(is_error);
+        assert!(text.contains("E_DRAFT_SUMMARY_EMPTY"));
+
+        let draft = "<summary>Add search</summary>\n\n## Intent\nFind entries by keyword";
+        let (_, is_error) = call(&server, "write_draft", json!({"content": draft}));
+        assert!(!is_error);
+        assert_eq!(call(&server, "read_draft", json!({})).0, draft);
+
+        let (text, is_error) = call(&server, "commit", json!({}));
+        assert!(!is_error, "{}", text);
+        assert!(text.contains("000001_"));
+
+        let (text, _) = call(&server, "verify", json!({}));
+        assert!(text.contains("\"entry_count\": 1"));
+
+        let (text, _) = call(&server, "log", json!({}));
+        assert!(text.contains("Add search"));
+
+        let (text, is_error) = call(&server, "show", json!({"entry": "1"}));
+        assert!(!is_error);
+        assert!(text.contains("Find entries by keyword"));
+
+        let (text, _) = call(&server, "search", json!({"query": "KEYWORD"}));
+        assert!(text.contains("Add search"));
+        let (text, _) = call(&server, "search", json!({"query": "absent"}));
+        assert_eq!(text, "[]");
+    }
+
+    #[test]
+    fn test_resources() {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let server = McpServer::new(dir.path());
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.write_draft("<summary>First</summary>\n\nBody")
+            .unwrap();
+        let committed = repo.commit().unwrap();
+
+        let response = request(&server, 1, "resources/list", json!({}));
+        let resources = response["result"]["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0]["uri"], SUMMARY_URI);
+
+        let uri = format!("{}{}", ENTRY_URI_PREFIX, committed.filename);
+        assert_eq!(resources[1]["uri"], uri.as_str());
+
+        let response = request(&server, 2, "resources/read", json!({"uri": uri}));
+        let text = response["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with("Summary: First\n"));
+
+        let response = request(&server, 3, "resources/read", json!({"uri": SUMMARY_URI}));
+        let text = response["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.contains(&committed.filename));
+
+        let response = request(
+            &server,
+            4,
+            "resources/read",
+            json!({"uri": "engram://nope"}),
+        );
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+}
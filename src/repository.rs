@@ -0,0 +1,506 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::commit::{run_commit_in_dir, validate_draft_in_dir, CommitResult};
+use crate::commands::init::{run_init_in_dir, InitOptions, InitReport};
+use crate::commands::status::{run_status_in_dir, StatusReport};
+use crate::commands::verify::{collect_entries, verify_chain_in_dir, VerifyResult};
+use crate::engram::draft::Draft;
+use crate::engram::refs::Reference;
+use crate::engram::summary::{rebuild, row_text};
+use crate::engram::worklog::{EntryContent, DATE_FORMAT};
+use crate::error::EngramError;
+use crate::utils::clock::{self, Clock, SystemClock};
+use crate::utils::hash::sha256_hex;
+
+const ENGRAM_DIR: &str = ".engram";
+const DRAFT_FILE: &str = ".engram/draft.md";
+const WORKLOG_DIR: &str = ".engram/worklog";
+const SUMMARY_FILE: &str = ".engram/worklog/SUMMARY.md";
+
+/// Shortest hash prefix accepted as an entry reference
+const MIN_HASH_PREFIX: usize = 4;
+
+/// A committed worklog entry read back from disk
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub sequence: u32,
+    pub filename: String, // "000002_e5f6a7b8.md"
+    pub hash: String,     // full SHA256 of the entry bytes
+    pub content: EntryContent,
+    pub path: PathBuf,
+}
+
+impl Entry {
+    /// JSON form used by `--json` and the MCP server
+    pub fn to_json(&self) -> Value {
+        json!({
+            "sequence": self.sequence,
+            "filename": self.filename,
+            "hash": self.hash,
+            "previous": self.content.previous,
+            "date": self.content.date.format(DATE_FORMAT).to_string(),
+            "summary": self.content.summary,
+            "tags": self.content.tags(),
+            "refs": self
+                .content
+                .refs()
+                .iter()
+                .map(Reference::as_str)
+                .collect::<Vec<_>>(),
+            "headers": self.headers_json(),
+            "body": self.content.body,
+        })
+    }
+
+    /// Extra headers (Tags, Refs, ...) as a JSON object
+    pub fn headers_json(&self) -> Value {
+        self.content
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    /// Whether `query` appears in the summary or body, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.content.summary.to_lowercase().contains(&query)
+            || self.content.body.to_lowercase().contains(&query)
+    }
+}
+
+/// An Engram repository rooted at the directory that contains `.engram/`
+#[derive(Debug, Clone)]
+pub struct Repository {
+    root: PathBuf,
+}
+
+impl Repository {
+    /// Open an initialized repository
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, EngramError> {
+        let root = root.as_ref();
+        if !root.join(ENGRAM_DIR).is_dir() || !root.join(WORKLOG_DIR).is_dir() {
+            return Err(EngramError::NotInitialized);
+        }
+
+        Ok(Repository {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Initialize Engram in `root` and open the result
+    pub fn init(
+        root: impl AsRef<Path>,
+        options: InitOptions,
+    ) -> Result<(Self, InitReport), EngramError> {
+        let report = run_init_in_dir(root.as_ref(), options)?;
+        Ok((Repository::open(root)?, report))
+    }
+
+    /// Directory that contains `.engram/`
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of `.engram/draft.md`
+    pub fn draft_path(&self) -> PathBuf {
+        self.root.join(DRAFT_FILE)
+    }
+
+    /// Path of `.engram/worklog/`
+    pub fn worklog_path(&self) -> PathBuf {
+        self.root.join(WORKLOG_DIR)
+    }
+
+    /// Path of `.engram/worklog/SUMMARY.md`
+    pub fn summary_path(&self) -> PathBuf {
+        self.root.join(SUMMARY_FILE)
+    }
+
+    /// Rewrite SUMMARY.md from the entries on disk
+    pub(crate) fn rebuild_summary(&self) -> Result<(), EngramError> {
+        let rows: Vec<(String, String)> = self
+            .entries()?
+            .into_iter()
+            .map(|entry| {
+                let text = row_text(&entry.content.summary, &entry.content.tags());
+                (entry.filename, text)
+            })
+            .collect();
+        rebuild(&self.summary_path(), &rows)?;
+        Ok(())
+    }
+
+    /// Current contents of the draft
+    pub fn read_draft(&self) -> Result<String, EngramError> {
+        let path = self.draft_path();
+        if !path.exists() {
+            return Err(EngramError::DraftMissing);
+        }
+        Ok(fs::read_to_string(path)?)
+    }
+
+    /// Replace the draft with `content`
+    pub fn write_draft(&self, content: &str) -> Result<(), EngramError> {
+        Ok(fs::write(self.draft_path(), content)?)
+    }
+
+    /// Check the draft as commit would, without committing
+    pub fn validate_draft(&self) -> Result<Draft, EngramError> {
+        validate_draft_in_dir(&self.root)
+    }
+
+    /// Commit the draft, dated by SOURCE_DATE_EPOCH when set or the system clock otherwise
+    pub fn commit(&self) -> Result<CommitResult, EngramError> {
+        let clock = clock::from_env()?;
+        self.commit_with_clock(clock.as_ref())
+    }
+
+    /// Commit the draft, dated by `clock`
+    pub fn commit_with_clock(&self, clock: &dyn Clock) -> Result<CommitResult, EngramError> {
+        run_commit_in_dir(&self.root, clock)
+    }
+
+    /// Verify the hash chain, sequence numbering and dates against the system clock
+    pub fn verify(&self) -> Result<VerifyResult, EngramError> {
+        self.verify_with_clock(&SystemClock)
+    }
+
+    /// Verify the hash chain, sequence numbering and dates; no entry may be
+    /// dated more than the allowed skew past `clock`
+    pub fn verify_with_clock(&self, clock: &dyn Clock) -> Result<VerifyResult, EngramError> {
+        verify_chain_in_dir(&self.root, clock)
+    }
+
+    /// Worklog, draft and chain status
+    pub fn status(&self) -> Result<StatusReport, EngramError> {
+        run_status_in_dir(&self.root)
+    }
+
+    /// All entries in sequence order. Does not verify the chain.
+    pub fn entries(&self) -> Result<Vec<Entry>, EngramError> {
+        let mut worklog_entries = collect_entries(&self.worklog_path())?;
+        worklog_entries.sort_by(|a, b| (a.sequence, &a.filename).cmp(&(b.sequence, &b.filename)));
+
+        let mut entries = Vec::with_capacity(worklog_entries.len());
+        for entry in worklog_entries {
+            let bytes = fs::read(&entry.path)?;
+            let hash = sha256_hex(&bytes);
+            let content = String::from_utf8(bytes)
+                .ok()
+                .and_then(|text| EntryContent::parse(&text))
+                .ok_or_else(|| EngramError::InvalidEntry(entry.filename.clone()))?;
+
+            entries.push(Entry {
+                sequence: entry.sequence,
+                filename: entry.filename,
+                hash,
+                content,
+                path: entry.path,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Find an entry by sequence number (`3` or `000003`), filename, or hash prefix.
+    /// A sequence number wins over a hash prefix made of the same digits; a
+    /// hash prefix shared by several entries is an error, never a guess.
+    pub fn find_entry(&self, reference: &str) -> Result<Entry, EngramError> {
+        let reference = reference.trim();
+        let mut entries = self.entries()?;
+
+        let by_sequence = reference
+            .parse::<u32>()
+            .ok()
+            .and_then(|sequence| entries.iter().position(|e| e.sequence == sequence));
+        if let Some(index) =
+            by_sequence.or_else(|| entries.iter().position(|e| e.filename == reference))
+        {
+            return Ok(entries.swap_remove(index));
+        }
+
+        let prefix = reference.to_lowercase();
+        let is_hash =
+            prefix.len() >= MIN_HASH_PREFIX && prefix.chars().all(|c| c.is_ascii_hexdigit());
+        if is_hash {
+            entries.retain(|e| e.hash.starts_with(&prefix));
+        } else {
+            entries.clear();
+        }
+        match entries.len() {
+            0 => Err(EngramError::EntryNotFound(reference.to_string())),
+            1 => Ok(entries.remove(0)),
+            _ => Err(EngramError::AmbiguousEntry {
+                reference: reference.to_string(),
+                matches: entries.into_iter().map(|e| e.filename).collect(),
+            }),
+        }
+    }
+
+    /// Every tag with the number of entries carrying it, most used first
+    pub fn tag_counts(&self) -> Result<Vec<(String, usize)>, EngramError> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for entry in self.entries()? {
+            for tag in entry.content.tags() {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(counts)
+    }
+
+    /// Entries tagged `tag` (case-insensitive), in sequence order
+    pub fn entries_with_tag(&self, tag: &str) -> Result<Vec<Entry>, EngramError> {
+        let tag = tag.to_lowercase();
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.content.tags().contains(&tag))
+            .collect())
+    }
+
+    /// Entries whose `Refs:` name `entry`, in sequence order
+    pub fn backlinks(&self, entry: &Entry) -> Result<Vec<Entry>, EngramError> {
+        let target = Reference::Entry(entry.hash.clone());
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|other| other.content.refs().contains(&target))
+            .collect())
+    }
+
+    /// Entries whose summary or body contains `query`, ignoring case
+    pub fn search(&self, query: &str) -> Result<Vec<Entry>, EngramError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.matches(query))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::status::DraftStatus;
+    use crate::engram::draft::DraftError;
+    use crate::utils::clock::FixedClock;
+    use chrono::{TimeZone, Utc};
+    use tempfile::tempdir;
+
+    fn write_draft(repo: &Repository, summary: &str) {
+        fs::write(
+            repo.draft_path(),
+            format!("<summary>{}</summary>\n\n## Intent\nDo it", summary),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_open_requires_initialization() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            Repository::open(dir.path()),
+            Err(EngramError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_init_twice_fails() {
+        let dir = tempdir().unwrap();
+        Repository::init(dir.path(), InitOptions::default()).unwrap();
+        assert!(matches!(
+            Repository::init(dir.path(), InitOptions::default()),
+            Err(EngramError::AlreadyInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_commit_verify_and_iterate() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap());
+        write_draft(&repo, "First");
+        let first = repo.commit_with_clock(&clock).unwrap();
+        write_draft(&repo, "Second");
+        repo.commit_with_clock(&clock).unwrap();
+
+        assert_eq!(repo.verify().unwrap().entry_count, 2);
+
+        let entries = repo.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].filename, first.filename);
+        assert_eq!(entries[0].content.summary, "First");
+        assert_eq!(entries[1].sequence, 2);
+        assert_eq!(entries[1].content.previous, entries[0].hash);
+        assert_eq!(entries[1].content.date, clock.0);
+    }
+
+    #[test]
+    fn test_find_entry_and_search() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap());
+        write_draft(&repo, "Add login form");
+        repo.commit_with_clock(&clock).unwrap();
+        write_draft(&repo, "Fix token expiry");
+        let second = repo.commit_with_clock(&clock).unwrap();
+
+        let entry = repo.find_entry("2").unwrap();
+        assert_eq!(entry.filename, second.filename);
+        assert_eq!(repo.find_entry("000002").unwrap().filename, second.filename);
+        assert_eq!(repo.find_entry(&second.filename).unwrap().sequence, 2);
+        assert_eq!(repo.find_entry(&entry.hash[..8]).unwrap().sequence, 2);
+        assert!(matches!(
+            repo.find_entry("7"),
+            Err(EngramError::EntryNotFound(_))
+        ));
+
+        let found = repo.search("TOKEN").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].content.summary, "Fix token expiry");
+        assert_eq!(repo.search("do it").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_find_entry_rejects_ambiguous_prefix() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+
+        // Two entry texts whose hashes share the shortest accepted prefix
+        let mut seen: BTreeMap<String, String> = BTreeMap::new();
+        let (first, second) = (0..)
+            .find_map(|i| {
+                let text = EntryContent {
+                    summary: format!("Entry {}", i),
+                    previous: "none".to_string(),
+                    date: Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap(),
+                    headers: Vec::new(),
+                    body: "## Intent\nDo it\n".to_string(),
+                }
+                .to_string();
+                let prefix = sha256_hex(&text)[..MIN_HASH_PREFIX].to_string();
+                seen.insert(prefix, text.clone())
+                    .map(|earlier| (earlier, text))
+            })
+            .unwrap();
+        for (sequence, text) in [(1, &first), (2, &second)] {
+            let name = format!("{:06}_{}.md", sequence, &sha256_hex(text)[..8]);
+            fs::write(repo.worklog_path().join(name), text).unwrap();
+        }
+
+        let prefix = &sha256_hex(&first)[..MIN_HASH_PREFIX];
+        match repo.find_entry(prefix) {
+            Err(EngramError::AmbiguousEntry { reference, matches }) => {
+                assert_eq!(reference, prefix);
+                assert_eq!(matches.len(), 2);
+            }
+            other => panic!("expected an ambiguity error, got {:?}", other),
+        }
+        assert_eq!(repo.find_entry(&sha256_hex(&second)).unwrap().sequence, 2);
+    }
+
+    #[test]
+    fn test_tags_recorded_and_counted() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap());
+        for (summary, tags) in [("One", "auth, bugfix"), ("Two", "auth"), ("Three", "")] {
+            fs::write(
+                repo.draft_path(),
+                format!(
+                    "<summary>{}</summary>\nTags: {}\n\n## Intent\nDo it",
+                    summary, tags
+                ),
+            )
+            .unwrap();
+            repo.commit_with_clock(&clock).unwrap();
+        }
+
+        let entries = repo.entries().unwrap();
+        assert_eq!(entries[0].content.header("Tags"), Some("auth, bugfix"));
+        assert!(entries[2].content.headers.is_empty());
+        assert_eq!(
+            repo.tag_counts().unwrap(),
+            vec![("auth".to_string(), 2), ("bugfix".to_string(), 1)]
+        );
+        assert_eq!(repo.entries_with_tag("AUTH").unwrap().len(), 2);
+        assert!(repo.verify().is_ok());
+
+        let summary = fs::read_to_string(repo.summary_path()).unwrap();
+        assert!(summary.contains("| One `auth` `bugfix` |"));
+        assert!(summary.contains("| Three |"));
+    }
+
+    #[test]
+    fn test_refs_validated_and_backlinked() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap());
+        write_draft(&repo, "Add login");
+        repo.commit_with_clock(&clock).unwrap();
+        let login = repo.find_entry("1").unwrap();
+
+        let unknown = "c".repeat(64);
+        fs::write(
+            repo.draft_path(),
+            format!("<summary>Bad</summary>\nRefs: {}\n\n## Intent\nx", unknown),
+        )
+        .unwrap();
+        assert!(matches!(
+            repo.commit_with_clock(&clock),
+            Err(EngramError::Draft(DraftError::UnknownRef(hash))) if hash == unknown
+        ));
+
+        for summary in ["Revert login", "Fix login"] {
+            fs::write(
+                repo.draft_path(),
+                format!(
+                    "<summary>{}</summary>\nRefs: {}, #12\n\n## Intent\nx",
+                    summary, login.hash
+                ),
+            )
+            .unwrap();
+            repo.commit_with_clock(&clock).unwrap();
+        }
+
+        let backlinks = repo.backlinks(&login).unwrap();
+        assert_eq!(
+            backlinks.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(repo.verify().is_ok());
+    }
+
+    #[test]
+    fn test_entries_rejects_unparsable_entry() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+        fs::write(repo.worklog_path().join("000001_deadbeef.md"), "garbage").unwrap();
+
+        match repo.entries() {
+            Err(EngramError::InvalidEntry(filename)) => assert_eq!(filename, "000001_deadbeef.md"),
+            other => panic!("expected InvalidEntry, got {:?}", other.map(|e| e.len())),
+        }
+    }
+
+    #[test]
+    fn test_status_reports_draft_and_chain() {
+        let dir = tempdir().unwrap();
+        let (repo, _) = Repository::init(dir.path(), InitOptions::default()).unwrap();
+        write_draft(&repo, "Pending");
+
+        let status = repo.status().unwrap();
+        assert_eq!(status.worklog.entry_count, 0);
+        assert_eq!(status.draft, DraftStatus::HasContent("Pending".to_string()));
+        assert!(status.chain.is_ok());
+    }
+}
@@ -0,0 +1,448 @@
+//! Read-only HTTP server for browsing the worklog (`engram serve`).
+//!
+//! Binds to 127.0.0.1 only and answers GET/HEAD; nothing it serves can modify
+//! the repository. Each connection gets its own thread and a read timeout, so
+//! an idle client cannot stall other requests, and requests must name the
+//! server by a loopback `Host` so a DNS-rebinding page cannot read the worklog.
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::EngramError;
+use crate::html;
+use crate::repository::{Entry, Repository};
+
+/// Address the server binds to. Never a public interface.
+const LOCALHOST: &str = "127.0.0.1";
+
+/// How often the timeline's verify badge refreshes itself
+const BADGE_REFRESH_MS: u32 = 10_000;
+
+/// How long a connection may sit idle before it is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest request line or header line accepted, in bytes
+const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// Most header bytes accepted for one request
+const MAX_HEADER_BYTES: usize = 32 * 1024;
+
+/// An HTTP response ready to be written
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn html(status: u16, body: String) -> Self {
+        Response {
+            status,
+            content_type: "text/html; charset=utf-8",
+            body,
+        }
+    }
+
+    fn json(status: u16, value: &Value) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_string_pretty(value).expect("JSON values always serialize"),
+        }
+    }
+
+    fn not_found() -> Self {
+        Response::html(404, html::page("Not found", "<h1>Not found</h1>\n"))
+    }
+
+    fn bad_request() -> Self {
+        Response::html(400, html::page("Bad request", "<h1>Bad request</h1>\n"))
+    }
+
+    fn misdirected() -> Self {
+        Response::html(
+            421,
+            html::page("Misdirected request", "<h1>Misdirected request</h1>\n"),
+        )
+    }
+}
+
+/// Serves the repository at `root`
+pub struct WebServer {
+    root: PathBuf,
+}
+
+impl WebServer {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        WebServer {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Bind to localhost. Port 0 picks a free port.
+    pub fn bind(port: u16) -> io::Result<TcpListener> {
+        TcpListener::bind((LOCALHOST, port))
+    }
+
+    /// Answer connections until the listener fails, one thread per connection
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        let port = listener.local_addr()?.port();
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = WebServer::new(&self.root);
+            // A misbehaving client must not take the server down
+            thread::spawn(move || server.handle_connection(stream, port));
+        }
+        Ok(())
+    }
+
+    fn handle_connection(&self, stream: TcpStream, port: u16) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let Some(request_line) = read_line_limited(&mut reader)? else {
+            return write_response(stream, &Response::bad_request(), false);
+        };
+
+        // Keep only the Host header; requests never carry a body we care about
+        let mut host = None;
+        let mut header_bytes = 0;
+        loop {
+            let Some(header) = read_line_limited(&mut reader)? else {
+                return write_response(stream, &Response::bad_request(), false);
+            };
+            header_bytes += header.len();
+            if header_bytes > MAX_HEADER_BYTES {
+                return write_response(stream, &Response::bad_request(), false);
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("host") {
+                    host = Some(value.trim().to_string());
+                }
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let target = parts.next().unwrap_or("/");
+        let path = target.split(['?', '#']).next().unwrap_or("/");
+
+        let response = if host_allowed(host.as_deref(), port) {
+            self.respond(method, path)
+        } else {
+            Response::misdirected()
+        };
+        write_response(stream, &response, method == "HEAD")
+    }
+
+    /// Route a request. Only GET and HEAD are allowed.
+    pub fn respond(&self, method: &str, path: &str) -> Response {
+        if method != "GET" && method != "HEAD" {
+            return Response::json(
+                405,
+                &json!({"error": {"code": "E_READ_ONLY", "message": "The worklog server is read-only"}}),
+            );
+        }
+
+        let repo = match Repository::open(&self.root) {
+            Ok(repo) => repo,
+            Err(e) => return error_response(&e),
+        };
+
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let result = match segments.as_slice() {
+            [""] => self.timeline(&repo),
+            ["entries", sequence] => self.entry_page(&repo, sequence),
+            ["api", "entries"] => repo.entries().map(|entries| {
+                let entries: Vec<Value> = entries.iter().map(Entry::to_json).collect();
+                Response::json(200, &Value::Array(entries))
+            }),
+            ["api", "entries", sequence] => {
+                find_by_sequence(&repo, sequence).map(|found| match found {
+                    Some((entries, index)) => Response::json(200, &entries[index].to_json()),
+                    None => Response::json(
+                        404,
+                        &EngramError::EntryNotFound(sequence.to_string()).to_json(),
+                    ),
+                })
+            }
+            ["api", "verify"] => Ok(match repo.verify() {
+                Ok(result) => Response::json(200, &result.to_json()),
+                Err(e) => Response::json(
+                    200,
+                    &json!({"verified": false, "error": e.to_json()["error"]}),
+                ),
+            }),
+            _ => Ok(Response::not_found()),
+        };
+
+        result.unwrap_or_else(|e| error_response(&e))
+    }
+
+    fn timeline(&self, repo: &Repository) -> Result<Response, EngramError> {
+        let entries = repo.entries()?;
+        let badge = html::verify_badge(&repo.verify());
+        let script = format!(
+            "<script>\nsetInterval(function () {{\n  fetch('/api/verify').then(function (r) {{ return r.json(); }}).then(function (v) {{\n    var badge = document.getElementById('verify-badge');\n    badge.className = 'badge ' + (v.verified ? 'ok' : 'broken');\n    badge.textContent = v.verified ? '✓ Chain verified (' + v.entry_count + ' entries)' : '✗ ' + v.error.code;\n  }});\n}}, {});\n</script>\n",
+            BADGE_REFRESH_MS
+        );
+        Ok(Response::html(
+            200,
+            html::timeline(&entries, &badge, entry_href, &script),
+        ))
+    }
+
+    fn entry_page(&self, repo: &Repository, sequence: &str) -> Result<Response, EngramError> {
+        let Some((entries, index)) = find_by_sequence(repo, sequence)? else {
+            return Ok(Response::not_found());
+        };
+
+        let previous = index.checked_sub(1).map(|i| &entries[i]);
+        let next = entries.get(index + 1);
+        Ok(Response::html(
+            200,
+            html::entry_page(&entries[index], previous, next, "/", entry_href),
+        ))
+    }
+}
+
+fn entry_href(entry: &Entry) -> String {
+    format!("/entries/{}", entry.sequence)
+}
+
+/// All entries plus the index of the one with this sequence number, if any
+fn find_by_sequence(
+    repo: &Repository,
+    sequence: &str,
+) -> Result<Option<(Vec<Entry>, usize)>, EngramError> {
+    let Ok(sequence) = sequence.parse::<u32>() else {
+        return Ok(None);
+    };
+    let entries = repo.entries()?;
+    Ok(entries
+        .iter()
+        .position(|entry| entry.sequence == sequence)
+        .map(|index| (entries, index)))
+}
+
+/// Whether a `Host` header names this server by a loopback name and port
+fn host_allowed(host: Option<&str>, port: u16) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    let (name, host_port) = match host.rsplit_once(':') {
+        Some((name, host_port)) => (name, host_port.parse::<u16>().ok()),
+        None => (host, Some(80)),
+    };
+    let name = name.to_ascii_lowercase();
+    (name == "localhost" || name == LOCALHOST) && host_port == Some(port)
+}
+
+/// Read one line of at most `MAX_LINE_BYTES`. `None` when the line is too
+/// long, not UTF-8, or the client closed the connection before finishing it.
+fn read_line_limited(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = Vec::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE_BYTES)
+        .read_until(b'\n', &mut line)?;
+    if !line.ends_with(b"\n") {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(line).ok())
+}
+
+fn error_response(error: &EngramError) -> Response {
+    Response::json(500, &error.to_json())
+}
+
+fn write_response(mut stream: TcpStream, response: &Response, head_only: bool) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        421 => "Misdirected Request",
+        _ => "Internal Server Error",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+         X-Content-Type-Options: nosniff\r\nConnection: close\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    )?;
+    if response.status == 405 {
+        write!(stream, "Allow: GET, HEAD\r\n")?;
+    }
+    write!(stream, "\r\n")?;
+    if !head_only {
+        stream.write_all(response.body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::{run_init_in_dir, InitOptions};
+    use std::fs;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn repo_with_entries(summaries: &[&str]) -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        run_init_in_dir(dir.path(), InitOptions::default()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        for summary in summaries {
+            repo.write_draft(&format!(
+                "<summary>{}</summary>\n\n## Changes\n- Work",
+                summary
+            ))
+            .unwrap();
+            repo.commit().unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_timeline_and_entry_pages() {
+        let dir = repo_with_entries(&["First <b>entry</b>", "Second entry"]);
+        let server = WebServer::new(dir.path());
+
+        let response = server.respond("GET", "/");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("First &lt;b&gt;entry&lt;/b&gt;"));
+        assert!(response.body.contains("href=\"/entries/2\""));
+        assert!(response.body.contains("Chain verified (2 entries)"));
+
+        let response = server.respond("GET", "/entries/2");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("<li>Work</li>"));
+        assert!(response.body.contains("href=\"/entries/1\""));
+
+        assert_eq!(server.respond("GET", "/entries/9").status, 404);
+        assert_eq!(server.respond("GET", "/nope").status, 404);
+    }
+
+    #[test]
+    fn test_json_api() {
+        let dir = repo_with_entries(&["Only entry"]);
+        let server = WebServer::new(dir.path());
+
+        let response = server.respond("GET", "/api/entries");
+        let entries: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(entries[0]["summary"], "Only entry");
+        assert_eq!(entries[0]["previous"], "none");
+
+        let response = server.respond("GET", "/api/entries/1");
+        let entry: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(entry["sequence"], 1);
+
+        let response = server.respond("GET", "/api/entries/5");
+        assert_eq!(response.status, 404);
+        assert!(response.body.contains("E_ENTRY_NOT_FOUND"));
+
+        let response = server.respond("GET", "/api/verify");
+        let verify: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(verify["verified"], true);
+    }
+
+    #[test]
+    fn test_verify_reports_broken_chain() {
+        let dir = repo_with_entries(&["Only entry"]);
+        fs::write(dir.path().join(".engram/worklog/stray.txt"), "x").unwrap();
+        let server = WebServer::new(dir.path());
+
+        let verify: Value =
+            serde_json::from_str(&server.respond("GET", "/api/verify").body).unwrap();
+        assert_eq!(verify["verified"], false);
+        assert_eq!(verify["error"]["code"], "E_UNEXPECTED_FILE");
+        assert!(server.respond("GET", "/").body.contains("badge broken"));
+    }
+
+    #[test]
+    fn test_rejects_writes() {
+        let dir = repo_with_entries(&[]);
+        let server = WebServer::new(dir.path());
+        assert_eq!(server.respond("POST", "/api/entries").status, 405);
+        assert_eq!(server.respond("DELETE", "/entries/1").status, 405);
+    }
+
+    #[test]
+    fn test_serves_over_localhost() {
+        let dir = repo_with_entries(&["Over the wire"]);
+        let listener = WebServer::bind(0).unwrap();
+        let address = listener.local_addr().unwrap();
+        assert!(address.ip().is_loopback());
+
+        let root = dir.path().to_path_buf();
+        std::thread::spawn(move || WebServer::new(root).serve(listener));
+
+        // An idle client must not block the request behind it
+        let _idle = TcpStream::connect(address).unwrap();
+
+        let request = |host: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(3)))
+                .unwrap();
+            write!(
+                stream,
+                "GET /api/entries/1 HTTP/1.1\r\nHost: {}\r\n\r\n",
+                host
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = request(&format!("localhost:{}", address.port()));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: application/json"));
+        assert!(response.contains("Over the wire"));
+
+        let response = request("evil.example");
+        assert!(response.starts_with("HTTP/1.1 421 Misdirected Request\r\n"));
+        assert!(!response.contains("Over the wire"));
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        assert!(host_allowed(Some("localhost:8765"), 8765));
+        assert!(host_allowed(Some("127.0.0.1:8765"), 8765));
+        assert!(host_allowed(Some("LocalHost:8765"), 8765));
+        assert!(host_allowed(Some("localhost"), 80));
+        assert!(!host_allowed(Some("localhost"), 8765));
+        assert!(!host_allowed(Some("localhost:9999"), 8765));
+        assert!(!host_allowed(Some("evil.example:8765"), 8765));
+        assert!(!host_allowed(None, 8765));
+    }
+
+    #[test]
+    fn test_rejects_overlong_request_line() {
+        let long = format!("GET /{} HTTP/1.1\r\n", "a".repeat(MAX_LINE_BYTES as usize));
+        let mut reader = BufReader::new(long.as_bytes());
+        assert!(read_line_limited(&mut reader).unwrap().is_none());
+
+        let mut reader = BufReader::new("GET / HTTP/1.1\r\nHost: x\r\n".as_bytes());
+        assert_eq!(
+            read_line_limited(&mut reader).unwrap().as_deref(),
+            Some("GET / HTTP/1.1\r\n")
+        );
+    }
+}
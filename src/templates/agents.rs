@@ -19,10 +19,15 @@ This project uses Engram for persistent agent memory. Follow this protocol for a
 
 1. Update `.engram/draft.md` with your work report:
    - Fill in the `<summary>` tag with ONE sentence describing the change
+   - Optionally add a `Tags: auth, bugfix` line below it to categorize the entry
    - Document Intent: why the change was made
    - Document Changes: specific files and functions modified
    - Document Verification: how you tested/validated
 
+   Rather than rewriting the file, you can use `./engram draft set-summary "..."`
+   and `./engram draft append --section Changes "..."`, then check the result
+   with `./engram draft validate`
+
 2. Run `./engram commit` to finalize the entry
 
 ## Rules
@@ -9,5 +9,11 @@ pub use agents::AGENTS_TEMPLATE;
 pub use directive::ROOT_DIRECTIVE_TEMPLATE;
 pub use draft::DRAFT_TEMPLATE;
 pub use summary::SUMMARY_TEMPLATE;
-pub use wrapper_cmd::WRAPPER_CMD_TEMPLATE;
-pub use wrapper_sh::WRAPPER_SH_TEMPLATE;
+pub use wrapper_cmd::{
+    WRAPPER_CMD_LOCAL_TEMPLATE, WRAPPER_CMD_MIRROR_TEMPLATE, WRAPPER_CMD_PATH_TEMPLATE,
+    WRAPPER_CMD_TEMPLATE,
+};
+pub use wrapper_sh::{
+    WRAPPER_SH_LOCAL_TEMPLATE, WRAPPER_SH_MIRROR_TEMPLATE, WRAPPER_SH_PATH_TEMPLATE,
+    WRAPPER_SH_TEMPLATE,
+};
@@ -89,3 +89,175 @@ if errorlevel 1 (
 :run
 "%BIN_PATH%" %*
 "#;
+
+/// Runs the binary `engram vendor` copied into `.engram\bin`, after checking
+/// it against the SHA-256 `vendor` recorded next to it; never downloads
+pub const WRAPPER_CMD_LOCAL_TEMPLATE: &str = r#"@echo off
+setlocal enableextensions
+
+rem Generated by `engram init` (Engram version __ENGRAM_VERSION__)
+rem Wrapper mode: local
+set ENGRAM_VERSION=__ENGRAM_VERSION__
+
+set ROOT_DIR=%~dp0
+cd /d "%ROOT_DIR%"
+
+set BIN_PATH=.engram\bin\engram-v%ENGRAM_VERSION%.exe
+set SUM_PATH=%BIN_PATH%.sha256
+
+if not exist "%BIN_PATH%" goto missing
+if not exist "%SUM_PATH%" goto missing
+
+where powershell >nul 2>nul
+if errorlevel 1 (
+  echo Error: PowerShell is required for checksum verification. 1>&2
+  exit /b 1
+)
+
+set EXPECTED=
+for /f "usebackq tokens=1" %%H in ("%SUM_PATH%") do if not defined EXPECTED set EXPECTED=%%H
+
+set ACTUAL=
+for /f "usebackq tokens=1" %%H in (`powershell -NoProfile -Command "(Get-FileHash -Algorithm SHA256 '%BIN_PATH%').Hash.ToLower()"`) do set ACTUAL=%%H
+if "%ACTUAL%"=="" (
+  echo Error: Failed to compute SHA256 for %BIN_PATH%. 1>&2
+  exit /b 1
+)
+
+if /i not "%EXPECTED%"=="%ACTUAL%" (
+  echo Error: checksum mismatch for %BIN_PATH%. 1>&2
+  echo Expected: %EXPECTED% 1>&2
+  echo Actual:   %ACTUAL% 1>&2
+  exit /b 1
+)
+
+"%BIN_PATH%" %*
+exit /b %errorlevel%
+
+:missing
+echo Error: %BIN_PATH% or %SUM_PATH% not found. 1>&2
+echo Run `engram vendor` with Engram %ENGRAM_VERSION% to copy it into place. 1>&2
+exit /b 1
+"#;
+
+/// Downloads from `ENGRAM_MIRROR` (a URL or file:// path laid out like the
+/// GitHub releases) and checks the SHA-256 pinned below
+pub const WRAPPER_CMD_MIRROR_TEMPLATE: &str = r#"@echo off
+setlocal enableextensions
+
+rem Generated by `engram init` (Engram version __ENGRAM_VERSION__)
+rem Wrapper mode: mirror
+set ENGRAM_VERSION=__ENGRAM_VERSION__
+if "%ENGRAM_MIRROR%"=="" set ENGRAM_MIRROR=__ENGRAM_MIRROR__
+
+set ROOT_DIR=%~dp0
+cd /d "%ROOT_DIR%"
+
+set ENGRAM_DIR=.engram
+set BIN_DIR=%ENGRAM_DIR%\bin
+set BIN_PATH=%BIN_DIR%\engram-v%ENGRAM_VERSION%.exe
+
+if not exist "%BIN_DIR%" mkdir "%BIN_DIR%"
+
+if /i not "%PROCESSOR_ARCHITECTURE%"=="AMD64" (
+  echo Unsupported architecture: %PROCESSOR_ARCHITECTURE% 1>&2
+  exit /b 1
+)
+
+set ASSET=engram-windows-x86_64.exe
+set TAG=v%ENGRAM_VERSION%
+
+if exist "%BIN_PATH%" goto run
+
+if "%ENGRAM_MIRROR%"=="" (
+  echo Error: set ENGRAM_MIRROR to the URL or file:// path of an Engram release mirror. 1>&2
+  exit /b 1
+)
+
+rem SHA-256 of the release asset, pinned when this wrapper was generated
+set EXPECTED=__ENGRAM_CHECKSUM__
+if "%EXPECTED%"=="" (
+  echo Error: no pinned checksum for %ASSET% in this wrapper. 1>&2
+  exit /b 1
+)
+
+set TMP_DIR=%TEMP%\engram-%RANDOM%%RANDOM%
+mkdir "%TMP_DIR%" >nul 2>nul
+set TMP_BIN=%TMP_DIR%\%ASSET%
+set SOURCE=%ENGRAM_MIRROR%/%TAG%/%ASSET%
+
+if /i "%SOURCE:~0,7%"=="file://" (
+  set SOURCE_PATH=%SOURCE:~7%
+) else if "%SOURCE:~1,1%"==":" (
+  set SOURCE_PATH=%SOURCE%
+) else (
+  set SOURCE_PATH=
+)
+
+if "%SOURCE_PATH%"=="" (
+  where curl >nul 2>nul
+  if errorlevel 1 (
+    echo Error: curl is required to download Engram on Windows. 1>&2
+    exit /b 1
+  )
+  curl -fsSL "%SOURCE%" -o "%TMP_BIN%"
+) else (
+  copy /y "%SOURCE_PATH:/=\%" "%TMP_BIN%" >nul
+)
+if errorlevel 1 (
+  echo Error: Failed to fetch %SOURCE%. 1>&2
+  exit /b 1
+)
+
+where powershell >nul 2>nul
+if errorlevel 1 (
+  echo Error: PowerShell is required for checksum verification. 1>&2
+  exit /b 1
+)
+
+set ACTUAL=
+for /f "usebackq tokens=1" %%H in (`powershell -NoProfile -Command "(Get-FileHash -Algorithm SHA256 '%TMP_BIN%').Hash.ToLower()"`) do set ACTUAL=%%H
+if "%ACTUAL%"=="" (
+  echo Error: Failed to compute SHA256 for downloaded binary. 1>&2
+  exit /b 1
+)
+
+if /i not "%EXPECTED%"=="%ACTUAL%" (
+  echo Error: checksum mismatch for %ASSET%. 1>&2
+  echo Expected: %EXPECTED% 1>&2
+  echo Actual:   %ACTUAL% 1>&2
+  exit /b 1
+)
+
+copy /y "%TMP_BIN%" "%BIN_PATH%" >nul
+if errorlevel 1 (
+  echo Error: Failed to write %BIN_PATH%. 1>&2
+  exit /b 1
+)
+
+:run
+"%BIN_PATH%" %*
+"#;
+
+/// Runs `engram.exe` from PATH (or `ENGRAM_BIN`); never downloads
+pub const WRAPPER_CMD_PATH_TEMPLATE: &str = r#"@echo off
+setlocal enableextensions
+
+rem Generated by `engram init` (Engram version __ENGRAM_VERSION__)
+rem Wrapper mode: path
+set ENGRAM_VERSION=__ENGRAM_VERSION__
+if "%ENGRAM_BIN%"=="" set ENGRAM_BIN=engram.exe
+
+set ROOT_DIR=%~dp0
+cd /d "%ROOT_DIR%"
+
+where "%ENGRAM_BIN%" >nul 2>nul
+if errorlevel 1 (
+  if not exist "%ENGRAM_BIN%" (
+    echo Error: no engram binary found on PATH ^(set ENGRAM_BIN to its location^). 1>&2
+    exit /b 1
+  )
+)
+
+"%ENGRAM_BIN%" %*
+"#;
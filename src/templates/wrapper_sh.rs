@@ -90,3 +90,153 @@ fi
 
 exec "$BIN_PATH" "$@"
 "#;
+
+/// Runs the binary `engram vendor` copied into `.engram/bin`, after checking
+/// it against the SHA-256 `vendor` recorded next to it; never downloads
+pub const WRAPPER_SH_LOCAL_TEMPLATE: &str = r#"#!/bin/sh
+set -eu
+
+# Generated by `engram init` (Engram version __ENGRAM_VERSION__)
+# Wrapper mode: local
+ENGRAM_VERSION="__ENGRAM_VERSION__"
+
+ROOT_DIR=$(CDPATH= cd -- "$(dirname -- "$0")" && pwd)
+cd "$ROOT_DIR"
+
+BIN_PATH=".engram/bin/engram-v${ENGRAM_VERSION}"
+SUM_PATH="${BIN_PATH}.sha256"
+
+if [ ! -x "$BIN_PATH" ] || [ ! -f "$SUM_PATH" ]; then
+  echo "Error: $BIN_PATH or $SUM_PATH not found" >&2
+  echo "Run \`engram vendor\` with Engram ${ENGRAM_VERSION} to copy it into place." >&2
+  exit 1
+fi
+
+expected=$(awk '{print $1; exit}' "$SUM_PATH")
+if command -v sha256sum >/dev/null 2>&1; then
+  actual=$(sha256sum "$BIN_PATH" | awk '{print $1}')
+elif command -v shasum >/dev/null 2>&1; then
+  actual=$(shasum -a 256 "$BIN_PATH" | awk '{print $1}')
+else
+  echo "Error: sha256sum or shasum is required for checksum verification" >&2
+  exit 1
+fi
+
+if [ "$expected" != "$actual" ]; then
+  echo "Error: checksum mismatch for $BIN_PATH" >&2
+  echo "Expected: $expected" >&2
+  echo "Actual:   $actual" >&2
+  exit 1
+fi
+
+exec "$BIN_PATH" "$@"
+"#;
+
+/// Downloads from `ENGRAM_MIRROR` (a URL or file:// path laid out like the
+/// GitHub releases) and checks the SHA-256 pinned below for each asset
+pub const WRAPPER_SH_MIRROR_TEMPLATE: &str = r#"#!/bin/sh
+set -eu
+
+# Generated by `engram init` (Engram version __ENGRAM_VERSION__)
+# Wrapper mode: mirror
+ENGRAM_VERSION="__ENGRAM_VERSION__"
+ENGRAM_MIRROR="${ENGRAM_MIRROR:-__ENGRAM_MIRROR__}"
+
+ROOT_DIR=$(CDPATH= cd -- "$(dirname -- "$0")" && pwd)
+cd "$ROOT_DIR"
+
+ENGRAM_DIR=".engram"
+BIN_DIR="$ENGRAM_DIR/bin"
+BIN_PATH="$BIN_DIR/engram-v${ENGRAM_VERSION}"
+
+mkdir -p "$BIN_DIR"
+
+# Detect platform
+OS=$(uname -s | tr '[:upper:]' '[:lower:]')
+ARCH=$(uname -m)
+
+case "$ARCH" in
+  aarch64|arm64) ARCH="aarch64" ;;
+esac
+
+ASSET="engram-${OS}-${ARCH}"
+TAG="v${ENGRAM_VERSION}"
+
+if [ ! -x "$BIN_PATH" ]; then
+  if [ -z "$ENGRAM_MIRROR" ]; then
+    echo "Error: set ENGRAM_MIRROR to the URL or file:// path of an Engram release mirror" >&2
+    exit 1
+  fi
+
+  # SHA-256 of each release asset, pinned when this wrapper was generated
+  case "$ASSET" in
+__ENGRAM_CHECKSUMS__    *) echo "Error: no pinned checksum for $ASSET in this wrapper" >&2; exit 1 ;;
+  esac
+
+  tmp_dir=$(mktemp -d 2>/dev/null || mktemp -d -t engram)
+  cleanup() { rm -rf "$tmp_dir"; }
+  trap cleanup EXIT INT TERM
+
+  bin_file="$tmp_dir/$ASSET"
+  SOURCE="${ENGRAM_MIRROR%/}/${TAG}/${ASSET}"
+
+  case "$SOURCE" in
+    file://*) cp "${SOURCE#file://}" "$bin_file" ;;
+    /*) cp "$SOURCE" "$bin_file" ;;
+    *)
+      if command -v curl >/dev/null 2>&1; then
+        curl -fsSL "$SOURCE" -o "$bin_file"
+      elif command -v wget >/dev/null 2>&1; then
+        wget -q "$SOURCE" -O "$bin_file"
+      else
+        echo "Error: curl or wget is required to download Engram" >&2
+        exit 1
+      fi
+      ;;
+  esac
+
+  if command -v sha256sum >/dev/null 2>&1; then
+    actual=$(sha256sum "$bin_file" | awk '{print $1}')
+  elif command -v shasum >/dev/null 2>&1; then
+    actual=$(shasum -a 256 "$bin_file" | awk '{print $1}')
+  else
+    echo "Error: sha256sum or shasum is required for checksum verification" >&2
+    exit 1
+  fi
+
+  if [ "$EXPECTED" != "$actual" ]; then
+    echo "Error: checksum mismatch for $ASSET" >&2
+    echo "Expected: $EXPECTED" >&2
+    echo "Actual:   $actual" >&2
+    exit 1
+  fi
+
+  chmod +x "$bin_file"
+  mv "$bin_file" "$BIN_PATH"
+fi
+
+exec "$BIN_PATH" "$@"
+"#;
+
+/// Runs `engram` from PATH (or `ENGRAM_BIN`); never downloads
+pub const WRAPPER_SH_PATH_TEMPLATE: &str = r#"#!/bin/sh
+set -eu
+
+# Generated by `engram init` (Engram version __ENGRAM_VERSION__)
+# Wrapper mode: path
+ENGRAM_VERSION="__ENGRAM_VERSION__"
+ENGRAM_BIN="${ENGRAM_BIN:-engram}"
+
+ROOT_DIR=$(CDPATH= cd -- "$(dirname -- "$0")" && pwd)
+cd "$ROOT_DIR"
+
+found=$(command -v "$ENGRAM_BIN" 2>/dev/null || true)
+case "$found" in
+  ""|engram|./engram|"$ROOT_DIR/engram")
+    echo "Error: no engram binary found on PATH (set ENGRAM_BIN to its location)" >&2
+    exit 1
+    ;;
+esac
+
+exec "$found" "$@"
+"#;
@@ -0,0 +1,965 @@
+//! State and drawing of the TUI, independent of the terminal so it can be
+//! driven by tests
+
+use std::collections::BTreeMap;
+
+use crate::commands::verify::VerifyResult;
+use crate::engram::draft::Draft;
+use crate::error::EngramError;
+use crate::repository::Entry;
+use crate::tui::editor::Editor;
+use crate::tui::terminal::Key;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const PLAIN: &str = "";
+const TAB_WIDTH: usize = 4;
+
+const BROWSE_HELP: &str =
+    "j/k move  / search  t tag  d draft  space/b scroll  esc clear  r reload  q quit";
+const DRAFT_HELP: &str = "ctrl-s save  ctrl-g commit  esc back to the list";
+
+/// How far an entry is covered by `verify`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryStatus {
+    Verified,
+    /// Where verification failed
+    Broken,
+    /// After the failure, so not checked
+    Unchecked,
+}
+
+/// What the event loop must do after a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    SaveDraft,
+    Commit,
+    Reload,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Browse,
+    Search,
+    Tag,
+    Draft,
+}
+
+pub struct App {
+    /// Entries newest first, with their verify status
+    entries: Vec<(Entry, EntryStatus)>,
+    /// Verified entry count, or the verify error code
+    chain: Result<usize, &'static str>,
+    tags: Vec<(String, usize)>,
+    mode: Mode,
+    query: String,
+    tag: Option<String>,
+    tag_input: String,
+    tag_choice: usize,
+    /// Indexes into `entries` passing the search and tag filters
+    visible: Vec<usize>,
+    selected: usize,
+    list_top: usize,
+    body_top: usize,
+    pane_height: usize,
+    editor: Editor,
+    message: Option<String>,
+}
+
+impl App {
+    pub fn new(
+        entries: Vec<Entry>,
+        verify: &Result<VerifyResult, EngramError>,
+        draft: &str,
+    ) -> Self {
+        let mut app = App {
+            entries: Vec::new(),
+            chain: Ok(0),
+            tags: Vec::new(),
+            mode: Mode::Browse,
+            query: String::new(),
+            tag: None,
+            tag_input: String::new(),
+            tag_choice: 0,
+            visible: Vec::new(),
+            selected: 0,
+            list_top: 0,
+            body_top: 0,
+            pane_height: 1,
+            editor: Editor::new(draft),
+            message: None,
+        };
+        app.load(entries, verify);
+        app
+    }
+
+    /// Replace the entries, keeping the filters
+    pub fn load(&mut self, entries: Vec<Entry>, verify: &Result<VerifyResult, EngramError>) {
+        let statuses = statuses(&entries, verify);
+        let mut tags: BTreeMap<String, usize> = BTreeMap::new();
+        for entry in &entries {
+            for tag in entry.content.tags() {
+                *tags.entry(tag).or_default() += 1;
+            }
+        }
+        self.tags = tags.into_iter().collect();
+        self.tags
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        self.chain = verify
+            .as_ref()
+            .map(|result| result.entry_count)
+            .map_err(EngramError::code);
+        self.entries = entries.into_iter().zip(statuses).rev().collect();
+        self.filter();
+    }
+
+    #[cfg(test)]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn draft_text(&self) -> String {
+        self.editor.text()
+    }
+
+    pub fn draft_modified(&self) -> bool {
+        self.editor.is_modified()
+    }
+
+    pub fn draft_saved(&mut self) {
+        self.editor.mark_saved();
+        self.message = Some("Saved .engram/draft.md".to_string());
+    }
+
+    /// After a commit: show the new entry and edit the fresh draft
+    pub fn committed(&mut self, filename: &str, draft: &str) {
+        self.editor = Editor::new(draft);
+        self.mode = Mode::Browse;
+        self.message = Some(format!("Committed {}", filename));
+        if let Some(position) = self
+            .visible
+            .iter()
+            .position(|&index| self.entries[index].0.filename == filename)
+        {
+            self.select(position);
+        }
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /// Filenames of the entries in the list, top first
+    #[cfg(test)]
+    pub fn visible_filenames(&self) -> Vec<&str> {
+        self.visible
+            .iter()
+            .map(|&index| self.entries[index].0.filename.as_str())
+            .collect()
+    }
+
+    pub fn selected_filename(&self) -> Option<&str> {
+        self.current().map(|(entry, _)| entry.filename.as_str())
+    }
+
+    /// Live `Draft::parse` result for the editor contents
+    pub fn draft_check(&self) -> Result<Draft, String> {
+        Draft::parse(&self.editor.text()).map_err(|error| format!("{}: {}", error.code(), error))
+    }
+
+    pub fn handle(&mut self, key: Key) -> Action {
+        if key == Key::Ctrl('c') {
+            return Action::Quit;
+        }
+        self.message = None;
+        match self.mode {
+            Mode::Browse => self.browse(key),
+            Mode::Search => self.search(key),
+            Mode::Tag => self.pick_tag(key),
+            Mode::Draft => self.edit(key),
+        }
+    }
+
+    fn browse(&mut self, key: Key) -> Action {
+        let page = self.pane_height.saturating_sub(2).max(1);
+        match key {
+            Key::Char('q') => return Action::Quit,
+            Key::Char('j') | Key::Down => self.select(self.selected + 1),
+            Key::Char('k') | Key::Up => self.select(self.selected.saturating_sub(1)),
+            Key::Char('g') | Key::Home => self.select(0),
+            Key::Char('G') | Key::End => self.select(self.visible.len().saturating_sub(1)),
+            Key::Char('J') => self.body_top += 1,
+            Key::Char('K') => self.body_top = self.body_top.saturating_sub(1),
+            Key::Char(' ') | Key::PageDown => self.body_top += page,
+            Key::Char('b') | Key::PageUp => self.body_top = self.body_top.saturating_sub(page),
+            Key::Char('/') => self.mode = Mode::Search,
+            Key::Char('t') => {
+                self.tag_input.clear();
+                self.tag_choice = 0;
+                self.mode = Mode::Tag;
+            }
+            Key::Char('d') => self.mode = Mode::Draft,
+            Key::Char('r') => return Action::Reload,
+            Key::Esc => {
+                self.query.clear();
+                self.tag = None;
+                self.filter();
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn search(&mut self, key: Key) -> Action {
+        match key {
+            Key::Char(c) => self.query.push(c),
+            Key::Backspace => {
+                self.query.pop();
+            }
+            Key::Enter => self.mode = Mode::Browse,
+            Key::Esc => {
+                self.query.clear();
+                self.mode = Mode::Browse;
+            }
+            Key::Down => self.select(self.selected + 1),
+            Key::Up => self.select(self.selected.saturating_sub(1)),
+            _ => return Action::None,
+        }
+        if matches!(key, Key::Char(_) | Key::Backspace | Key::Esc) {
+            self.filter();
+        }
+        Action::None
+    }
+
+    fn pick_tag(&mut self, key: Key) -> Action {
+        let candidates = self.tag_candidates().len();
+        match key {
+            Key::Char(c) => {
+                self.tag_input.push(c);
+                self.tag_choice = 0;
+            }
+            Key::Backspace => {
+                self.tag_input.pop();
+                self.tag_choice = 0;
+            }
+            Key::Tab | Key::Right | Key::Down if candidates > 0 => {
+                self.tag_choice = (self.tag_choice + 1) % candidates;
+            }
+            Key::Left | Key::Up if candidates > 0 => {
+                self.tag_choice = (self.tag_choice + candidates - 1) % candidates;
+            }
+            Key::Enter => {
+                self.tag = if self.tag_input.is_empty() && candidates == 0 {
+                    None
+                } else {
+                    self.tag_candidates()
+                        .get(self.tag_choice)
+                        .map(|(tag, _)| tag.to_string())
+                };
+                if self.tag.is_none() && !self.tag_input.is_empty() {
+                    self.message = Some(format!("No tag starts with '{}'", self.tag_input));
+                }
+                self.mode = Mode::Browse;
+                self.filter();
+            }
+            Key::Esc => self.mode = Mode::Browse,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn edit(&mut self, key: Key) -> Action {
+        let page = self.pane_height.max(1);
+        match key {
+            Key::Char(c) => self.editor.insert(c),
+            Key::Tab => (0..4).for_each(|_| self.editor.insert(' ')),
+            Key::Enter => self.editor.newline(),
+            Key::Backspace => self.editor.backspace(),
+            Key::Delete => self.editor.delete(),
+            Key::Left => self.editor.left(),
+            Key::Right => self.editor.right(),
+            Key::Up => self.editor.up(1),
+            Key::Down => self.editor.down(1),
+            Key::PageUp => self.editor.up(page),
+            Key::PageDown => self.editor.down(page),
+            Key::Home => self.editor.home(),
+            Key::End => self.editor.end(),
+            Key::Ctrl('s') => return Action::SaveDraft,
+            Key::Ctrl('g') => match self.draft_check() {
+                Ok(_) => return Action::Commit,
+                Err(error) => self.message = Some(format!("Cannot commit: {}", error)),
+            },
+            Key::Esc => {
+                self.mode = Mode::Browse;
+                if self.editor.is_modified() {
+                    return Action::SaveDraft;
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Tags starting with the typed prefix, most used first
+    fn tag_candidates(&self) -> Vec<&(String, usize)> {
+        let prefix = self.tag_input.to_lowercase();
+        self.tags
+            .iter()
+            .filter(|(tag, _)| tag.starts_with(&prefix))
+            .collect()
+    }
+
+    fn filter(&mut self) {
+        let previous = self.selected_filename().map(str::to_string);
+        self.visible = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (entry, _))| self.query.is_empty() || entry.matches(&self.query))
+            .filter(|(_, (entry, _))| {
+                self.tag
+                    .as_ref()
+                    .is_none_or(|tag| entry.content.tags().contains(tag))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let position = previous.and_then(|filename| {
+            self.visible
+                .iter()
+                .position(|&index| self.entries[index].0.filename == filename)
+        });
+        self.select(position.unwrap_or(0));
+    }
+
+    fn select(&mut self, position: usize) {
+        let position = position.min(self.visible.len().saturating_sub(1));
+        if position != self.selected {
+            self.body_top = 0;
+        }
+        self.selected = position;
+    }
+
+    fn current(&self) -> Option<&(Entry, EntryStatus)> {
+        self.visible
+            .get(self.selected)
+            .map(|&index| &self.entries[index])
+    }
+
+    /// Draw a `rows` x `cols` frame, returning its lines and where to show
+    /// the cursor
+    pub fn render(&mut self, rows: usize, cols: usize) -> (Vec<String>, Option<(usize, usize)>) {
+        let rows = rows.max(4);
+        let height = rows - 3;
+        self.pane_height = height;
+        let left = (cols * 2 / 5).clamp(24, 60).min(cols.saturating_sub(21));
+        let right = cols.saturating_sub(left + 1);
+
+        let mut lines = Vec::with_capacity(rows);
+        let chain = match self.chain {
+            Ok(count) => (GREEN, format!("✓ chain verified ({} entries)", count)),
+            Err(code) => (RED, format!("✗ {}", code)),
+        };
+        lines.push(paint(
+            &[
+                (BOLD, " engram "),
+                (PLAIN, "  "),
+                (chain.0, &chain.1),
+                (
+                    PLAIN,
+                    &format!("  {} of {} entries", self.visible.len(), self.entries.len()),
+                ),
+            ],
+            cols,
+            REVERSE,
+        ));
+
+        // Entry list
+        if self.selected < self.list_top {
+            self.list_top = self.selected;
+        } else if self.selected >= self.list_top + height {
+            self.list_top = self.selected + 1 - height;
+        }
+        let mut list: Vec<String> = self.visible[self.list_top.min(self.visible.len())..]
+            .iter()
+            .take(height)
+            .enumerate()
+            .map(|(offset, &index)| {
+                let (entry, status) = &self.entries[index];
+                let glyph = match status {
+                    EntryStatus::Verified => (GREEN, "✓ "),
+                    EntryStatus::Broken => (RED, "✗ "),
+                    EntryStatus::Unchecked => (DIM, "· "),
+                };
+                let row = format!(
+                    "{:>6} {} {}",
+                    entry.sequence,
+                    entry.content.date.format("%Y-%m-%d"),
+                    entry.content.summary
+                );
+                let base = if self.list_top + offset == self.selected {
+                    REVERSE
+                } else {
+                    PLAIN
+                };
+                paint(&[glyph, (PLAIN, &row)], left, base)
+            })
+            .collect();
+        if self.visible.is_empty() {
+            let empty = if self.entries.is_empty() {
+                "No entries yet"
+            } else {
+                "No matching entries"
+            };
+            list.push(paint(&[(DIM, empty)], left, PLAIN));
+        }
+        list.resize(height, " ".repeat(left));
+
+        // Entry detail or draft editor
+        let mut cursor = None;
+        let pane: Vec<String> = if self.mode == Mode::Draft {
+            let top = self.editor.scroll(height);
+            let (row, col) = self.editor.cursor();
+            let before: String = self.editor.lines()[row].chars().take(col).collect();
+            let col = text_width(&printable(&before, 0));
+            cursor = Some((row - top + 1, left + 1 + col.min(right.saturating_sub(1))));
+            self.editor.lines()[top..]
+                .iter()
+                .take(height)
+                .map(|line| paint(&[(PLAIN, line)], right, PLAIN))
+                .collect()
+        } else {
+            let detail = self.detail(right);
+            self.body_top = self.body_top.min(detail.len().saturating_sub(1));
+            detail
+                .iter()
+                .skip(self.body_top)
+                .take(height)
+                .map(|(style, text)| paint(&[(style, text)], right, PLAIN))
+                .collect()
+        };
+
+        for (row, entry) in list.iter().enumerate() {
+            let text = pane.get(row).map(String::as_str).unwrap_or("");
+            lines.push(format!("{}{}│{}{}", entry, DIM, RESET, text));
+        }
+
+        // Status line: draft validation, a message or the active filters
+        let status = if let Some(message) = &self.message {
+            paint(&[(BOLD, message)], cols, PLAIN)
+        } else if self.mode == Mode::Draft {
+            match self.draft_check() {
+                Ok(draft) => paint(
+                    &[(GREEN, "✓ ready to commit: "), (PLAIN, &draft.summary)],
+                    cols,
+                    PLAIN,
+                ),
+                Err(error) => paint(&[(RED, &format!("✗ {}", error))], cols, PLAIN),
+            }
+        } else {
+            let mut filters = Vec::new();
+            if !self.query.is_empty() {
+                filters.push(format!("search: {}", self.query));
+            }
+            if let Some(tag) = &self.tag {
+                filters.push(format!("tag: {}", tag));
+            }
+            paint(&[(CYAN, &filters.join("  "))], cols, PLAIN)
+        };
+        lines.push(status);
+
+        // Prompt or key help
+        let prompt = match self.mode {
+            Mode::Browse => paint(&[(DIM, BROWSE_HELP)], cols, PLAIN),
+            Mode::Draft => paint(&[(DIM, DRAFT_HELP)], cols, PLAIN),
+            Mode::Search => {
+                cursor = Some((
+                    rows - 1,
+                    ("/".len() + text_width(&self.query)).min(cols - 1),
+                ));
+                paint(&[(PLAIN, &format!("/{}", self.query))], cols, PLAIN)
+            }
+            Mode::Tag => {
+                let label = format!("tag: {}", self.tag_input);
+                cursor = Some((rows - 1, text_width(&label).min(cols - 1)));
+                let mut segments = vec![(PLAIN, label), (PLAIN, "   ".to_string())];
+                for (index, (tag, count)) in self.tag_candidates().into_iter().enumerate() {
+                    let style = if index == self.tag_choice {
+                        REVERSE
+                    } else {
+                        DIM
+                    };
+                    segments.push((style, format!("{} ({})", tag, count)));
+                    segments.push((PLAIN, " ".to_string()));
+                }
+                let segments: Vec<(&str, &str)> = segments
+                    .iter()
+                    .map(|(style, text)| (*style, text.as_str()))
+                    .collect();
+                paint(&segments, cols, PLAIN)
+            }
+        };
+        lines.push(prompt);
+        (lines, cursor)
+    }
+
+    /// Header and rendered body of the selected entry
+    fn detail(&self, width: usize) -> Vec<(&'static str, String)> {
+        let Some((entry, status)) = self.current() else {
+            return Vec::new();
+        };
+        let mut lines = vec![(BOLD, entry.content.summary.clone())];
+        lines.push((
+            DIM,
+            format!(
+                "{}  {}",
+                entry.filename,
+                entry.content.date.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+        ));
+        let status = match status {
+            EntryStatus::Verified => (GREEN, "✓ verified".to_string()),
+            EntryStatus::Broken => (RED, format!("✗ {}", self.chain.err().unwrap_or("broken"))),
+            EntryStatus::Unchecked => (DIM, "· not verified (after a broken entry)".to_string()),
+        };
+        lines.push(status);
+        for (name, value) in &entry.content.headers {
+            lines.push((CYAN, format!("{}: {}", name, value)));
+        }
+        lines.push((PLAIN, String::new()));
+        lines.extend(render_markdown(&entry.content.body, width));
+        lines
+    }
+}
+
+/// Verify status of each entry: verified up to the entry `verify` failed at
+fn statuses(entries: &[Entry], verify: &Result<VerifyResult, EngramError>) -> Vec<EntryStatus> {
+    let broken = match verify {
+        Ok(_) => return vec![EntryStatus::Verified; entries.len()],
+        Err(EngramError::Chain(error)) => entries
+            .iter()
+            .position(|entry| entry.filename == error.filename()),
+        Err(_) => None,
+    };
+    (0..entries.len())
+        .map(|index| match broken {
+            Some(at) if index < at => EntryStatus::Verified,
+            Some(at) if index == at => EntryStatus::Broken,
+            _ => EntryStatus::Unchecked,
+        })
+        .collect()
+}
+
+/// Markdown as styled, wrapped terminal lines: headings bold, list items
+/// bulleted, code blocks dimmed, HTML comments dropped
+pub fn render_markdown(markdown: &str, width: usize) -> Vec<(&'static str, String)> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push((DIM, format!("  {}", line)));
+        } else if trimmed.starts_with("<!--") && trimmed.ends_with("-->") {
+            continue;
+        } else if trimmed.starts_with('#') {
+            lines.push((BOLD, trimmed.trim_start_matches('#').trim().to_string()));
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            for (index, part) in wrap(item, width.saturating_sub(2)).into_iter().enumerate() {
+                let bullet = if index == 0 { "• " } else { "  " };
+                lines.push((PLAIN, format!("{}{}", bullet, part)));
+            }
+        } else {
+            lines.extend(wrap(trimmed, width).into_iter().map(|part| (PLAIN, part)));
+        }
+    }
+    lines
+}
+
+/// Word-wrap to `width` columns; an empty line stays one empty line
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let needed = text_width(&current) + text_width(word) + usize::from(!current.is_empty());
+        if !current.is_empty() && needed > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+/// `text` as it may be written to the terminal from `column` on: tabs
+/// expanded and control characters, which could start escape sequences,
+/// shown as U+FFFD
+fn printable(text: &str, column: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut column = column;
+    for c in text.chars() {
+        if c == '\t' {
+            let spaces = TAB_WIDTH - column % TAB_WIDTH;
+            out.push_str(&" ".repeat(spaces));
+            column += spaces;
+            continue;
+        }
+        let c = if c.is_control() { '\u{fffd}' } else { c };
+        out.push(c);
+        column += char_width(c);
+    }
+    out
+}
+
+/// Terminal columns taken by `c`: 2 for East Asian wide and fullwidth
+/// characters and most emoji, 0 for combining marks and joiners, else 1.
+/// A small table covering the common blocks, not the full Unicode data.
+fn char_width(c: char) -> usize {
+    const ZERO: &[(u32, u32)] = &[
+        (0x0300, 0x036F),
+        (0x200B, 0x200F),
+        (0x20D0, 0x20FF),
+        (0xFE00, 0xFE0F),
+        (0xFE20, 0xFE2F),
+    ];
+    const WIDE: &[(u32, u32)] = &[
+        (0x1100, 0x115F),
+        (0x231A, 0x231B),
+        (0x2329, 0x232A),
+        (0x23E9, 0x23EC),
+        (0x23F0, 0x23F3),
+        (0x25FD, 0x25FE),
+        (0x2614, 0x2615),
+        (0x2648, 0x2653),
+        (0x26A1, 0x26A1),
+        (0x26AA, 0x26AB),
+        (0x26BD, 0x26BE),
+        (0x26C4, 0x26C5),
+        (0x26D4, 0x26D4),
+        (0x26EA, 0x26EA),
+        (0x26F2, 0x26FD),
+        (0x2705, 0x2705),
+        (0x270A, 0x270B),
+        (0x2728, 0x2728),
+        (0x274C, 0x274E),
+        (0x2753, 0x2757),
+        (0x2795, 0x2797),
+        (0x27B0, 0x27BF),
+        (0x2B1B, 0x2B1C),
+        (0x2B50, 0x2B55),
+        (0x2E80, 0x303E),
+        (0x3041, 0x33FF),
+        (0x3400, 0x4DBF),
+        (0x4E00, 0x9FFF),
+        (0xA000, 0xA4CF),
+        (0xA960, 0xA97F),
+        (0xAC00, 0xD7A3),
+        (0xF900, 0xFAFF),
+        (0xFE10, 0xFE19),
+        (0xFE30, 0xFE6F),
+        (0xFF00, 0xFF60),
+        (0xFFE0, 0xFFE6),
+        (0x1F004, 0x1F004),
+        (0x1F0CF, 0x1F0CF),
+        (0x1F18E, 0x1F18E),
+        (0x1F191, 0x1F19A),
+        (0x1F200, 0x1F251),
+        (0x1F300, 0x1F64F),
+        (0x1F680, 0x1F6FF),
+        (0x1F7E0, 0x1F7EB),
+        (0x1F90C, 0x1F9FF),
+        (0x1FA70, 0x1FAFF),
+        (0x20000, 0x3FFFD),
+    ];
+    let code = u32::from(c);
+    let within = |table: &[(u32, u32)]| {
+        table
+            .binary_search_by(|&(start, end)| {
+                if end < code {
+                    std::cmp::Ordering::Less
+                } else if start > code {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    };
+    if code == 0x200D || within(ZERO) {
+        0
+    } else if within(WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Terminal columns taken by `text`
+fn text_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Styled segments cut or padded to exactly `width` columns, on a `base`
+/// style that applies to the whole row. Text is made [`printable`].
+fn paint(segments: &[(&str, &str)], width: usize, base: &str) -> String {
+    let mut out = String::from(base);
+    let mut used = 0;
+    for (style, text) in segments {
+        if used >= width {
+            break;
+        }
+        let text = printable(text, used);
+        let room = width - used;
+        let shown: String = if text_width(&text) > room {
+            // Leave a column for the ellipsis; a wide character that does
+            // not fit is dropped whole
+            let mut cut = String::new();
+            let mut taken = 0;
+            for c in text.chars() {
+                taken += char_width(c);
+                if taken > room - 1 {
+                    break;
+                }
+                cut.push(c);
+            }
+            cut.push('…');
+            cut
+        } else {
+            text
+        };
+        used += text_width(&shown);
+        out.push_str(style);
+        out.push_str(&shown);
+        out.push_str(RESET);
+        out.push_str(base);
+    }
+    out.push_str(&" ".repeat(width.saturating_sub(used)));
+    out.push_str(RESET);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::verify::VerifyError;
+    use crate::engram::worklog::EntryContent;
+    use std::path::PathBuf;
+
+    fn entry(sequence: u32, summary: &str, tags: &str, body: &str) -> Entry {
+        let text = format!(
+            "Summary: {}\nPrevious: none\nDate: 2025-06-1{}T14:32:07Z\n{}\n---\n\n{}\n",
+            summary,
+            sequence,
+            if tags.is_empty() {
+                String::new()
+            } else {
+                format!("Tags: {}\n", tags)
+            },
+            body
+        );
+        Entry {
+            sequence,
+            filename: format!("{:06}_0000000{}.md", sequence, sequence),
+            hash: format!("{:064}", sequence),
+            content: EntryContent::parse(&text).unwrap(),
+            path: PathBuf::new(),
+        }
+    }
+
+    fn app() -> App {
+        let entries = vec![
+            entry(1, "Add login form", "auth", "## Changes\n- Added the form"),
+            entry(2, "Fix token expiry", "auth, bugfix", "Tokens now expire."),
+            entry(3, "Update README", "", "Docs only."),
+        ];
+        let verify = Ok(VerifyResult {
+            entry_count: 3,
+            first_entry: None,
+            latest_entry: None,
+        });
+        App::new(entries, &verify, "<summary></summary>\n")
+    }
+
+    /// Frame lines without escape sequences or trailing padding
+    fn plain(lines: Vec<String>) -> Vec<String> {
+        let escape = regex::Regex::new("\x1b\\[[0-9;?]*[A-Za-z]").unwrap();
+        lines
+            .iter()
+            .map(|line| escape.replace_all(line, "").trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_paint_neutralizes_control_characters() {
+        let line = paint(&[(PLAIN, "\x1b]52;c;aGk=\x07\x1b[2Jok\u{9b}")], 20, PLAIN);
+        assert!(!line.contains("\x1b]"));
+        assert!(!line.contains("\x07"));
+        assert!(!line.contains("\u{9b}"));
+        assert!(plain(vec![line])
+            .concat()
+            .starts_with("\u{fffd}]52;c;aGk=\u{fffd}\u{fffd}[2Jok\u{fffd}"));
+
+        assert_eq!(printable("a\tb\t", 0), "a   b   ");
+        assert_eq!(printable("\tx", 2), "  x");
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_search_as_you_type_filters_the_list() {
+        let mut app = app();
+        assert_eq!(app.visible_filenames().len(), 3);
+
+        app.handle(Key::Char('/'));
+        type_text(&mut app, "tok");
+        assert_eq!(app.visible_filenames(), ["000002_00000002.md"]);
+        app.handle(Key::Backspace);
+        app.handle(Key::Backspace);
+        assert_eq!(app.visible_filenames().len(), 3);
+
+        app.handle(Key::Esc);
+        assert_eq!(app.mode(), Mode::Browse);
+        assert_eq!(app.visible_filenames().len(), 3);
+    }
+
+    #[test]
+    fn test_jump_by_tag() {
+        let mut app = app();
+        app.handle(Key::Char('t'));
+        type_text(&mut app, "b");
+        app.handle(Key::Enter);
+        assert_eq!(app.visible_filenames(), ["000002_00000002.md"]);
+
+        // Most used tag first; Tab moves to the next candidate
+        app.handle(Key::Char('t'));
+        app.handle(Key::Tab);
+        app.handle(Key::Enter);
+        assert_eq!(app.visible_filenames(), ["000002_00000002.md"]);
+        app.handle(Key::Char('t'));
+        app.handle(Key::Enter);
+        assert_eq!(
+            app.visible_filenames(),
+            ["000002_00000002.md", "000001_00000001.md"]
+        );
+
+        app.handle(Key::Esc);
+        assert_eq!(app.visible_filenames().len(), 3);
+    }
+
+    #[test]
+    fn test_draft_editor_validates_live_and_commits() {
+        let mut app = app();
+        app.handle(Key::Char('d'));
+        assert!(app
+            .draft_check()
+            .unwrap_err()
+            .starts_with("E_DRAFT_SUMMARY_EMPTY"));
+        assert_eq!(app.handle(Key::Ctrl('g')), Action::None);
+
+        for _ in 0.."<summary>".len() {
+            app.handle(Key::Right);
+        }
+        type_text(&mut app, "Add logout");
+        assert!(app.draft_check().unwrap_err().starts_with("E_DRAFT_EMPTY"));
+        app.handle(Key::End);
+        app.handle(Key::Enter);
+        type_text(&mut app, "Added a logout button.");
+        assert_eq!(app.draft_check().unwrap().summary, "Add logout");
+        assert_eq!(app.handle(Key::Ctrl('g')), Action::Commit);
+
+        let (lines, cursor) = app.render(12, 100);
+        assert!(lines[10].contains("ready to commit"));
+        assert!(cursor.is_some());
+
+        assert_eq!(app.handle(Key::Esc), Action::SaveDraft);
+        assert_eq!(app.mode(), Mode::Browse);
+    }
+
+    #[test]
+    fn test_render_shows_status_and_selected_body() {
+        let mut app = app();
+        let lines = plain(app.render(12, 100).0);
+        assert_eq!(lines.len(), 12);
+        assert!(lines[0].contains("✓ chain verified (3 entries)  3 of 3 entries"));
+        assert!(lines[1].starts_with("✓      3 2025-06-13 Update README "));
+        assert!(lines[1].ends_with("│Update README"));
+        assert!(lines.iter().any(|line| line.ends_with("│Docs only.")));
+
+        app.handle(Key::Char('G'));
+        let lines = plain(app.render(12, 100).0);
+        assert!(lines.iter().any(|line| line.ends_with("│• Added the form")));
+        assert!(lines.iter().any(|line| line.ends_with("│Tags: auth")));
+    }
+
+    #[test]
+    fn test_wide_characters_keep_the_panes_aligned() {
+        assert_eq!(text_width("日本語 ok"), 9);
+        assert_eq!(text_width("🚀e\u{301}"), 3);
+
+        let painted = paint(&[(PLAIN, "日本語のテキスト")], 7, "");
+        let shown = painted.replace(PLAIN, "").replace(RESET, "");
+        assert_eq!(shown, "日本語…");
+        assert_eq!(text_width(&shown), 7);
+        assert_eq!(wrap("日本 語の テキスト", 6), ["日本", "語の", "テキスト"]);
+
+        let mut app = App::new(
+            vec![entry(1, "絵文字 🚀 の要約がとても長いエントリー", "", "x")],
+            &Ok(VerifyResult {
+                entry_count: 1,
+                first_entry: None,
+                latest_entry: None,
+            }),
+            "",
+        );
+        let lines = plain(app.render(6, 60).0);
+        let separator = |line: &String| text_width(&line[..line.find('│').unwrap()]);
+        assert_eq!(separator(&lines[1]), separator(&lines[2]));
+    }
+
+    #[test]
+    fn test_statuses_stop_at_the_broken_entry() {
+        let entries = vec![
+            entry(1, "a", "", "x"),
+            entry(2, "b", "", "x"),
+            entry(3, "c", "", "x"),
+        ];
+        let verify = Err(EngramError::Chain(VerifyError::MissingPreviousLine(
+            "000002_00000002.md".to_string(),
+        )));
+        assert_eq!(
+            statuses(&entries, &verify),
+            [
+                EntryStatus::Verified,
+                EntryStatus::Broken,
+                EntryStatus::Unchecked
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paint_fits_width() {
+        assert_eq!(paint(&[(PLAIN, "abcdef")], 4, PLAIN), "abc…\x1b[0m\x1b[0m");
+        assert_eq!(paint(&[(PLAIN, "ab")], 4, PLAIN), "ab\x1b[0m  \x1b[0m");
+        assert_eq!(wrap("one two three", 7), ["one two", "three"]);
+    }
+}
@@ -0,0 +1,206 @@
+//! A small multi-line text buffer for the draft pane
+
+/// Lines of text with a cursor. Columns count characters, not bytes.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+    /// First line shown in the pane
+    top: usize,
+    modified: bool,
+}
+
+impl Editor {
+    pub fn new(text: &str) -> Self {
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        Editor {
+            lines,
+            row: 0,
+            col: 0,
+            top: 0,
+            modified: false,
+        }
+    }
+
+    /// The buffer with a trailing newline, as drafts are stored
+    pub fn text(&self) -> String {
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Cursor as (row, column)
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.modified = false;
+    }
+
+    /// First visible line for a pane `height` lines tall, scrolled so the
+    /// cursor stays in view
+    pub fn scroll(&mut self, height: usize) -> usize {
+        let height = height.max(1);
+        if self.row < self.top {
+            self.top = self.row;
+        } else if self.row >= self.top + height {
+            self.top = self.row + 1 - height;
+        }
+        self.top
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = byte_index(&self.lines[self.row], self.col);
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.modified = true;
+    }
+
+    pub fn newline(&mut self) {
+        let at = byte_index(&self.lines[self.row], self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.row += 1;
+        self.lines.insert(self.row, rest);
+        self.col = 0;
+        self.modified = true;
+    }
+
+    /// Delete the character before the cursor, joining lines at column 0
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = byte_index(&self.lines[self.row], self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.lines[self.row].chars().count();
+            self.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    /// Delete the character under the cursor, joining the next line at the end
+    pub fn delete(&mut self) {
+        let len = self.lines[self.row].chars().count();
+        if self.col < len {
+            let at = byte_index(&self.lines[self.row], self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    pub fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if self.col < self.line_len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    pub fn up(&mut self, lines: usize) {
+        self.row = self.row.saturating_sub(lines);
+        self.col = self.col.min(self.line_len());
+    }
+
+    pub fn down(&mut self, lines: usize) {
+        self.row = (self.row + lines).min(self.lines.len() - 1);
+        self.col = self.col.min(self.line_len());
+    }
+
+    pub fn home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.col = self.line_len();
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+}
+
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices()
+        .nth(col)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing_round_trips_text() {
+        let mut editor = Editor::new("<summary></summary>\n\n## Intent\n");
+        editor.right();
+        for _ in 0..8 {
+            editor.right();
+        }
+        for c in "Fix é".chars() {
+            editor.insert(c);
+        }
+        assert_eq!(editor.lines()[0], "<summary>Fix é</summary>");
+
+        editor.down(2);
+        editor.end();
+        editor.newline();
+        editor.insert('x');
+        assert_eq!(editor.cursor(), (3, 1));
+        editor.backspace();
+        editor.backspace();
+        assert!(editor.is_modified());
+        assert_eq!(editor.text(), "<summary>Fix é</summary>\n\n## Intent\n");
+    }
+
+    #[test]
+    fn test_delete_joins_lines() {
+        let mut editor = Editor::new("ab\ncd\n");
+        editor.end();
+        editor.delete();
+        assert_eq!(editor.text(), "abcd\n");
+        editor.home();
+        editor.backspace();
+        assert_eq!(editor.text(), "abcd\n");
+    }
+
+    #[test]
+    fn test_scroll_follows_cursor() {
+        let mut editor = Editor::new(&"line\n".repeat(20));
+        editor.down(12);
+        assert_eq!(editor.scroll(5), 8);
+        editor.up(10);
+        assert_eq!(editor.scroll(5), 2);
+    }
+}
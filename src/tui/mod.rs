@@ -0,0 +1,66 @@
+//! Interactive terminal UI (`engram tui`): the entry list with verify status
+//! beside the selected entry, search-as-you-type, tag filters and a draft
+//! editor that validates as you type and can commit.
+//!
+//! Drawing uses plain ANSI escape sequences and raw mode comes from `stty`,
+//! so it works in any Unix terminal or SSH session and adds no dependency.
+
+mod app;
+mod editor;
+mod terminal;
+
+pub use app::{Action, App};
+
+use terminal::Terminal;
+
+use std::path::Path;
+
+use crate::error::EngramError;
+use crate::repository::Repository;
+use crate::templates::DRAFT_TEMPLATE;
+
+/// Run the TUI on the repository at `root` until the user quits. A modified
+/// draft is saved on the way out.
+pub fn run(root: &Path) -> Result<(), EngramError> {
+    let repo = Repository::open(root)?;
+    let draft = |repo: &Repository| {
+        repo.read_draft()
+            .unwrap_or_else(|_| DRAFT_TEMPLATE.to_string())
+    };
+    let mut app = App::new(repo.entries()?, &repo.verify(), &draft(&repo));
+    let mut terminal = Terminal::open()?;
+
+    loop {
+        let (rows, cols) = terminal.size();
+        let (lines, cursor) = app.render(rows, cols);
+        terminal.draw(&lines, cursor)?;
+
+        for key in terminal.read_keys()? {
+            match app.handle(key) {
+                Action::None => {}
+                Action::Quit => {
+                    if app.draft_modified() {
+                        repo.write_draft(&app.draft_text())?;
+                    }
+                    return Ok(());
+                }
+                Action::SaveDraft => {
+                    repo.write_draft(&app.draft_text())?;
+                    app.draft_saved();
+                }
+                Action::Reload => app.load(repo.entries()?, &repo.verify()),
+                Action::Commit => {
+                    repo.write_draft(&app.draft_text())?;
+                    app.draft_saved();
+                    match repo.commit() {
+                        Ok(result) => {
+                            app.load(repo.entries()?, &repo.verify());
+                            app.committed(&result.filename, &draft(&repo));
+                        }
+                        Err(error) => app.set_message(format!("✗ {}: {}", error.code(), error)),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Raw-mode terminal I/O with ANSI escape sequences. Raw mode is switched
+//! with `stty`, so the TUI runs on any Unix terminal, SSH sessions included,
+//! without a terminal library.
+
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
+use crate::error::EngramError;
+
+/// A key press decoded from the terminal's input bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    /// Ctrl plus a letter, lowercase
+    Ctrl(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+/// Turns terminal input into keys. A UTF-8 character or escape sequence
+/// cut off at the end of one read is held until the next read completes it.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    pending: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Key> {
+        let mut input = std::mem::take(&mut self.pending);
+        input.extend_from_slice(bytes);
+        let split = input.len() - incomplete_utf8(&input);
+        let text = String::from_utf8_lossy(&input[..split]);
+        let (keys, unfinished) = decode_text(&text);
+        if let Some(start) = unfinished {
+            self.pending.extend_from_slice(text[start..].as_bytes());
+        }
+        self.pending.extend_from_slice(&input[split..]);
+        keys
+    }
+}
+
+/// Length of an unfinished UTF-8 character at the end of `bytes`
+fn incomplete_utf8(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xc0 == 0x80 {
+            continue;
+        }
+        let needed = match byte {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+        return if needed > back { back } else { 0 };
+    }
+    0
+}
+
+/// Keys in `text`, and where an escape sequence that runs past its end
+/// starts. A lone ESC is the Escape key; the terminal sends a whole
+/// sequence at once, so only a read boundary can split one.
+fn decode_text(text: &str) -> (Vec<Key>, Option<usize>) {
+    let mut keys = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let key = match c {
+            '\x1b' => match chars.peek() {
+                Some((_, '[')) | Some((_, 'O')) => {
+                    chars.next();
+                    let mut sequence = String::new();
+                    let mut finished = false;
+                    while let Some(&(_, next)) = chars.peek() {
+                        chars.next();
+                        sequence.push(next);
+                        if next.is_ascii_alphabetic() || next == '~' {
+                            finished = true;
+                            break;
+                        }
+                    }
+                    if !finished {
+                        return (keys, Some(start));
+                    }
+                    match sequence.as_str() {
+                        "A" => Key::Up,
+                        "B" => Key::Down,
+                        "C" => Key::Right,
+                        "D" => Key::Left,
+                        "H" | "1~" | "7~" => Key::Home,
+                        "F" | "4~" | "8~" => Key::End,
+                        "3~" => Key::Delete,
+                        "5~" => Key::PageUp,
+                        "6~" => Key::PageDown,
+                        _ => continue,
+                    }
+                }
+                _ => Key::Esc,
+            },
+            '\r' | '\n' => Key::Enter,
+            '\t' => Key::Tab,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x01'..='\x1a' => Key::Ctrl((b'a' + c as u8 - 1) as char),
+            c if c.is_control() => continue,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    (keys, None)
+}
+
+/// The controlling terminal in raw mode on the alternate screen. Dropping it
+/// restores the previous terminal settings.
+pub struct Terminal {
+    saved: String,
+    decoder: Decoder,
+}
+
+impl Terminal {
+    pub fn open() -> Result<Self, EngramError> {
+        if !cfg!(unix) {
+            return Err(EngramError::Config(
+                "engram tui needs a Unix terminal; use an SSH session or WSL on Windows"
+                    .to_string(),
+            ));
+        }
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err(EngramError::Config(
+                "engram tui needs an interactive terminal".to_string(),
+            ));
+        }
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        let terminal = Terminal {
+            saved: saved.trim().to_string(),
+            decoder: Decoder::default(),
+        };
+        terminal.write("\x1b[?1049h\x1b[?25l")?;
+        Ok(terminal)
+    }
+
+    /// Rows and columns
+    pub fn size(&self) -> (usize, usize) {
+        stty(&["size"])
+            .ok()
+            .and_then(|size| {
+                let (rows, cols) = size.trim().split_once(' ')?;
+                Some((rows.parse().ok()?, cols.parse().ok()?))
+            })
+            .filter(|&(rows, cols)| rows > 0 && cols > 0)
+            .unwrap_or((24, 80))
+    }
+
+    /// Block until input arrives and return the keys it holds
+    pub fn read_keys(&mut self) -> io::Result<Vec<Key>> {
+        let mut buffer = [0u8; 64];
+        let read = io::stdin().read(&mut buffer)?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "terminal closed",
+            ));
+        }
+        Ok(self.decoder.feed(&buffer[..read]))
+    }
+
+    /// Draw a full frame. `cursor` is a (row, column) to show the cursor at.
+    pub fn draw(&self, lines: &[String], cursor: Option<(usize, usize)>) -> io::Result<()> {
+        let mut frame = String::from("\x1b[?25l");
+        for (row, line) in lines.iter().enumerate() {
+            frame.push_str(&format!("\x1b[{};1H\x1b[2K{}\x1b[0m", row + 1, line));
+        }
+        if let Some((row, col)) = cursor {
+            frame.push_str(&format!("\x1b[{};{}H\x1b[?25h", row + 1, col + 1));
+        }
+        self.write(&frame)
+    }
+
+    fn write(&self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.write("\x1b[?25h\x1b[?1049l");
+        let _ = stty(&[self.saved.as_str()]);
+    }
+}
+
+/// Run stty against the terminal on stdin
+fn stty(args: &[&str]) -> Result<String, EngramError> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .map_err(|err| EngramError::Config(format!("cannot run stty: {}", err)))?;
+    if !output.status.success() {
+        return Err(EngramError::Config(format!(
+            "stty failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &[u8]) -> Vec<Key> {
+        Decoder::default().feed(bytes)
+    }
+
+    #[test]
+    fn test_decode_keys() {
+        assert_eq!(decode(b"q"), [Key::Char('q')]);
+        assert_eq!(decode(b"\x1b"), [Key::Esc]);
+        assert_eq!(
+            decode(b"\x1b[A\x1b[6~\r"),
+            [Key::Up, Key::PageDown, Key::Enter]
+        );
+        assert_eq!(decode(b"\x1bOH\x7f"), [Key::Home, Key::Backspace]);
+        assert_eq!(decode(b"\x07\x13"), [Key::Ctrl('g'), Key::Ctrl('s')]);
+        assert_eq!(decode("é".as_bytes()), [Key::Char('é')]);
+    }
+
+    #[test]
+    fn test_input_split_across_reads() {
+        let mut decoder = Decoder::default();
+        let text = "aé€😀".as_bytes();
+        let mut keys = Vec::new();
+        for byte in text {
+            keys.extend(decoder.feed(&[*byte]));
+        }
+        assert_eq!(
+            keys,
+            [
+                Key::Char('a'),
+                Key::Char('é'),
+                Key::Char('€'),
+                Key::Char('😀')
+            ]
+        );
+
+        assert_eq!(decoder.feed(b"x\x1b[6"), [Key::Char('x')]);
+        assert_eq!(decoder.feed(b"~\x1b"), [Key::PageDown, Key::Esc]);
+        assert_eq!(decoder.feed(b"\xff"), [Key::Char('\u{fffd}')]);
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::env;
+
+use crate::error::EngramError;
+
+/// Environment variable used by reproducible builds to pin timestamps
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Source of the current time, injectable so tests and reproducible builds can pin it
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The real system clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that always returns the same instant
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Use `SOURCE_DATE_EPOCH` (seconds since the Unix epoch) when set, otherwise the system clock
+pub fn from_env() -> Result<Box<dyn Clock>, EngramError> {
+    match env::var(SOURCE_DATE_EPOCH) {
+        Ok(value) => parse_epoch(&value).map(|date| Box::new(FixedClock(date)) as Box<dyn Clock>),
+        Err(_) => Ok(Box::new(SystemClock)),
+    }
+}
+
+fn parse_epoch(value: &str) -> Result<DateTime<Utc>, EngramError> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        .ok_or_else(|| {
+            EngramError::Config(format!(
+                "{} must be a Unix timestamp, got '{}'",
+                SOURCE_DATE_EPOCH, value
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let date = Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap();
+        assert_eq!(FixedClock(date).now(), date);
+    }
+
+    #[test]
+    fn test_parse_epoch() {
+        let date = parse_epoch("1749738727").unwrap();
+        assert_eq!(date, Utc.with_ymd_and_hms(2025, 6, 12, 14, 32, 7).unwrap());
+        assert!(parse_epoch("yesterday").is_err());
+    }
+}
@@ -0,0 +1,114 @@
+/// Lines of unchanged context shown around each change
+const CONTEXT: usize = 3;
+
+/// Line-based unified diff of `old` and `new` labelled with `path`, or an
+/// empty string when they are equal
+///
+/// Uses a plain LCS table, which is fine for the instruction files and
+/// templates this is used on.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Walk the table into (marker, line, old position, new position) operations
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', a[i], i, j));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', a[i], i, j));
+            i += 1;
+        } else {
+            ops.push(('+', b[j], i, j));
+            j += 1;
+        }
+    }
+
+    // Group changed operations, with context, into hunks
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in ops.iter().enumerate().filter(|(_, op)| op.0 != ' ') {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_count = hunk.iter().filter(|op| op.0 != '-').count();
+        let (_, _, old_pos, new_pos) = hunk[0];
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_pos + usize::from(old_count > 0),
+            old_count,
+            new_pos + usize::from(new_count > 0),
+            new_count
+        ));
+        for (marker, line, _, _) in hunk {
+            diff.push(*marker);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_inputs_have_no_diff() {
+        assert_eq!(unified_diff("a.md", "x\ny\n", "x\ny\n"), "");
+    }
+
+    #[test]
+    fn test_replaced_line_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        assert_eq!(
+            unified_diff("n.txt", old, new),
+            "--- a/n.txt\n+++ b/n.txt\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_separate_hunks() {
+        let old: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        let new = old.replace("2\n", "two\n").replace("19\n", "nineteen\n");
+        let diff = unified_diff("n.txt", &old, &new);
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("-19\n+nineteen\n"));
+    }
+
+    #[test]
+    fn test_new_file() {
+        assert_eq!(
+            unified_diff("new.md", "", "a\nb\n"),
+            "--- a/new.md\n+++ b/new.md\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+    }
+}
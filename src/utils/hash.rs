@@ -1,15 +1,18 @@
 use sha2::{Digest, Sha256};
 
-/// Compute SHA256 hash of content and return as lowercase hex string
-pub fn sha256_hex(content: &str) -> String {
+/// Compute SHA256 hash of raw bytes and return as lowercase hex string
+///
+/// Entries are hashed exactly as stored on disk, so callers should pass the
+/// bytes read from the file rather than a decoded string where possible.
+pub fn sha256_hex(content: impl AsRef<[u8]>) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
+    hasher.update(content.as_ref());
     let result = hasher.finalize();
     format!("{:x}", result)
 }
 
 /// Compute SHA256 hash and return the first 8 characters (short hash)
-pub fn sha256_short(content: &str) -> String {
+pub fn sha256_short(content: impl AsRef<[u8]>) -> String {
     sha256_hex(content)[..8].to_string()
 }
 
@@ -43,4 +46,11 @@ mod tests {
             "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
         );
     }
+
+    #[test]
+    fn test_sha256_bytes_match_str() {
+        assert_eq!(sha256_hex(b"hello world"), sha256_hex("hello world"));
+        // Non-UTF-8 input must hash without error
+        assert_eq!(sha256_hex([0xff, 0xfe]).len(), 64);
+    }
 }
@@ -1 +1,4 @@
+pub mod clock;
+pub mod diff;
 pub mod hash;
+pub mod tar;
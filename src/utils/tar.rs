@@ -0,0 +1,240 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Size of a tar header and of the padding unit for file contents
+const BLOCK: usize = 512;
+
+/// Write `dir` (recursively, as `name/...`) to `out` as an uncompressed
+/// POSIX ustar archive. Entries are sorted so the layout is stable; paths
+/// listed in `skip` (relative to `dir`) are left out. Symlinks are stored as
+/// links and never followed.
+pub fn write_dir<W: Write>(out: &mut W, dir: &Path, name: &str, skip: &[&str]) -> io::Result<()> {
+    append_dir(out, dir, name, name, skip)?;
+    // End of archive: two zero blocks
+    out.write_all(&[0; BLOCK * 2])
+}
+
+fn append_dir<W: Write>(
+    out: &mut W,
+    dir: &Path,
+    root: &str,
+    name: &str,
+    skip: &[&str],
+) -> io::Result<()> {
+    write_header(out, &format!("{}/", name), &fs::metadata(dir)?, b'5', 0, "")?;
+
+    let mut children: Vec<_> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    children.sort_by_key(|child| child.file_name());
+    for child in children {
+        let child_name = format!("{}/{}", name, child.file_name().to_string_lossy());
+        let relative = &child_name[root.len() + 1..];
+        if skip.contains(&relative) {
+            continue;
+        }
+
+        let path = child.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            let target = fs::read_link(&path)?;
+            write_header(
+                out,
+                &child_name,
+                &metadata,
+                b'2',
+                0,
+                &target.to_string_lossy(),
+            )?;
+        } else if metadata.is_dir() {
+            append_dir(out, &path, root, &child_name, skip)?;
+        } else {
+            let content = fs::read(&path)?;
+            write_header(out, &child_name, &metadata, b'0', content.len() as u64, "")?;
+            out.write_all(&content)?;
+            let padding = (BLOCK - content.len() % BLOCK) % BLOCK;
+            out.write_all(&vec![0; padding])?;
+        }
+    }
+    Ok(())
+}
+
+fn write_header<W: Write>(
+    out: &mut W,
+    path: &str,
+    metadata: &fs::Metadata,
+    kind: u8,
+    size: u64,
+    link: &str,
+) -> io::Result<()> {
+    let mut header = [0u8; BLOCK];
+    if link.len() > 100 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("symlink target too long for a tar archive: {}", link),
+        ));
+    }
+    header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+
+    // Long paths are split into a prefix (up to 155 bytes) and a name (up to 100)
+    let (prefix, name) = split_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path too long for a tar archive: {}", path),
+        )
+    })?;
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    let mode = match kind {
+        b'5' => 0o755,
+        b'2' => 0o777,
+        _ => file_mode(metadata),
+    };
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs());
+
+    write_octal(&mut header[100..108], mode);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], size);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = kind;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with its own field set to spaces
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u64 = header.iter().map(|&byte| u64::from(byte)).sum();
+    write_octal(&mut header[148..155], checksum);
+    header[155] = b' ';
+
+    out.write_all(&header)
+}
+
+/// Split `path` into ustar (prefix, name) fields
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    if path.len() <= 100 {
+        return Some(("", path));
+    }
+    // Split at a '/' so that both halves fit
+    path.char_indices()
+        .filter(|&(index, c)| c == '/' && index <= 155 && path.len() - index - 1 <= 100)
+        .map(|(index, _)| (&path[..index], &path[index + 1..]))
+        .next()
+}
+
+/// Zero-padded octal digits followed by a NUL, filling `field`
+fn write_octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{:0width$o}", value, width = width);
+    field[..width].copy_from_slice(&digits.as_bytes()[digits.len() - width..]);
+    field[width] = 0;
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::PermissionsExt;
+    u64::from(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> u64 {
+    0o644
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// (path, typeflag, content) of each member, parsed back from `archive`
+    fn members(archive: &[u8]) -> Vec<(String, u8, Vec<u8>)> {
+        let mut members = Vec::new();
+        let mut offset = 0;
+        while archive[offset..offset + BLOCK]
+            .iter()
+            .any(|&byte| byte != 0)
+        {
+            let header = &archive[offset..offset + BLOCK];
+            let field = |range: std::ops::Range<usize>| {
+                String::from_utf8_lossy(&header[range])
+                    .trim_end_matches('\0')
+                    .to_string()
+            };
+            let name = field(0..100);
+            let size = u64::from_str_radix(field(124..135).trim(), 8).unwrap() as usize;
+
+            let stored: u64 = u64::from_str_radix(field(148..155).trim(), 8).unwrap();
+            let mut blank = header.to_vec();
+            blank[148..156].copy_from_slice(b"        ");
+            assert_eq!(stored, blank.iter().map(|&b| u64::from(b)).sum::<u64>());
+
+            offset += BLOCK;
+            members.push((name, header[156], archive[offset..offset + size].to_vec()));
+            offset += size.div_ceil(BLOCK) * BLOCK;
+        }
+        members
+    }
+
+    #[test]
+    fn test_write_dir() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join(".engram");
+        fs::create_dir_all(root.join("worklog")).unwrap();
+        fs::create_dir_all(root.join("bin")).unwrap();
+        fs::write(root.join("draft.md"), "draft").unwrap();
+        fs::write(root.join("worklog/SUMMARY.md"), "x".repeat(600)).unwrap();
+        fs::write(root.join("bin/engram"), "binary").unwrap();
+
+        let mut archive = Vec::new();
+        write_dir(&mut archive, &root, ".engram", &["bin"]).unwrap();
+
+        assert_eq!(archive.len() % BLOCK, 0);
+        let members = members(&archive);
+        let names: Vec<_> = members.iter().map(|m| m.0.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                ".engram/",
+                ".engram/draft.md",
+                ".engram/worklog/",
+                ".engram/worklog/SUMMARY.md"
+            ]
+        );
+        assert_eq!(members[1].1, b'0');
+        assert_eq!(members[1].2, b"draft");
+        assert_eq!(members[3].2.len(), 600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_dir_stores_symlinks_without_following() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join(".engram");
+        fs::create_dir_all(root.join("worklog")).unwrap();
+        // A loop that would recurse forever if followed
+        std::os::unix::fs::symlink("..", root.join("worklog/loop")).unwrap();
+
+        let mut archive = Vec::new();
+        write_dir(&mut archive, &root, ".engram", &[]).unwrap();
+
+        let members = members(&archive);
+        assert_eq!(members.len(), 3);
+        assert_eq!(members[2].0, ".engram/worklog/loop");
+        assert_eq!(members[2].1, b'2');
+        assert!(members[2].2.is_empty());
+        let header = &archive[2 * BLOCK..3 * BLOCK];
+        assert_eq!(&header[157..160], b"..\0");
+    }
+
+    #[test]
+    fn test_split_long_path() {
+        let path = format!("{}/{}", "d".repeat(120), "f".repeat(90));
+        assert_eq!(split_path(&path), Some((&path[..120], &path[121..])));
+        assert!(split_path(&"x".repeat(300)).is_none());
+    }
+}
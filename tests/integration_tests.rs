@@ -155,6 +155,13 @@ fn test_init_with_all_flag() {
     assert!(dir.path().join("CLAUDE.md").exists());
     assert!(dir.path().join(".junie/guidelines.md").exists());
     assert!(dir.path().join("AGENTS.md").exists());
+    assert!(dir.path().join(".cursor/rules/engram.mdc").exists());
+    assert!(dir.path().join(".github/copilot-instructions.md").exists());
+    assert!(dir.path().join(".windsurfrules").exists());
+    assert!(dir.path().join(".clinerules").exists());
+    assert!(dir.path().join("GEMINI.md").exists());
+    assert!(dir.path().join("CONVENTIONS.md").exists());
+    assert!(dir.path().join(".aider.conf.yml").exists());
 }
 
 #[test]
@@ -230,6 +237,57 @@ fn test_commit_requires_valid_draft() {
     // Error should mention draft is empty or invalid
 }
 
+#[test]
+fn test_errors_carry_stable_codes() {
+    let dir = tempdir().unwrap();
+
+    let output = run_engram(dir.path(), &["commit"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr_str(&output).contains("E_NOT_INITIALIZED"));
+
+    run_engram(dir.path(), &["init"]);
+    let output = run_engram(dir.path(), &["init"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr_str(&output).contains("E_ALREADY_INITIALIZED"));
+
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Work</summary>\n\n<!-- nothing yet -->",
+    )
+    .unwrap();
+    let output = run_engram(dir.path(), &["commit"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr_str(&output).contains("Error [E_DRAFT_EMPTY]"));
+}
+
+#[test]
+fn test_json_output_for_errors_and_results() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let output = run_engram(dir.path(), &["commit", "--json"]);
+    assert_eq!(output.status.code(), Some(4));
+    let error: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(error["error"]["code"], "E_DRAFT_SUMMARY_EMPTY");
+    assert_eq!(error["error"]["exit_code"], 4);
+
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Work</summary>\n\nDid things",
+    )
+    .unwrap();
+    let output = run_engram(dir.path(), &["--json", "commit"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let commit: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(commit["summary"], "Work");
+    assert_eq!(commit["previous"], "none");
+
+    let output = run_engram(dir.path(), &["verify", "--json"]);
+    let verify: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(verify["verified"], true);
+    assert_eq!(verify["entry_count"], 1);
+}
+
 #[test]
 fn test_commit_first_entry() {
     let dir = tempdir().unwrap();
@@ -345,6 +403,34 @@ Tests pass"#;
     assert!(!entry_content.contains("Previous: none"));
 }
 
+#[test]
+fn test_commit_honours_source_date_epoch() {
+    let dir = tempdir().unwrap();
+
+    run_engram(dir.path(), &["init"]);
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Pinned date</summary>\n\n## Intent\nReproducible build",
+    )
+    .unwrap();
+
+    let output = Command::new(engram_bin())
+        .current_dir(dir.path())
+        .env("SOURCE_DATE_EPOCH", "1749738727")
+        .arg("commit")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr_str(&output));
+
+    let entry = fs::read_dir(dir.path().join(".engram/worklog"))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().starts_with("000001_"))
+        .unwrap();
+    let content = fs::read_to_string(entry.path()).unwrap();
+    assert!(content.contains("Date: 2025-06-12T14:32:07Z"));
+}
+
 // =============================================================================
 // VERIFY COMMAND TESTS
 // =============================================================================
@@ -463,6 +549,105 @@ Pass"#;
     assert!(stderr.contains("✗") || stderr.contains("mismatch") || stderr.contains("Hash"));
 }
 
+#[test]
+fn test_verify_detects_deleted_middle_entry() {
+    let dir = tempdir().unwrap();
+
+    run_engram(dir.path(), &["init"]);
+    for summary in ["First", "Second", "Third"] {
+        let draft = format!("<summary>{}</summary>\n\n## Intent\nTest", summary);
+        fs::write(dir.path().join(".engram/draft.md"), draft).unwrap();
+        assert!(run_engram(dir.path(), &["commit"]).status.success());
+    }
+
+    let history_dir = dir.path().join(".engram/worklog");
+    let second = fs::read_dir(&history_dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().starts_with("000002_"))
+        .map(|e| e.path())
+        .unwrap();
+    fs::remove_file(second).unwrap();
+
+    let output = run_engram(dir.path(), &["verify"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = stderr_str(&output);
+    assert!(stderr.contains("Sequence gap at 000003_"), "{}", stderr);
+    assert!(stderr.contains("Expected sequence: 000002"));
+}
+
+#[test]
+fn test_verify_reports_crlf_and_doctor_fixes_it() {
+    let dir = tempdir().unwrap();
+
+    run_engram(dir.path(), &["init"]);
+
+    let draft = r#"<summary>Test entry</summary>
+
+## Intent
+Test
+
+## Changes
+- Test
+
+## Verification
+Pass"#;
+    fs::write(dir.path().join(".engram/draft.md"), draft).unwrap();
+    run_engram(dir.path(), &["commit"]);
+
+    // Simulate a checkout that converted the entry to CRLF
+    let history_dir = dir.path().join(".engram/worklog");
+    let entry: std::path::PathBuf = fs::read_dir(&history_dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().starts_with("000001_"))
+        .map(|e| e.path())
+        .unwrap();
+    let original = fs::read_to_string(&entry).unwrap();
+    fs::write(&entry, original.replace('\n', "\r\n")).unwrap();
+
+    let output = run_engram(dir.path(), &["verify"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = stderr_str(&output);
+    assert!(stderr.contains("differs only by CRLF line endings"));
+    assert!(stderr.contains("engram doctor --fix-eol"));
+
+    // Without --fix-eol the doctor only reports
+    let output = run_engram(dir.path(), &["doctor"]);
+    assert_eq!(output.status.code(), Some(12)); // EXIT_PROBLEMS_FOUND
+    assert!(stdout_str(&output).contains("--fix-eol"));
+
+    let output = run_engram(dir.path(), &["doctor", "--fix-eol"]);
+    assert!(output.status.success(), "doctor --fix-eol failed");
+    assert_eq!(fs::read_to_string(&entry).unwrap(), original);
+
+    let output = run_engram(dir.path(), &["verify"]);
+    assert!(output.status.success(), "verify after fix failed");
+}
+
+#[test]
+fn test_doctor_fix_repairs_installation() {
+    let dir = tempdir().unwrap();
+
+    run_engram(dir.path(), &["init"]);
+    fs::remove_file(dir.path().join(".engram/.gitignore")).unwrap();
+    fs::remove_file(dir.path().join(".engram/draft.md")).unwrap();
+
+    let output = run_engram(dir.path(), &["doctor"]);
+    assert_eq!(output.status.code(), Some(12)); // EXIT_PROBLEMS_FOUND
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains(".engram/.gitignore: missing"));
+    assert!(stdout.contains("engram doctor --fix"));
+
+    let output = run_engram(dir.path(), &["doctor", "--fix"]);
+    assert!(output.status.success(), "doctor --fix failed");
+    assert!(dir.path().join(".engram/.gitignore").exists());
+    assert!(dir.path().join(".engram/draft.md").exists());
+
+    let output = run_engram(dir.path(), &["doctor"]);
+    assert!(output.status.success(), "doctor after fix failed");
+}
+
 // =============================================================================
 // STATUS COMMAND TESTS
 // =============================================================================
@@ -677,3 +862,951 @@ All tests pass"#;
     assert!(output.status.success(), "final verify failed");
     assert!(stdout_str(&output).contains("2 entries"));
 }
+
+// =============================================================================
+// MCP SERVER TESTS
+// =============================================================================
+
+#[test]
+fn test_mcp_server_over_stdio() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"write_draft","arguments":{"content":"<summary>Via MCP</summary>\n\nBody"}}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"commit","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"verify","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"resources/list"}"#,
+    ];
+
+    let mut child = Command::new(engram_bin())
+        .current_dir(dir.path())
+        .arg("mcp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        for request in requests {
+            writeln!(stdin, "{}", request).unwrap();
+        }
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = stdout_str(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // The notification gets no response
+    assert_eq!(responses.len(), 5);
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "engram");
+    assert_eq!(responses[2]["result"]["isError"], false);
+    assert!(responses[3]["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("\"entry_count\": 1"));
+    assert_eq!(
+        responses[4]["result"]["resources"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let output = run_engram(dir.path(), &["verify"]);
+    assert!(output.status.success());
+}
+
+// =============================================================================
+// SERVE COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_serve_binds_localhost_and_answers_api() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let mut child = Command::new(engram_bin())
+        .current_dir(dir.path())
+        .args(["serve", "--port", "0"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut banner = String::new();
+    BufReader::new(child.stdout.as_mut().unwrap())
+        .read_line(&mut banner)
+        .unwrap();
+    let address = banner
+        .split("http://")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap()
+        .to_string();
+    assert!(address.starts_with("127.0.0.1:"), "{}", banner);
+
+    let mut stream = TcpStream::connect(&address).unwrap();
+    write!(
+        stream,
+        "GET /api/verify HTTP/1.1\r\nHost: {}\r\n\r\n",
+        address
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("\"verified\": true"));
+}
+
+#[test]
+fn test_serve_requires_init() {
+    let dir = tempdir().unwrap();
+    let output = run_engram(dir.path(), &["serve", "--port", "0"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// EXPORT COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_export_html_site() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Publish me</summary>\n\n## Changes\n- Exported",
+    )
+    .unwrap();
+    assert!(run_engram(dir.path(), &["commit"]).status.success());
+
+    let output = run_engram(dir.path(), &["export", "html", "--out", "site"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert!(stdout_str(&output).contains("✓ Chain verified: 1 entries"));
+
+    let index = fs::read_to_string(dir.path().join("site/index.html")).unwrap();
+    assert!(index.contains("Publish me"));
+    assert!(dir.path().join("site/chain.html").exists());
+    assert!(dir.path().join("site/search-index.json").exists());
+    let pages = fs::read_dir(dir.path().join("site/entries"))
+        .unwrap()
+        .count();
+    assert_eq!(pages, 1);
+}
+
+#[test]
+fn test_export_data_formats_and_range() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    for summary in ["One", "Two", "Three"] {
+        fs::write(
+            dir.path().join(".engram/draft.md"),
+            format!(
+                "<summary>{}</summary>\n\n## Changes\n- {}",
+                summary, summary
+            ),
+        )
+        .unwrap();
+        assert!(run_engram(dir.path(), &["commit"]).status.success());
+    }
+
+    let output = run_engram(dir.path(), &["export"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert_eq!(stdout_str(&output).lines().count(), 3);
+
+    let output = run_engram(dir.path(), &["export", "--format", "json", "--from", "2"]);
+    let document: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(document["entries"][0]["summary"], "Two");
+    assert_eq!(document["range"]["from"], "2");
+
+    let output = run_engram(dir.path(), &["export", "--format", "csv", "--to", "1"]);
+    let csv = stdout_str(&output);
+    assert!(csv.starts_with("sequence,filename,hash,"));
+    assert!(csv.contains("\r\n1,000001_"));
+    assert!(!csv.contains("\r\n2,000002_"));
+
+    let output = run_engram(dir.path(), &["export", "--from", "last week"]);
+    assert!(!output.status.success());
+}
+
+// =============================================================================
+// IMPORT COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_import_round_trips_export() {
+    let source = tempdir().unwrap();
+    run_engram(source.path(), &["init"]);
+    fs::write(
+        source.path().join(".engram/draft.md"),
+        "<summary>Portable</summary>\n\n## Changes\n- Moved",
+    )
+    .unwrap();
+    assert!(run_engram(source.path(), &["commit"]).status.success());
+    let export = run_engram(source.path(), &["export"]);
+    let file = source.path().join("worklog.jsonl");
+    fs::write(&file, &export.stdout).unwrap();
+
+    let target = tempdir().unwrap();
+    run_engram(target.path(), &["init"]);
+    let output = run_engram(target.path(), &["import", file.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert!(stdout_str(&output).contains("✓ Imported 1 entries"));
+    assert!(run_engram(target.path(), &["verify"]).status.success());
+
+    // A second worklog with its own history cannot take the same entries unchanged
+    let other = tempdir().unwrap();
+    run_engram(other.path(), &["init"]);
+    fs::write(
+        other.path().join(".engram/draft.md"),
+        "<summary>Local</summary>\n\n## Changes\n- Local",
+    )
+    .unwrap();
+    run_engram(other.path(), &["commit"]);
+    let output = run_engram(other.path(), &["import", file.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(10));
+    assert!(stderr_str(&output).contains("E_IMPORT_REJECTED"));
+
+    let output = run_engram(
+        other.path(),
+        &["import", "--rechain", file.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let entries = fs::read_dir(other.path().join(".engram/worklog"))
+        .unwrap()
+        .count();
+    assert_eq!(entries, 3); // two entries + SUMMARY.md
+}
+
+// =============================================================================
+// CHANGELOG COMMAND TESTS
+// =============================================================================
+
+/// Run git with a throwaway identity
+fn run_git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("Failed to execute git");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+#[test]
+fn test_changelog_since_git_tag() {
+    let dir = tempdir().unwrap();
+    run_git(dir.path(), &["init", "-q"]);
+    run_engram(dir.path(), &["init"]);
+    let commit = |summary: &str| {
+        fs::write(
+            dir.path().join(".engram/draft.md"),
+            format!("<summary>{}</summary>\n\n## Changes\n- Work", summary),
+        )
+        .unwrap();
+        assert!(run_engram(dir.path(), &["commit"]).status.success());
+    };
+
+    commit("feat: first release");
+    run_git(dir.path(), &["add", "-A"]);
+    run_git(dir.path(), &["commit", "-qm", "Release"]);
+    run_git(dir.path(), &["tag", "v1.0.0"]);
+    // A tag that reads like a sequence number still names the revision
+    run_git(dir.path(), &["tag", "2"]);
+    commit("fix: after the release");
+
+    for tag in ["v1.0.0", "2"] {
+        let output = run_engram(dir.path(), &["changelog", "--from-rev", tag]);
+        assert!(output.status.success(), "{}", stderr_str(&output));
+        let stdout = stdout_str(&output);
+        assert!(stdout.contains("## [Unreleased]\n\n### Fixed\n\n- after the release"));
+        assert!(!stdout.contains("first release"));
+    }
+
+    // Entry bounds are inclusive on both sides
+    let output = run_engram(dir.path(), &["changelog", "--from", "1", "--to", "1"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains("- first release"));
+    assert!(!stdout.contains("after the release"));
+
+    let output = run_engram(
+        dir.path(),
+        &["changelog", "--from", "1", "--from-rev", "v1.0.0"],
+    );
+    assert!(!output.status.success());
+
+    let output = run_engram(
+        dir.path(),
+        &[
+            "changelog",
+            "--to-rev",
+            "v1.0.0",
+            "--version",
+            "1.0.0",
+            "--append",
+            "CHANGELOG.md",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let changelog = fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("### Added\n\n- first release"));
+}
+
+#[test]
+fn test_changelog_uses_type_header() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Token expiry off by one</summary>\nType: fix\n\n## Changes\n- Fixed",
+    )
+    .unwrap();
+    assert!(run_engram(dir.path(), &["commit"]).status.success());
+
+    let output = run_engram(dir.path(), &["changelog"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert!(stdout_str(&output).contains("### Fixed\n\n- Token expiry off by one"));
+}
+
+// =============================================================================
+// IMPORT-GIT COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_import_git_creates_verifiable_chain() {
+    let dir = tempdir().unwrap();
+    run_git(dir.path(), &["init", "-q"]);
+    for name in ["one", "two"] {
+        fs::write(dir.path().join(name), name).unwrap();
+        run_git(dir.path(), &["add", name]);
+        run_git(dir.path(), &["commit", "-qm", &format!("Add {}", name)]);
+    }
+    run_engram(dir.path(), &["init"]);
+
+    let output = run_engram(dir.path(), &["import-git", "--since", "HEAD~1"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert!(stdout_str(&output).contains("as 1 entries"));
+
+    let output = run_engram(dir.path(), &["import-git"]);
+    assert!(stdout_str(&output).contains("Skipped 1 commits"));
+    assert!(run_engram(dir.path(), &["verify"]).status.success());
+
+    let summary = fs::read_to_string(dir.path().join(".engram/worklog/SUMMARY.md")).unwrap();
+    let add_two = summary.find("| Add two |").unwrap();
+    let add_one = summary.find("| Add one |").unwrap();
+    assert!(add_two < add_one);
+}
+
+// =============================================================================
+// TAGS COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_tags_recorded_and_listed() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Fix token expiry</summary>\nTags: auth, bugfix\n\n## Changes\n- Fixed",
+    )
+    .unwrap();
+    assert!(run_engram(dir.path(), &["commit"]).status.success());
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "---\ntags: [auth]\n---\n<summary>Add login</summary>\n\n## Changes\n- Added",
+    )
+    .unwrap();
+    assert!(run_engram(dir.path(), &["commit"]).status.success());
+
+    let stdout = stdout_str(&run_engram(dir.path(), &["tags"]));
+    assert!(stdout.contains("    2  auth"));
+    assert!(stdout.contains("    1  bugfix"));
+
+    let stdout = stdout_str(&run_engram(dir.path(), &["tags", "bugfix"]));
+    assert!(stdout.contains("Fix token expiry"));
+    assert!(!stdout.contains("Add login"));
+
+    let summary = fs::read_to_string(dir.path().join(".engram/worklog/SUMMARY.md")).unwrap();
+    assert!(summary.contains("| Fix token expiry `auth` `bugfix` |"));
+
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Bad</summary>\nTags: not valid!\n\n## Changes\n- x",
+    )
+    .unwrap();
+    let output = run_engram(dir.path(), &["commit"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr_str(&output).contains("E_DRAFT_INVALID_TAG"));
+}
+
+// =============================================================================
+// REFS COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_refs_backlinks() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    let commit = |draft: String| {
+        fs::write(dir.path().join(".engram/draft.md"), draft).unwrap();
+        run_engram(dir.path(), &["commit"])
+    };
+    assert!(
+        commit("<summary>Add login</summary>\n\n## Changes\n- Added".to_string())
+            .status
+            .success()
+    );
+
+    let export = stdout_str(&run_engram(dir.path(), &["export"]));
+    let first: serde_json::Value = serde_json::from_str(export.lines().next().unwrap()).unwrap();
+    let hash = first["hash"].as_str().unwrap();
+
+    let output = commit(format!(
+        "<summary>Revert login</summary>\nRefs: {}, #123\n\n## Changes\n- Reverted",
+        hash
+    ));
+    assert!(output.status.success(), "{}", stderr_str(&output));
+
+    let stdout = stdout_str(&run_engram(dir.path(), &["refs", "1"]));
+    assert!(stdout.contains("Referenced by: 000002"));
+    let stdout = stdout_str(&run_engram(dir.path(), &["refs", "2"]));
+    assert!(stdout.contains("Refs:          000001_"));
+    assert!(stdout.contains("#123"));
+
+    let output = commit(format!(
+        "<summary>Bad ref</summary>\nRefs: {}\n\n## Changes\n- x",
+        "e".repeat(64)
+    ));
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr_str(&output).contains("E_DRAFT_UNKNOWN_REF"));
+}
+
+#[test]
+fn test_init_detects_more_agent_tools() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join(".cursor")).unwrap();
+    fs::write(dir.path().join(".windsurfrules"), "Prefer small diffs.\n").unwrap();
+    fs::write(dir.path().join(".aider.conf.yml"), "read: [NOTES.md]\n").unwrap();
+
+    let output = run_engram(dir.path(), &["init"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+
+    let rule = fs::read_to_string(dir.path().join(".cursor/rules/engram.mdc")).unwrap();
+    assert!(rule.starts_with("---\n"));
+    assert!(rule.contains("alwaysApply: true"));
+    assert!(rule.contains("Engram Protocol"));
+    let windsurf = fs::read_to_string(dir.path().join(".windsurfrules")).unwrap();
+    assert!(windsurf.contains("Engram Protocol"));
+    assert!(windsurf.contains("Prefer small diffs."));
+    assert!(dir.path().join("CONVENTIONS.md").exists());
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".aider.conf.yml")).unwrap(),
+        "read: [NOTES.md, CONVENTIONS.md]\n"
+    );
+    assert!(stdout_str(&output).contains("Updated: .aider.conf.yml"));
+
+    assert!(!dir.path().join("AGENTS.md").exists());
+    assert!(!dir.path().join("GEMINI.md").exists());
+}
+
+#[test]
+fn test_init_copilot_and_gemini_flags() {
+    let dir = tempdir().unwrap();
+
+    let output = run_engram(dir.path(), &["init", "--copilot", "--gemini"]);
+
+    assert!(output.status.success());
+    let copilot = fs::read_to_string(dir.path().join(".github/copilot-instructions.md")).unwrap();
+    assert!(copilot.starts_with("# Copilot Instructions"));
+    assert!(copilot.contains("Engram Protocol"));
+    assert!(dir.path().join("GEMINI.md").exists());
+    assert!(!dir.path().join("AGENTS.md").exists());
+}
+
+// =============================================================================
+// SYNC-DIRECTIVES COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_sync_directives_dry_run_then_apply() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init", "--warp"]);
+    let warp_path = dir.path().join("WARP.md");
+    let current = fs::read_to_string(&warp_path).unwrap();
+    assert!(current.contains("<!-- engram:begin v"));
+
+    // Simulate a block written by an older release
+    let begin = current.find("<!-- engram:begin").unwrap();
+    let end = current.find("<!-- engram:end -->").unwrap();
+    let stale = format!(
+        "{}<!-- engram:begin v0.0.1 -->\n## 🔒 Engram Protocol\nOld text.\n{}",
+        &current[..begin],
+        &current[end..]
+    );
+    fs::write(&warp_path, &stale).unwrap();
+
+    let output = run_engram(dir.path(), &["sync-directives", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains("--- a/WARP.md"));
+    assert!(stdout.contains("-Old text."));
+    assert!(stdout.contains("1 file(s) would change"));
+    assert_eq!(fs::read_to_string(&warp_path).unwrap(), stale);
+
+    let output = run_engram(dir.path(), &["sync-directives"]);
+    assert!(output.status.success());
+    assert!(stdout_str(&output).contains("Updated: WARP.md"));
+    assert_eq!(fs::read_to_string(&warp_path).unwrap(), current);
+
+    let output = run_engram(dir.path(), &["sync-directives", "--dry-run"]);
+    assert!(stdout_str(&output).contains("All directives are up to date."));
+}
+
+// =============================================================================
+// UPGRADE COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_upgrade_renames_legacy_entries_and_records_it() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    assert!(dir.path().join(".engram/VERSION").exists());
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>First</summary>\n\n## Changes\n- x",
+    )
+    .unwrap();
+    assert!(run_engram(dir.path(), &["commit"]).status.success());
+
+    let output = run_engram(dir.path(), &["upgrade"]);
+    assert!(output.status.success());
+    assert!(stdout_str(&output).contains("✓ Legacy entry filenames: nothing to do"));
+
+    // Simulate an installation that predates 6-digit sequences
+    let worklog = dir.path().join(".engram/worklog");
+    let entry = fs::read_dir(&worklog)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .find(|name| name.starts_with("000001_"))
+        .unwrap();
+    fs::rename(worklog.join(&entry), worklog.join(&entry[3..])).unwrap();
+    fs::remove_file(dir.path().join(".engram/VERSION")).unwrap();
+    assert_eq!(run_engram(dir.path(), &["verify"]).status.code(), Some(1));
+
+    let output = run_engram(dir.path(), &["upgrade"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains(&format!("Renamed {} to {}", &entry[3..], entry)));
+    assert!(stdout.contains("Recorded the upgrade in 000002_"));
+
+    assert!(run_engram(dir.path(), &["verify"]).status.success());
+    let json = stdout_str(&run_engram(dir.path(), &["upgrade", "--json"]));
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(report["entry"].is_null());
+}
+
+#[test]
+fn test_init_repair_and_force() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    fs::remove_file(dir.path().join(".engram/.gitattributes")).unwrap();
+
+    let output = run_engram(dir.path(), &["init"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = run_engram(dir.path(), &["init", "--repair"]);
+    assert!(output.status.success());
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains("Created: .engram/.gitattributes"));
+    assert!(stdout.contains("Skipped: .engram/draft.md (already exists)"));
+
+    fs::write(dir.path().join(".engram/AGENTS.md"), "stale\n").unwrap();
+    let output = run_engram(dir.path(), &["init", "--force"]);
+    assert!(output.status.success());
+    assert!(stdout_str(&output).contains("Updated: .engram/AGENTS.md"));
+
+    let output = run_engram(dir.path(), &["init", "--repair", "--force"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_deinit_dry_run_then_archive() {
+    let dir = tempdir().unwrap();
+    let original = "# Claude\n\nUse tabs.\n";
+    fs::write(dir.path().join("CLAUDE.md"), original).unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let output = run_engram(dir.path(), &["deinit", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = stdout_str(&output);
+    assert!(stdout.contains("Removed directive: CLAUDE.md"));
+    assert!(stdout.contains("Deleted: .engram/"));
+    assert!(stdout.contains("Dry run: nothing was changed"));
+    assert!(dir.path().join(".engram").exists());
+
+    let output = run_engram(dir.path(), &["deinit"]);
+    assert_eq!(output.status.code(), Some(7));
+    assert!(stderr_str(&output).contains("--yes"));
+    assert!(dir.path().join("engram").exists());
+
+    let output = run_engram(dir.path(), &["deinit", "--archive", "nodir/worklog.tar"]);
+    assert_eq!(output.status.code(), Some(8));
+    assert_ne!(
+        fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap(),
+        original
+    );
+    assert!(dir.path().join("engram").exists());
+
+    let output = run_engram(dir.path(), &["deinit", "--archive", "worklog.tar"]);
+    assert!(output.status.success());
+    assert!(stdout_str(&output).contains("Archived: .engram/ -> worklog.tar"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap(),
+        original
+    );
+    assert!(dir.path().join("worklog.tar").exists());
+    assert!(!dir.path().join(".engram").exists());
+    assert!(!dir.path().join("engram").exists());
+
+    let output = run_engram(dir.path(), &["deinit"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+/// Run the generated `./engram` wrapper in `dir`
+#[cfg(unix)]
+fn run_wrapper(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(dir.join("engram"))
+        .current_dir(dir)
+        .args(args)
+        .env_remove("ENGRAM_MIRROR")
+        .output()
+        .expect("Failed to execute wrapper")
+}
+
+#[cfg(unix)]
+#[test]
+fn test_local_wrapper_runs_vendored_binary() {
+    let dir = tempdir().unwrap();
+    let output = run_engram(dir.path(), &["init", "--wrapper-mode", "local"]);
+    assert!(output.status.success());
+    let wrapper = fs::read_to_string(dir.path().join("engram")).unwrap();
+    assert!(wrapper.contains("# Wrapper mode: local"));
+    assert!(!wrapper.contains("github.com"));
+
+    let output = run_wrapper(dir.path(), &["status"]);
+    assert!(!output.status.success());
+    assert!(stderr_str(&output).contains("engram vendor"));
+
+    let output = run_engram(dir.path(), &["vendor"]);
+    assert!(output.status.success());
+    let version = env!("CARGO_PKG_VERSION");
+    assert!(stdout_str(&output).contains(&format!(".engram/bin/engram-v{}", version)));
+
+    let output = run_wrapper(dir.path(), &["--version"]);
+    assert!(output.status.success());
+    assert!(stdout_str(&output).contains(version));
+
+    // A binary swapped in after vendoring is refused
+    let vendored = dir.path().join(format!(".engram/bin/engram-v{}", version));
+    fs::write(&vendored, "#!/bin/sh\necho swapped\n").unwrap();
+    let output = run_wrapper(dir.path(), &["--version"]);
+    assert!(!output.status.success());
+    assert!(!stdout_str(&output).contains("swapped"));
+    assert!(stderr_str(&output).contains("checksum mismatch"));
+
+    // --force keeps the installed mode
+    let output = run_engram(dir.path(), &["init", "--force"]);
+    assert!(output.status.success());
+    let wrapper = fs::read_to_string(dir.path().join("engram")).unwrap();
+    assert!(wrapper.contains("# Wrapper mode: local"));
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[test]
+fn test_mirror_wrapper_checks_pinned_checksum() {
+    let dir = tempdir().unwrap();
+    let mirror = tempdir().unwrap();
+    let version = env!("CARGO_PKG_VERSION");
+    let release = mirror.path().join(format!("v{}", version));
+    fs::create_dir_all(&release).unwrap();
+    fs::copy(engram_bin(), release.join("engram-linux-x86_64")).unwrap();
+    let output = Command::new("sha256sum")
+        .arg("engram-linux-x86_64")
+        .current_dir(&release)
+        .output()
+        .unwrap();
+    fs::write(release.join("checksums.txt"), &output.stdout).unwrap();
+
+    let mirror_url = format!("file://{}", mirror.path().display());
+    let output = run_engram(
+        dir.path(),
+        &["init", "--wrapper-mode", "mirror", "--mirror", &mirror_url],
+    );
+    assert!(output.status.success(), "{}", stderr_str(&output));
+
+    let output = run_wrapper(dir.path(), &["--version"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert!(stdout_str(&output).contains(version));
+
+    // A tampered asset is refused
+    fs::remove_dir_all(dir.path().join(".engram/bin")).unwrap();
+    fs::write(
+        release.join("engram-linux-x86_64"),
+        "#!/bin/sh\necho pwned\n",
+    )
+    .unwrap();
+    let output = run_wrapper(dir.path(), &["--version"]);
+    assert!(!output.status.success());
+    assert!(stderr_str(&output).contains("checksum mismatch"));
+}
+
+#[test]
+fn test_init_mirror_mode_needs_checksums() {
+    let dir = tempdir().unwrap();
+    let output = run_engram(
+        dir.path(),
+        &[
+            "init",
+            "--wrapper-mode",
+            "mirror",
+            "--mirror",
+            "https://mirror.example/engram",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(7));
+    assert!(!dir.path().join(".engram").exists());
+}
+
+#[test]
+fn test_pin_moves_wrappers_to_another_release() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let output = run_engram(dir.path(), &["pin", "9.8.7"]);
+    assert!(output.status.success());
+    assert!(stdout_str(&output).contains("Pinned: engram (was"));
+    assert!(fs::read_to_string(dir.path().join("engram"))
+        .unwrap()
+        .contains("ENGRAM_VERSION=\"9.8.7\""));
+    assert!(fs::read_to_string(dir.path().join("engram.cmd"))
+        .unwrap()
+        .contains("set ENGRAM_VERSION=9.8.7"));
+
+    let cmd = dir.path().join("engram.cmd");
+    let edited = fs::read_to_string(&cmd).unwrap() + "rem local tweak\n";
+    fs::write(&cmd, &edited).unwrap();
+    let output = run_engram(dir.path(), &["pin", "9.8.8"]);
+    assert_eq!(output.status.code(), Some(7));
+    assert!(stdout_str(&output).contains("Skipped: engram.cmd"));
+    assert_eq!(fs::read_to_string(&cmd).unwrap(), edited);
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[test]
+fn test_self_update_from_local_release_directory() {
+    let dir = tempdir().unwrap();
+    let exe = dir.path().join("engram");
+    fs::copy(engram_bin(), &exe).unwrap();
+    let release = dir.path().join("releases/v99.0.0");
+    fs::create_dir_all(&release).unwrap();
+    fs::write(
+        release.join("engram-linux-x86_64"),
+        "#!/bin/sh\necho engram 99.0.0\n",
+    )
+    .unwrap();
+    let output = Command::new("sha256sum")
+        .arg("engram-linux-x86_64")
+        .current_dir(&release)
+        .output()
+        .unwrap();
+    fs::write(release.join("checksums.txt"), &output.stdout).unwrap();
+
+    let output = Command::new(&exe)
+        .current_dir(dir.path())
+        .args(["self-update", "--from", "releases"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    assert!(stdout_str(&output).contains("→ 99.0.0"));
+
+    let output = Command::new(&exe).arg("--version").output().unwrap();
+    assert_eq!(stdout_str(&output), "engram 99.0.0\n");
+}
+
+#[test]
+fn test_bash_completion_offers_subcommands_and_entries() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Add login form</summary>\n\n## Changes\nAdded the form.\n",
+    )
+    .unwrap();
+    run_engram(dir.path(), &["commit"]);
+
+    let output = run_engram(dir.path(), &["__complete-entries"]);
+    let stdout = stdout_str(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "1\tAdd login form");
+    assert!(lines[1].ends_with("\t#1 Add login form"));
+
+    let output = run_engram(dir.path(), &["completions", "bash"]);
+    assert!(output.status.success());
+    fs::write(dir.path().join("engram.bash"), stdout_str(&output)).unwrap();
+    // complete -F passes the command, the current word and the previous word
+    let script = "source engram.bash\n\
+         COMP_WORDS=(\"$ENGRAM\" s); COMP_CWORD=1; _engram_with_entries \"$ENGRAM\" s \"$ENGRAM\"; echo \"${COMPREPLY[*]}\"\n\
+         COMP_WORDS=(\"$ENGRAM\" refs ''); COMP_CWORD=2; _engram_with_entries \"$ENGRAM\" '' refs; echo \"${COMPREPLY[*]}\"";
+    let output = Command::new("bash")
+        .args(["-c", script])
+        .env("ENGRAM", engram_bin())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let stdout = stdout_str(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "status serve sync-directives self-update");
+    // The sequence number, then the entry's 8-character hash prefix
+    assert!(lines[1].starts_with("1 "));
+    assert_eq!(lines[1].len(), "1 ".len() + 8);
+}
+
+#[test]
+fn test_completions_for_every_shell() {
+    let dir = tempdir().unwrap();
+    for (shell, marker, entries) in [
+        ("zsh", "#compdef engram", true),
+        ("fish", "complete -c engram", true),
+        ("powershell", "Register-ArgumentCompleter", false),
+        ("elvish", "edit:completion:arg-completer[engram]", false),
+    ] {
+        let output = run_engram(dir.path(), &["completions", shell]);
+        assert!(output.status.success());
+        let stdout = stdout_str(&output);
+        assert!(stdout.contains(marker), "{}", shell);
+        assert_eq!(stdout.contains("__complete-entries"), entries, "{}", shell);
+    }
+
+    // Outside a repository the entry list is empty rather than an error
+    let output = run_engram(dir.path(), &["__complete-entries"]);
+    assert!(output.status.success());
+    assert_eq!(stdout_str(&output), "");
+}
+
+#[test]
+fn test_man_writes_a_page_per_subcommand() {
+    let dir = tempdir().unwrap();
+
+    let output = run_engram(dir.path(), &["man", "--out", "man1"]);
+    assert!(output.status.success());
+    let man = dir.path().join("man1");
+    for page in [
+        "engram.1",
+        "engram-init.1",
+        "engram-export-html.1",
+        "engram-man.1",
+    ] {
+        assert!(man.join(page).exists(), "{}", page);
+    }
+    assert!(!man.join("engram-__complete-entries.1").exists());
+    let refs = fs::read_to_string(man.join("engram-refs.1")).unwrap();
+    assert!(refs.contains(".TH engram-refs 1  \"engram "));
+    assert!(refs.contains("\\fBengram refs\\fR"));
+    assert!(refs.contains("<\\fIENTRY\\fR>"));
+    assert!(refs.contains("EXAMPLES:"));
+}
+
+#[test]
+fn test_tui_needs_a_terminal() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let output = run_engram(dir.path(), &["tui"]);
+    assert_eq!(output.status.code(), Some(7));
+    assert!(stderr_str(&output).contains("E_CONFIG"));
+    assert!(stderr_str(&output).contains("terminal"));
+}
+
+#[test]
+fn test_draft_subcommands_build_a_committable_report() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+
+    let output = run_engram(dir.path(), &["draft", "validate"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr_str(&output).contains("Error [E_DRAFT_SUMMARY_EMPTY]"));
+
+    let output = run_engram(dir.path(), &["draft", "set-summary", "Add token refresh"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    run_engram(
+        dir.path(),
+        &["draft", "append", "--section", "Changes", "- src/auth.rs"],
+    );
+    run_engram(
+        dir.path(),
+        &["draft", "append", "--section", "verification", "cargo test"],
+    );
+
+    let shown = stdout_str(&run_engram(dir.path(), &["draft", "show"]));
+    assert!(shown.starts_with("<summary>Add token refresh</summary>\n\n## Intent\n<!--"));
+    assert!(shown.contains("modified -->\n- src/auth.rs\n\n## Verification\n"));
+    assert!(shown.ends_with("validate this change? -->\ncargo test\n"));
+
+    let output = run_engram(dir.path(), &["draft", "validate", "--json"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let draft: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(draft["summary"], "Add token refresh");
+
+    let output = run_engram(dir.path(), &["commit"]);
+    assert!(output.status.success(), "{}", stderr_str(&output));
+    let entry = fs::read_dir(dir.path().join(".engram/worklog"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.file_name().unwrap() != "SUMMARY.md")
+        .unwrap();
+    assert!(fs::read_to_string(entry).unwrap().contains("- src/auth.rs"));
+}
+
+#[test]
+fn test_draft_validate_reports_errors_like_commit() {
+    let dir = tempdir().unwrap();
+    run_engram(dir.path(), &["init"]);
+    fs::write(
+        dir.path().join(".engram/draft.md"),
+        "<summary>Work</summary>\nRefs: abcd1234\n\n## Changes\n- x\n",
+    )
+    .unwrap();
+
+    let validate = run_engram(dir.path(), &["draft", "validate", "--json"]);
+    let commit = run_engram(dir.path(), &["commit", "--json"]);
+    assert_eq!(validate.status.code(), Some(4));
+    assert_eq!(validate.status.code(), commit.status.code());
+    assert_eq!(validate.stdout, commit.stdout);
+    assert!(stdout_str(&validate).contains("E_DRAFT_INVALID_REF"));
+
+    let output = run_engram(dir.path(), &["draft", "clear"]);
+    assert!(output.status.success());
+    assert!(fs::read_to_string(dir.path().join(".engram/draft.md"))
+        .unwrap()
+        .starts_with("<summary></summary>\n\n## Intent\n"));
+    let output = run_engram(dir.path(), &["draft", "validate"]);
+    assert!(stderr_str(&output).contains("E_DRAFT_SUMMARY_EMPTY"));
+}